@@ -3,7 +3,8 @@ use proc_macro::TokenStream;
 use quote::{quote, quote_spanned};
 use syn::{
     parse::Parse, punctuated::Punctuated, spanned::Spanned, token::Comma, Data, DataEnum,
-    DataStruct, DeriveInput, Error, Expr, ExprLit, Ident, Lit, Meta, MetaList, Result,
+    DataStruct, DeriveInput, Error, Expr, ExprLit, Ident, Lit, LitStr, Member, Meta, MetaList,
+    Result,
 };
 
 // FIXME: https://github.com/rust-lang/rust/issues/117463
@@ -80,6 +81,8 @@ use syn::{
 ///     ("Rust files", "*.rs Cargo.*"),
 ///     // The name is optional
 ///     (_, "*.md"),
+///     // A pattern without a name can also be written on its own
+///     "*.json",
 ///     // MIME types
 ///     "text/plain",
 ///     // Mime types with wildcards
@@ -89,6 +92,36 @@ use syn::{
 /// # }
 /// ```
 ///
+/// # Nesting
+///
+/// A struct that derives `Gui` can be used as a field of another struct that
+/// derives `Gui` by marking the field with `#[nested]`. Its settings are then
+/// registered in place of the field. If the field has a doc comment, the
+/// nested settings are registered under a title with that description, whose
+/// heading level can be specified with `#[heading_level]`. By default the
+/// nested settings use their own keys. The keys can be prefixed to avoid
+/// collisions by specifying a prefix instead of `#[nested]`:
+///
+/// ```no_run
+/// #[derive(Gui)]
+/// struct BossSplits {
+///     /// First Boss
+///     first: bool,
+///     /// Second Boss
+///     second: bool,
+/// }
+///
+/// #[derive(Gui)]
+/// struct Settings {
+///     /// Boss Splits
+///     #[prefix = "boss_splits_"]
+///     boss_splits: BossSplits,
+/// }
+/// ```
+///
+/// With the `alloc` feature enabled, registering the settings panics if more
+/// than one setting uses the same key.
+///
 /// # Choices
 ///
 /// You can derive `Gui` for an enum to create a choice widget. You can mark one
@@ -107,6 +140,10 @@ use syn::{
 /// }
 /// ```
 ///
+/// The rest of the doc comment of a variant is used as its tooltip. The
+/// tooltips of all the variants are shown together as the tooltip of the choice
+/// widget, unless the field that uses the choice has a tooltip of its own.
+///
 /// You can then use it as a widget like so:
 ///
 /// ```no_run
@@ -119,6 +156,10 @@ use syn::{
 ///
 /// # Tracking changes
 ///
+/// Calling `update_returns_changed` instead of `update` reports whether any of
+/// the settings changed, which allows skipping work on ticks where the user
+/// didn't change anything.
+///
 /// You can track changes to a setting by wrapping the widget type in a `Pair`.
 /// It acts like the widget by itself, but also keeps track of the previous
 /// value when you call `update` on the struct.
@@ -132,7 +173,7 @@ use syn::{
 ///     use_game_time: Pair<bool>,
 /// }
 /// ```
-#[proc_macro_derive(Gui, attributes(default, heading_level, filter))]
+#[proc_macro_derive(Gui, attributes(default, heading_level, filter, nested, prefix))]
 pub fn settings_macro(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
 
@@ -153,16 +194,12 @@ pub fn settings_macro(input: TokenStream) -> TokenStream {
 
 fn generate_struct_settings(struct_name: Ident, struct_data: DataStruct) -> Result<TokenStream> {
     let mut field_names = Vec::new();
-    let mut field_name_strings = Vec::new();
-    let mut field_descs = Vec::new();
-    let mut field_tooltips = Vec::new();
-    let mut field_tys = Vec::new();
-    let mut args_init = Vec::new();
+    let mut field_registers = Vec::new();
+    let mut field_updates = Vec::new();
     for field in struct_data.fields {
         let ident = field.ident.clone().unwrap();
         let ident_name = ident.to_string();
-        field_names.push(ident);
-        field_tys.push(field.ty);
+        let ty = field.ty;
         let mut doc_string = String::new();
         let mut tooltip_string = String::new();
         let mut is_in_tooltip = false;
@@ -202,17 +239,63 @@ fn generate_struct_settings(struct_name: Ident, struct_data: DataStruct) -> Resu
             }
             target_string.push_str(&value);
         }
+        let has_doc_comment = !doc_string.is_empty();
         if doc_string.is_empty() {
             doc_string = ident_name.to_title_case();
         }
 
-        field_descs.push(doc_string);
-        field_tooltips.push(if tooltip_string.is_empty() {
+        let tooltip = if tooltip_string.is_empty() {
             quote! {}
         } else {
-            quote! { asr::settings::gui::set_tooltip(#ident_name, #tooltip_string); }
+            quote! { asr::settings::gui::set_tooltip(&key, #tooltip_string); }
+        };
+
+        let nested_prefix = field.attrs.iter().find_map(|x| match &x.meta {
+            Meta::Path(path) if path.is_ident("nested") => Some(quote! { "" }),
+            Meta::NameValue(nv) if nv.path.is_ident("prefix") => {
+                let value = &nv.value;
+                Some(quote! { #value })
+            }
+            _ => None,
         });
-        field_name_strings.push(ident_name);
+
+        if let Some(nested_prefix) = nested_prefix {
+            // Nested settings are registered under a title if the field has a
+            // doc comment.
+            let title = if has_doc_comment {
+                let heading_level = field
+                    .attrs
+                    .iter()
+                    .find_map(|x| match &x.meta {
+                        Meta::NameValue(nv) if nv.path.is_ident("heading_level") => {
+                            let value = &nv.value;
+                            Some(quote! { #value })
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| quote! { 0 });
+                quote! {
+                    let key = asr::settings::gui::prefixed_key(prefix, #ident_name);
+                    keys.insert(&key);
+                    asr::settings::gui::add_title(&key, #doc_string, #heading_level);
+                    #tooltip
+                }
+            } else {
+                quote! {}
+            };
+
+            field_registers.push(quote! {
+                #title
+                let prefix = asr::settings::gui::prefixed_key(prefix, #nested_prefix);
+                <#ty as asr::settings::gui::NestedGui>::register_nested(&prefix, keys)
+            });
+            field_updates.push(quote! {
+                let prefix = asr::settings::gui::prefixed_key(prefix, #nested_prefix);
+                changed |= asr::settings::gui::NestedGui::update_nested(&mut self.#ident, settings_map, &prefix);
+            });
+            field_names.push(ident);
+            continue;
+        }
 
         let args = field
             .attrs
@@ -240,33 +323,60 @@ fn generate_struct_settings(struct_name: Ident, struct_data: DataStruct) -> Resu
                 _ => None,
             })
             .collect::<Result<Vec<_>>>()?;
-        args_init.push(quote! { #(#args)* });
+
+        field_registers.push(quote! {
+            let key = asr::settings::gui::prefixed_key(prefix, #ident_name);
+            keys.insert(&key);
+            let mut args = <#ty as asr::settings::gui::Widget>::Args::default();
+            #(#args)*
+            let mut value = asr::settings::gui::Widget::register(&key, #doc_string, args);
+            #tooltip
+            value
+        });
+        field_updates.push(quote! {
+            let key = asr::settings::gui::prefixed_key(prefix, #ident_name);
+            let mut args = <#ty as asr::settings::gui::Widget>::Args::default();
+            #(#args)*
+            changed |= asr::settings::gui::Widget::update_from_returns_changed(&mut self.#ident, settings_map, &key, args);
+        });
+        field_names.push(ident);
     }
 
     Ok(quote! {
         impl asr::settings::Gui for #struct_name {
             fn register() -> Self {
+                let mut keys = asr::settings::gui::KeyRegistry::default();
+                asr::settings::gui::NestedGui::register_nested("", &mut keys)
+            }
+
+            fn update_from(&mut self, settings_map: &asr::settings::Map) {
+                asr::settings::gui::NestedGui::update_nested(self, settings_map, "");
+            }
+
+            fn update(&mut self) {
+                self.update_from(&asr::settings::Map::load());
+            }
+
+            fn update_from_returns_changed(&mut self, settings_map: &asr::settings::Map) -> bool {
+                asr::settings::gui::NestedGui::update_nested(self, settings_map, "")
+            }
+        }
+
+        impl asr::settings::gui::NestedGui for #struct_name {
+            fn register_nested(prefix: &str, keys: &mut asr::settings::gui::KeyRegistry) -> Self {
                 Self {
                     #(#field_names: {
-                        let mut args = <#field_tys as asr::settings::gui::Widget>::Args::default();
-                        #args_init
-                        let mut value = asr::settings::gui::Widget::register(#field_name_strings, #field_descs, args);
-                        #field_tooltips
-                        value
+                        #field_registers
                     },)*
                 }
             }
 
-            fn update_from(&mut self, settings_map: &asr::settings::Map) {
+            fn update_nested(&mut self, settings_map: &asr::settings::Map, prefix: &str) -> bool {
+                let mut changed = false;
                 #({
-                    let mut args = <#field_tys as asr::settings::gui::Widget>::Args::default();
-                    #args_init
-                    asr::settings::gui::Widget::update_from(&mut self.#field_names, settings_map, #field_name_strings, args);
+                    #field_updates
                 })*
-            }
-
-            fn update(&mut self) {
-                self.update_from(&asr::settings::Map::load());
+                changed
             }
         }
     }
@@ -277,6 +387,7 @@ fn generate_enum_settings(enum_name: Ident, enum_data: DataEnum) -> Result<Token
     let mut variant_names = Vec::new();
     let mut variant_name_strings = Vec::new();
     let mut variant_descs = Vec::new();
+    let mut variant_tooltips = Vec::new();
     let mut default_index = None;
     for (index, variant) in enum_data.variants.into_iter().enumerate() {
         let ident = variant.ident.clone();
@@ -325,6 +436,9 @@ fn generate_enum_settings(enum_name: Ident, enum_data: DataEnum) -> Result<Token
             doc_string = ident_name.to_title_case();
         }
 
+        if !tooltip_string.is_empty() {
+            variant_tooltips.push(format!("{doc_string}: {tooltip_string}"));
+        }
         variant_descs.push(doc_string);
         variant_name_strings.push(ident_name);
 
@@ -348,6 +462,15 @@ fn generate_enum_settings(enum_name: Ident, enum_data: DataEnum) -> Result<Token
     let default_option = &variant_names[default_index];
     let default_option_key = &variant_name_strings[default_index];
 
+    // The runtime doesn't support tooltips for individual options, so the
+    // tooltips of all the variants are combined into the tooltip of the choice.
+    let tooltip = if variant_tooltips.is_empty() {
+        quote! {}
+    } else {
+        let tooltip = variant_tooltips.join("\n");
+        quote! { asr::settings::gui::set_tooltip(key, #tooltip); }
+    };
+
     let longest_string = variant_name_strings
         .iter()
         .map(|x| x.len())
@@ -365,6 +488,7 @@ fn generate_enum_settings(enum_name: Ident, enum_data: DataEnum) -> Result<Token
                 #(if asr::settings::gui::add_choice_option(key, #variant_name_strings, #variant_descs) {
                     v = Self::#variant_names;
                 })*
+                #tooltip
                 v
             }
 
@@ -379,6 +503,13 @@ fn generate_enum_settings(enum_name: Ident, enum_data: DataEnum) -> Result<Token
                     _ => Self::#default_option,
                 };
             }
+
+            #[inline]
+            fn update_from_returns_changed(&mut self, settings_map: &asr::settings::Map, key: &str, args: Self::Args) -> bool {
+                let old = core::mem::discriminant(self);
+                asr::settings::gui::Widget::update_from(self, settings_map, key, args);
+                core::mem::discriminant(self) != old
+            }
         }
     }
     .into())
@@ -445,30 +576,7 @@ fn parse_filter(list: &MetaList) -> Result<proc_macro2::TokenStream> {
                 match second {
                     Expr::Lit(ExprLit {
                         lit: Lit::Str(lit), ..
-                    }) => {
-                        let value = lit.value();
-                        if value.is_empty() {
-                            return Err(Error::new(lit.span(), "The pattern must not be empty."));
-                        }
-                        if value.trim().len() != value.len() {
-                            return Err(Error::new(
-                                lit.span(),
-                                "The pattern must not contain leading or trailing whitespace.",
-                            ));
-                        }
-                        if value.contains("  ") {
-                            return Err(Error::new(
-                                lit.span(),
-                                "The pattern must not contain double whitespace.",
-                            ));
-                        }
-                        if value.contains("*.*") {
-                            return Err(Error::new(
-                                lit.span(),
-                                "The pattern handling all files doesn't need to be specified.",
-                            ));
-                        }
-                    }
+                    }) => validate_name_pattern(lit)?,
                     _ => return Err(Error::new(second.span(), "Expected a string literal.")),
                 }
 
@@ -483,6 +591,15 @@ fn parse_filter(list: &MetaList) -> Result<proc_macro2::TokenStream> {
                     lit: Lit::Str(lit), ..
                 } => {
                     let value = lit.value();
+                    // File name patterns can't contain a `/`, so a string
+                    // without one is a pattern without a description.
+                    if !value.contains('/') {
+                        validate_name_pattern(&lit)?;
+                        filters.push(
+                            quote! { asr::settings::gui::FileSelectFilter::NamePattern(None, #lit) },
+                        );
+                        continue;
+                    }
                     if value.bytes().filter(|b| *b == b'/').count() != 1 {
                         return Err(Error::new(
                             lit.span(),
@@ -517,6 +634,32 @@ fn parse_filter(list: &MetaList) -> Result<proc_macro2::TokenStream> {
     Ok(quote_spanned! { span => args.filter = &[#(#filters),*]; })
 }
 
+fn validate_name_pattern(lit: &LitStr) -> Result<()> {
+    let value = lit.value();
+    if value.is_empty() {
+        return Err(Error::new(lit.span(), "The pattern must not be empty."));
+    }
+    if value.trim().len() != value.len() {
+        return Err(Error::new(
+            lit.span(),
+            "The pattern must not contain leading or trailing whitespace.",
+        ));
+    }
+    if value.contains("  ") {
+        return Err(Error::new(
+            lit.span(),
+            "The pattern must not contain double whitespace.",
+        ));
+    }
+    if value.contains("*.*") {
+        return Err(Error::new(
+            lit.span(),
+            "The pattern handling all files doesn't need to be specified.",
+        ));
+    }
+    Ok(())
+}
+
 /// Generates an implementation of the `FromEndian` trait for a struct. This
 /// allows converting values from a given endianness to the host's endianness.
 ///
@@ -559,23 +702,30 @@ pub fn from_endian_macro(input: TokenStream) -> TokenStream {
 
     let struct_name = ast.ident;
 
-    let mut field_names = Vec::new();
-    for field in struct_data.fields {
-        field_names.push(field.ident);
-    }
+    // Tuple structs are supported as well, as `Self { 0: ... }` is valid
+    // syntax for them.
+    let field_names = struct_data
+        .fields
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| match field.ident {
+            Some(ident) => Member::Named(ident),
+            None => Member::Unnamed(index.into()),
+        })
+        .collect::<Vec<_>>();
 
     quote! {
-        impl asr::primitives::dynamic_endian::FromEndian for #struct_name {
+        impl asr::FromEndian for #struct_name {
             fn from_be(&self) -> Self {
                 Self {
-                    #(#field_names: asr::primitives::dynamic_endian::FromEndian::from_be(
+                    #(#field_names: asr::FromEndian::from_be(
                         &self.#field_names,
                     ),)*
                 }
             }
             fn from_le(&self) -> Self {
                 Self {
-                    #(#field_names: asr::primitives::dynamic_endian::FromEndian::from_le(
+                    #(#field_names: asr::FromEndian::from_le(
                         &self.#field_names,
                     ),)*
                 }
@@ -0,0 +1,162 @@
+//! Support for extracting individual bits from values, such as flags that are
+//! packed into a single byte.
+
+use core::{fmt, ops::Range};
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::FromEndian;
+
+#[doc(hidden)]
+pub use bytemuck as __bytemuck;
+
+/// An integer whose individual bits can be accessed. This can be read directly
+/// from a process's memory. It is also a good fit for a
+/// [`Watcher`](crate::watcher::Watcher), as it can be compared and copied.
+///
+/// # Example
+///
+/// ```
+/// # use asr::bitfield::Bits;
+/// let flags = Bits(0b1011_0100u8);
+/// assert!(flags.bit(2));
+/// assert!(!flags.bit(3));
+/// assert_eq!(flags.bits(4..8), 0b1011);
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Bits<T>(pub T);
+
+macro_rules! define {
+    ($ty:ty) => {
+        impl Bits<$ty> {
+            /// Checks whether the bit at the index given is set. The least
+            /// significant bit has the index 0.
+            ///
+            /// # Panics
+            ///
+            /// This panics if the index is out of bounds for the integer.
+            #[inline]
+            pub const fn bit(self, index: u32) -> bool {
+                assert!(index < <$ty>::BITS, "The bit index is out of bounds.");
+                (self.0 >> index) & 1 != 0
+            }
+
+            /// Returns the bits in the range given, shifted down so that the
+            /// first bit of the range is the least significant bit.
+            ///
+            /// # Panics
+            ///
+            /// This panics if the range is out of bounds for the integer or
+            /// its start is after its end.
+            #[inline]
+            pub const fn bits(self, range: Range<u32>) -> $ty {
+                assert!(
+                    range.start <= range.end && range.end <= <$ty>::BITS,
+                    "The bit range is out of bounds."
+                );
+                let len = range.end - range.start;
+                if len == 0 {
+                    return 0;
+                }
+                (self.0 >> range.start) & (<$ty>::MAX >> (<$ty>::BITS - len))
+            }
+        }
+
+        impl fmt::Debug for Bits<$ty> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "Bits({:#0width$b})",
+                    self.0,
+                    width = <$ty>::BITS as usize + 2
+                )
+            }
+        }
+
+        impl FromEndian for Bits<$ty> {
+            fn from_be(&self) -> Self {
+                Self(self.0.from_be())
+            }
+            fn from_le(&self) -> Self {
+                Self(self.0.from_le())
+            }
+        }
+
+        // SAFETY: The type is transparent over an integer, which is `Pod`.
+        unsafe impl Pod for Bits<$ty> {}
+        // SAFETY: The type is transparent over an integer, which is `Zeroable`.
+        unsafe impl Zeroable for Bits<$ty> {}
+    };
+}
+
+define!(u8);
+define!(u16);
+define!(u32);
+define!(u64);
+
+/// Defines a struct that wraps an integer and provides named accessors for its
+/// individual bits. The struct can be read directly from a process's memory,
+/// compared and used with a [`Watcher`](crate::watcher::Watcher). The
+/// underlying integer can be accessed as the `0` field. Each accessor is
+/// declared with the index of its bit, where the least significant bit has the
+/// index 0.
+///
+/// # Example
+///
+/// ```
+/// asr::bitflags_struct! {
+///     /// The abilities the player has unlocked.
+///     pub struct Abilities: u8 {
+///         /// Whether the player can double jump.
+///         has_double_jump = 0,
+///         /// Whether the player can dash.
+///         has_dash = 3,
+///     }
+/// }
+///
+/// let abilities = Abilities(0b1001);
+/// assert!(abilities.has_double_jump());
+/// assert!(abilities.has_dash());
+/// ```
+#[macro_export]
+macro_rules! bitflags_struct {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident: $ty:ty {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident = $bit:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        $vis struct $name(pub $ty);
+
+        impl $name {
+            $(
+                $(#[$field_attr])*
+                #[inline]
+                pub const fn $field(self) -> bool {
+                    $crate::bitfield::Bits(self.0).bit($bit)
+                }
+            )*
+        }
+
+        impl $crate::FromEndian for $name {
+            fn from_be(&self) -> Self {
+                Self($crate::FromEndian::from_be(&self.0))
+            }
+            fn from_le(&self) -> Self {
+                Self($crate::FromEndian::from_le(&self.0))
+            }
+        }
+
+        // SAFETY: The type is transparent over an integer, which is `Pod`.
+        unsafe impl $crate::bitfield::__bytemuck::Pod for $name {}
+        // SAFETY: The type is transparent over an integer, which is `Zeroable`.
+        unsafe impl $crate::bitfield::__bytemuck::Zeroable for $name {}
+    };
+}
@@ -1,10 +1,192 @@
 //! Support for storing pointer paths for easy dereferencing inside the autosplitter logic.
 
 use core::array;
+#[cfg(feature = "alloc")]
+use core::cell::Cell;
 
 use bytemuck::CheckedBitPattern;
 
-use crate::{Address, Error, PointerSize, Process};
+use crate::{memory_reader::MemoryReader, Address, Error, PointerSize};
+
+/// A single step of a pointer path. The offset is added to the current address
+/// and if a pointer size is specified, the pointer at the resulting address is
+/// read to get the next address. This allows a pointer path to mix pointer
+/// sizes, for example when a 64-bit process stores 32-bit pointers.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PathStep {
+    /// The offset that is added to the current address.
+    pub offset: u64,
+    /// The size of the pointer to read at the resulting address. If this is
+    /// [`None`], the resulting address is used as is, which is usually the
+    /// case for the last step.
+    pub deref: Option<PointerSize>,
+}
+
+impl PathStep {
+    /// Creates a step that adds the offset and then reads a pointer of the
+    /// size given.
+    pub const fn deref(offset: u64, pointer_size: PointerSize) -> Self {
+        Self {
+            offset,
+            deref: Some(pointer_size),
+        }
+    }
+
+    /// Creates a step that only adds the offset.
+    pub const fn offset(offset: u64) -> Self {
+        Self {
+            offset,
+            deref: None,
+        }
+    }
+}
+
+/// An error that occurred while following a pointer path. It describes which
+/// step of the path failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointerPathError {
+    /// The index of the step that failed. If reading the final value failed,
+    /// this is the number of steps.
+    pub step: usize,
+    /// The address that failed to be read.
+    pub address: Address,
+}
+
+impl From<PointerPathError> for Error {
+    fn from(_: PointerPathError) -> Self {
+        Error {}
+    }
+}
+
+/// The address that a pointer path starts at.
+#[derive(Copy, Clone)]
+enum Base {
+    Address(Address),
+    Module(&'static str, u64),
+}
+
+/// The address of a module along with the handle of the process that it was
+/// looked up in.
+type ModuleCache = Option<(u64, Address)>;
+
+impl Base {
+    /// Returns the address that the pointer path starts at and whether the
+    /// address of the module was taken from the cache. The cache is only used
+    /// if it belongs to the same process, and is updated after a lookup.
+    fn address(
+        self,
+        process: &impl MemoryReader,
+        cache: &mut ModuleCache,
+    ) -> Result<(Address, bool), Error> {
+        match self {
+            Base::Address(address) => Ok((address, false)),
+            Base::Module(name, offset) => {
+                let process = process.process();
+                let handle = process.handle();
+                if let Some((cached_handle, module_address)) = *cache {
+                    if cached_handle == handle {
+                        return Ok((module_address + offset, true));
+                    }
+                }
+                let module_address = process.get_module_address(name)?;
+                *cache = Some((handle, module_address));
+                Ok((module_address + offset, false))
+            }
+        }
+    }
+
+    /// Follows the pointer path from the address that it starts at. If that
+    /// fails while the address of the module was taken from the cache, the
+    /// module is looked up again, in case the game reloaded it, and the
+    /// pointer path is followed once more.
+    fn follow<T, E>(
+        self,
+        process: &impl MemoryReader,
+        cache: &mut ModuleCache,
+        map_err: impl Fn(Error) -> E,
+        follow: impl Fn(Address) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let (address, is_cached) = self.address(process, cache).map_err(&map_err)?;
+        match follow(address) {
+            Err(_) if is_cached => {
+                *cache = None;
+                follow(self.address(process, cache).map_err(map_err)?.0)
+            }
+            result => result,
+        }
+    }
+}
+
+/// The base of a pointer path along with the cached address of the module
+/// that it starts at.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+struct Start {
+    base: Base,
+    module_address: Cell<ModuleCache>,
+}
+
+#[cfg(feature = "alloc")]
+impl Start {
+    const fn new(base: Base) -> Self {
+        Self {
+            base,
+            module_address: Cell::new(None),
+        }
+    }
+
+    fn follow<T, E>(
+        &self,
+        process: &impl MemoryReader,
+        map_err: impl Fn(Error) -> E,
+        follow: impl Fn(Address) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut cache = self.module_address.get();
+        let result = self.base.follow(process, &mut cache, map_err, follow);
+        self.module_address.set(cache);
+        result
+    }
+}
+
+fn deref_offsets(
+    process: &impl MemoryReader,
+    mut address: Address,
+    pointer_size: PointerSize,
+    path: &[u64],
+) -> Result<Address, Error> {
+    let (&last, path) = path.split_last().ok_or(Error {})?;
+    for &offset in path {
+        address = process.read_pointer(address + offset, pointer_size)?;
+    }
+    Ok(address + last)
+}
+
+fn resolve(
+    process: &impl MemoryReader,
+    mut address: Address,
+    pointer_size: PointerSize,
+    path: &[u64],
+) -> Result<Address, PointerPathError> {
+    let Some((&last, path)) = path.split_last() else {
+        return Err(PointerPathError { step: 0, address });
+    };
+    for (step, &offset) in path.iter().enumerate() {
+        address = address + offset;
+        address = process
+            .read_pointer(address, pointer_size)
+            .map_err(|_| PointerPathError { step, address })?;
+    }
+    Ok(address + last)
+}
+
+/// The error that is reported by [`DeepPointer::resolve`] if the module that
+/// the pointer path starts at isn't loaded.
+const fn module_not_found(_: Error) -> PointerPathError {
+    PointerPathError {
+        step: 0,
+        address: Address::NULL,
+    }
+}
 
 /// An abstraction of a pointer path, usable for easy dereferencing inside an autosplitter logic.
 ///
@@ -12,10 +194,12 @@ use crate::{Address, Error, PointerSize, Process};
 ///
 /// `CAP` should be higher or equal to the number of offsets provided in `path`.
 /// If a higher number of offsets is provided, the pointer path will be truncated
-/// according to the value of `CAP`.
+/// according to the value of `CAP`. Pointer paths of unbounded length can be
+/// followed with [`UnboundedDeepPointer`] or [`MemoryReader::read_path`]
+/// instead.
 #[derive(Copy, Clone)]
 pub struct DeepPointer<const CAP: usize> {
-    base_address: Address,
+    base: Base,
     path: [u64; CAP],
     depth: usize,
     pointer_size: PointerSize,
@@ -26,7 +210,7 @@ impl<const CAP: usize> Default for DeepPointer<CAP> {
     #[inline]
     fn default() -> Self {
         Self {
-            base_address: Address::default(),
+            base: Base::Address(Address::default()),
             path: [u64::default(); CAP],
             depth: usize::default(),
             pointer_size: PointerSize::Bit64,
@@ -44,7 +228,7 @@ impl<const CAP: usize> DeepPointer<CAP> {
         };
 
         Self {
-            base_address: base_address.into(),
+            base: Base::Address(base_address.into()),
             path: this_path,
             depth: path.len().min(CAP),
             pointer_size,
@@ -61,22 +245,351 @@ impl<const CAP: usize> DeepPointer<CAP> {
         Self::new(base_address, PointerSize::Bit64, path)
     }
 
-    /// Dereferences the pointer path, returning the memory address of the value of interest
-    pub fn deref_offsets(&self, process: &Process) -> Result<Address, Error> {
-        let mut address = self.base_address;
-        let (&last, path) = self.path[..self.depth].split_last().ok_or(Error {})?;
-        for &offset in path {
-            address = process.read_pointer(address + offset, self.pointer_size)?;
+    /// Creates a new DeepPointer that starts at an offset into the module with
+    /// the given name. The address of the module is looked up whenever the
+    /// pointer path is followed, so the pointer path stays valid if the game
+    /// unloads and reloads the module. A [`CachedDeepPointer`] or an
+    /// [`UnboundedDeepPointer`] only look it up again when following the
+    /// pointer path fails or the process changes.
+    pub fn new_module_relative(
+        module_name: &'static str,
+        base_offset: u64,
+        pointer_size: PointerSize,
+        path: &[u64],
+    ) -> Self {
+        let mut this = Self::new(Address::NULL, pointer_size, path);
+        this.base = Base::Module(module_name, base_offset);
+        this
+    }
+
+    /// Creates a builder for a DeepPointer, which allows describing long
+    /// pointer paths step by step.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::deep_pointer::DeepPointer;
+    /// let pointer = DeepPointer::<4>::builder()
+    ///     .module("UnityPlayer.dll")
+    ///     .offset(0x1A2B3C)
+    ///     .deref()
+    ///     .offset(0x18)
+    ///     .build();
+    /// ```
+    pub const fn builder() -> DeepPointerBuilder<CAP> {
+        DeepPointerBuilder {
+            base: Base::Address(Address::NULL),
+            path: [0; CAP],
+            depth: 0,
+            pending: 0,
+            pointer_size: PointerSize::Bit64,
         }
-        Ok(address + last)
+    }
+
+    /// Dereferences the pointer path, returning the memory address of the value of interest.
+    /// The memory can be read either directly from a [`Process`](crate::Process) or through a
+    /// [`MemoryCache`](crate::memory_reader::MemoryCache).
+    pub fn deref_offsets(&self, process: &impl MemoryReader) -> Result<Address, Error> {
+        self.deref_offsets_cached(process, &mut None)
+    }
+
+    /// Dereferences the pointer path like
+    /// [`deref_offsets`](Self::deref_offsets), with the address of the module
+    /// taken from the cache given.
+    fn deref_offsets_cached(
+        &self,
+        process: &impl MemoryReader,
+        cache: &mut ModuleCache,
+    ) -> Result<Address, Error> {
+        let path = &self.path[..self.depth];
+        self.base.follow(
+            process,
+            cache,
+            |e| e,
+            |address| deref_offsets(process, address, self.pointer_size, path),
+        )
+    }
+
+    /// Dereferences the pointer path like [`deref_offsets`](Self::deref_offsets),
+    /// but if a pointer can't be read, the error describes which offset of the
+    /// path failed and the address that failed to be read. If the module that
+    /// the path starts at isn't loaded, the error is reported for the first
+    /// offset with a null address.
+    pub fn resolve(&self, process: &impl MemoryReader) -> Result<Address, PointerPathError> {
+        let path = &self.path[..self.depth];
+        self.base
+            .follow(process, &mut None, module_not_found, |address| {
+                resolve(process, address, self.pointer_size, path)
+            })
     }
 
     /// Dereferences the pointer path, returning the value stored at the final memory address
-    pub fn deref<T: CheckedBitPattern>(&self, process: &Process) -> Result<T, Error> {
-        process.read_pointer_path(
-            self.base_address,
-            self.pointer_size,
-            &self.path[..self.depth],
+    pub fn deref<T: CheckedBitPattern>(&self, process: &impl MemoryReader) -> Result<T, Error> {
+        let path = &self.path[..self.depth];
+        self.base.follow(
+            process,
+            &mut None,
+            |e| e,
+            |address| process.read_pointer_path(address, self.pointer_size, path),
+        )
+    }
+}
+
+/// A builder for a [`DeepPointer`]. Offsets are accumulated until
+/// [`deref`](Self::deref) is called, which reads the pointer at the current
+/// address. If more than `CAP` dereferences are described, the pointer path is
+/// truncated like with [`DeepPointer::new`].
+#[derive(Copy, Clone)]
+pub struct DeepPointerBuilder<const CAP: usize> {
+    base: Base,
+    path: [u64; CAP],
+    depth: usize,
+    pending: u64,
+    pointer_size: PointerSize,
+}
+
+impl<const CAP: usize> DeepPointerBuilder<CAP> {
+    /// Starts the pointer path at the address given.
+    pub const fn address(mut self, address: Address) -> Self {
+        self.base = Base::Address(address);
+        self
+    }
+
+    /// Starts the pointer path at the address of the module with the given
+    /// name. Check [`DeepPointer::new_module_relative`] for more information.
+    pub const fn module(mut self, module_name: &'static str) -> Self {
+        self.base = Base::Module(module_name, 0);
+        self
+    }
+
+    /// Sets the size of the pointers that are read. By default 64-bit pointers
+    /// are read.
+    pub const fn pointer_size(mut self, pointer_size: PointerSize) -> Self {
+        self.pointer_size = pointer_size;
+        self
+    }
+
+    /// Adds an offset to the current address.
+    pub const fn offset(mut self, offset: u64) -> Self {
+        self.pending = self.pending.wrapping_add(offset);
+        self
+    }
+
+    /// Reads the pointer at the current address and continues at the address
+    /// that it points to.
+    pub const fn deref(self) -> Self {
+        self.push_pending()
+    }
+
+    /// Builds the DeepPointer. The current address is the address of the value
+    /// of interest.
+    pub const fn build(self) -> DeepPointer<CAP> {
+        let this = self.push_pending();
+        DeepPointer {
+            base: this.base,
+            path: this.path,
+            depth: this.depth,
+            pointer_size: this.pointer_size,
+        }
+    }
+
+    const fn push_pending(mut self) -> Self {
+        if self.depth < CAP {
+            self.path[self.depth] = self.pending;
+            self.depth += 1;
+        }
+        self.pending = 0;
+        self
+    }
+}
+
+/// A pointer path like [`DeepPointer`], but the offsets are stored in a
+/// [`Vec`](alloc::vec::Vec), so the path can be of any length. This is useful
+/// for pointer paths that are generated at runtime.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct UnboundedDeepPointer {
+    start: Start,
+    path: alloc::vec::Vec<u64>,
+    pointer_size: PointerSize,
+}
+
+#[cfg(feature = "alloc")]
+impl UnboundedDeepPointer {
+    /// Creates a new pointer path that starts at the address given.
+    pub fn new(
+        base_address: impl Into<Address>,
+        pointer_size: PointerSize,
+        path: impl Into<alloc::vec::Vec<u64>>,
+    ) -> Self {
+        Self {
+            start: Start::new(Base::Address(base_address.into())),
+            path: path.into(),
+            pointer_size,
+        }
+    }
+
+    /// Creates a new pointer path that starts at an offset into the module
+    /// with the given name. Check [`DeepPointer::new_module_relative`] for
+    /// more information.
+    pub fn new_module_relative(
+        module_name: &'static str,
+        base_offset: u64,
+        pointer_size: PointerSize,
+        path: impl Into<alloc::vec::Vec<u64>>,
+    ) -> Self {
+        Self {
+            start: Start::new(Base::Module(module_name, base_offset)),
+            path: path.into(),
+            pointer_size,
+        }
+    }
+
+    /// Returns the offsets of the pointer path.
+    pub fn path(&self) -> &[u64] {
+        &self.path
+    }
+
+    /// Dereferences the pointer path, returning the memory address of the
+    /// value of interest.
+    pub fn deref_offsets(&self, process: &impl MemoryReader) -> Result<Address, Error> {
+        self.start.follow(
+            process,
+            |e| e,
+            |address| deref_offsets(process, address, self.pointer_size, &self.path),
+        )
+    }
+
+    /// Dereferences the pointer path like
+    /// [`deref_offsets`](Self::deref_offsets), but the error describes which
+    /// offset of the path failed. Check [`DeepPointer::resolve`] for more
+    /// information.
+    pub fn resolve(&self, process: &impl MemoryReader) -> Result<Address, PointerPathError> {
+        self.start.follow(process, module_not_found, |address| {
+            resolve(process, address, self.pointer_size, &self.path)
+        })
+    }
+
+    /// Dereferences the pointer path, returning the value stored at the final
+    /// memory address.
+    pub fn deref<T: CheckedBitPattern>(&self, process: &impl MemoryReader) -> Result<T, Error> {
+        self.start.follow(
+            process,
+            |e| e,
+            |address| process.read_pointer_path(address, self.pointer_size, &self.path),
         )
     }
 }
+
+#[cfg(feature = "alloc")]
+impl<const CAP: usize> From<DeepPointer<CAP>> for UnboundedDeepPointer {
+    fn from(pointer: DeepPointer<CAP>) -> Self {
+        Self {
+            path: pointer.path[..pointer.depth].into(),
+            start: Start::new(pointer.base),
+            pointer_size: pointer.pointer_size,
+        }
+    }
+}
+
+/// A [`DeepPointer`] that caches the address it resolves to, so dereferencing
+/// it usually only needs to read the value of interest instead of following
+/// the entire pointer path.
+///
+/// The cached address is revalidated by following the pointer path again
+/// whenever reading the value fails and after every `revalidation_interval`
+/// dereferences, which defaults to 8. A pointer along the path may change
+/// while the cached address still points at readable memory, in which case
+/// stale values are read until the next revalidation. So the interval should
+/// be kept small and [`invalidate`](Self::invalidate) should be called
+/// whenever the game is known to have moved its data, such as during load
+/// screens.
+///
+/// The address of the module that the pointer path starts at and the
+/// resolved address are cached along with the process they belong to, so
+/// they are discarded when a different process is used.
+#[derive(Copy, Clone)]
+pub struct CachedDeepPointer<const CAP: usize> {
+    pointer: DeepPointer<CAP>,
+    module_address: ModuleCache,
+    /// The resolved address along with the handle of the process that it
+    /// belongs to.
+    address: Option<(u64, Address)>,
+    uses: u32,
+    revalidation_interval: u32,
+}
+
+impl<const CAP: usize> CachedDeepPointer<CAP> {
+    /// Creates a new cache for the pointer path given.
+    pub const fn new(pointer: DeepPointer<CAP>) -> Self {
+        Self {
+            pointer,
+            module_address: None,
+            address: None,
+            uses: 0,
+            revalidation_interval: 8,
+        }
+    }
+
+    /// Sets after how many dereferences the cached address is revalidated. An
+    /// interval of 1 or less follows the pointer path every time.
+    pub const fn with_revalidation_interval(mut self, revalidation_interval: u32) -> Self {
+        self.revalidation_interval = revalidation_interval;
+        self
+    }
+
+    /// Returns the pointer path that is cached.
+    pub const fn pointer(&self) -> &DeepPointer<CAP> {
+        &self.pointer
+    }
+
+    /// Discards the cached address, so the next dereference follows the
+    /// pointer path again.
+    pub const fn invalidate(&mut self) {
+        self.module_address = None;
+        self.address = None;
+    }
+
+    /// Returns the memory address of the value of interest. The cached
+    /// address is returned unless it needs to be revalidated. As no memory is
+    /// read at the cached address, a failure to read it doesn't cause a
+    /// revalidation.
+    pub fn deref_offsets(&mut self, process: &impl MemoryReader) -> Result<Address, Error> {
+        if let Some(address) = self.cached_address(process) {
+            return Ok(address);
+        }
+        self.revalidate(process)
+    }
+
+    /// Dereferences the pointer path, returning the value stored at the final
+    /// memory address. Check the type level documentation for when the
+    /// pointer path is followed again.
+    pub fn deref<T: CheckedBitPattern>(&mut self, process: &impl MemoryReader) -> Result<T, Error> {
+        if let Some(address) = self.cached_address(process) {
+            if let Ok(value) = process.read(address) {
+                return Ok(value);
+            }
+        }
+        let address = self.revalidate(process)?;
+        process.read(address)
+    }
+
+    fn cached_address(&mut self, process: &impl MemoryReader) -> Option<Address> {
+        let (handle, address) = self.address?;
+        if handle != process.process().handle() || self.uses >= self.revalidation_interval {
+            return None;
+        }
+        self.uses += 1;
+        Some(address)
+    }
+
+    fn revalidate(&mut self, process: &impl MemoryReader) -> Result<Address, Error> {
+        self.address = None;
+        let address = self
+            .pointer
+            .deref_offsets_cached(process, &mut self.module_address)?;
+        self.address = Some((process.process().handle(), address));
+        self.uses = 1;
+        Ok(address)
+    }
+}
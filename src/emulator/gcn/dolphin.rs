@@ -29,6 +29,12 @@ impl State {
     }
 
     pub fn keep_alive(&self, game: &Process, ram_base: &Option<Address>) -> bool {
-        ram_base.is_some_and(|addr| game.read::<u8>(addr).is_ok())
+        // Checking the magic number as well makes sure that the emulator
+        // didn't reuse the memory for something else after restarting the
+        // emulation.
+        ram_base.is_some_and(|addr| {
+            game.read::<u32>(addr + 0x1C)
+                .is_ok_and(|magic| magic.from_endian(Endian::Big) == 0xC2339F3D)
+        })
     }
 }
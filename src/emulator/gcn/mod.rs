@@ -128,7 +128,13 @@ impl Emulator {
         let mem1 = self.mem1_base.get().ok_or(Error {})?;
         let end_offset = offset.checked_sub(0x80000000).unwrap_or(offset);
 
-        self.process.read(mem1 + end_offset)
+        self.process.read(mem1 + end_offset).inspect_err(|_| {
+            // The emulator reallocates the emulated RAM when the emulation
+            // restarts, so the RAM needs to be looked up again once it's gone.
+            if self.process.read::<u8>(mem1).is_err() {
+                self.mem1_base.set(None);
+            }
+        })
     }
 
     /// Reads any value from the emulated RAM.
@@ -147,6 +153,14 @@ impl Emulator {
             .read_ignoring_endianness::<T>(offset)?
             .from_endian(self.endian.get()))
     }
+
+    /// Reads the ID of the game that is currently running, such as `GALE01`.
+    /// The ID is stored at the start of the emulated RAM and consists of the
+    /// game code, the region code and the maker code. This can be used to
+    /// make sure that the emulator is running the right game.
+    pub fn game_id(&self) -> Result<[u8; 6], Error> {
+        self.read_ignoring_endianness(0x80000000)
+    }
 }
 
 /// A future that executes a future until the emulator closes.
@@ -107,17 +107,30 @@ impl Emulator {
     /// from `0x00100000` (addresses below this threashold are
     /// reserved for the kernel).
     ///
-    /// Valid addresses for the PS2's memory range from `0x00100000` to `0x01FFFFFF`
+    /// Valid addresses for the PS2's memory range from `0x00100000` to `0x01FFFFFF`.
+    /// The mirrors of the memory at `0x20000000`, `0x30000000`, `0x80000000`
+    /// and `0xA0000000` are translated to these addresses.
     ///
     /// Providing any offset outside the range of the PS2's RAM will return
     /// `Err()`.
     pub fn read<T: CheckedBitPattern>(&self, address: u32) -> Result<T, Error> {
+        let address = match address >> 28 {
+            0x0 | 0x2 | 0x3 | 0x8 | 0xA => address & 0x0FFFFFFF,
+            _ => return Err(Error {}),
+        };
         if !(0x00100000..0x02000000).contains(&address) {
             return Err(Error {});
         }
 
         let ram_base = self.ram_base.get().ok_or(Error {})?;
-        self.process.read(ram_base + address)
+        self.process.read(ram_base + address).inspect_err(|_| {
+            // The emulator may reallocate the emulated RAM when the game is
+            // reset or changed, so it needs to be looked up again once it's
+            // gone.
+            if self.process.read::<u8>(ram_base).is_err() {
+                self.ram_base.set(None);
+            }
+        })
     }
 
     /// Follows a path of pointers from the base address given and reads a value of the
@@ -16,7 +16,11 @@ impl State {
         self.is_64_bit =
             pe::MachineType::read(game, main_module_range.0) == Some(pe::MachineType::X86_64);
 
-        self.addr_base = if self.is_64_bit {
+        // Newer versions export the pointer to the emulated RAM for tools such
+        // as auto splitters, so the signatures are only needed for older ones.
+        self.addr_base = if let Some(eemem) = pe::find_export(game, main_module_range.0, "EEmem") {
+            eemem
+        } else if self.is_64_bit {
             const SIG: Signature<12> = Signature::new("48 8B ?? ?? ?? ?? ?? 25 F0 3F 00 00");
             let ptr = SIG.scan_process_range(game, main_module_range)? + 3;
             ptr + 0x4 + game.read::<i32>(ptr).ok()?
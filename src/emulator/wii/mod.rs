@@ -180,6 +180,25 @@ impl Emulator {
         Ok(address + last)
     }
 
+    /// Forgets the addresses of the emulated RAM if they can't be read
+    /// anymore. The emulator reallocates the emulated RAM when the emulation
+    /// restarts, so it then needs to be looked up again.
+    fn revalidate(&self) {
+        if let Some([mem1, mem2]) = self.ram_base.get() {
+            if self.process.read::<u8>(mem1).is_err() || self.process.read::<u8>(mem2).is_err() {
+                self.ram_base.set(None);
+            }
+        }
+    }
+
+    /// Reads the ID of the game that is currently running, such as `RMGE01`.
+    /// The ID is stored at the start of `MEM1` and consists of the game code,
+    /// the region code and the maker code. This can be used to make sure that
+    /// the emulator is running the right game.
+    pub fn game_id(&self) -> Result<[u8; 6], Error> {
+        self.read_ignoring_endianness_from_mem_1(0x80000000)
+    }
+
     /// Reads raw data from the emulated RAM ignoring all endianness settings.
     /// The same call, performed on two different emulators, might return different
     /// results due to the endianness used by the emulator.
@@ -202,7 +221,9 @@ impl Emulator {
 
         let [mem1, _] = self.ram_base.get().ok_or(Error {})?;
         let end_offset = address.checked_sub(0x80000000).unwrap_or(address);
-        self.process.read(mem1 + end_offset)
+        self.process
+            .read(mem1 + end_offset)
+            .inspect_err(|_| self.revalidate())
     }
 
     /// Reads raw data from the emulated RAM ignoring all endianness settings.
@@ -226,7 +247,9 @@ impl Emulator {
         }
         let [_, mem2] = self.ram_base.get().ok_or(Error {})?;
         let end_offset = address.checked_sub(0x90000000).unwrap_or(address);
-        self.process.read(mem2 + end_offset)
+        self.process
+            .read(mem2 + end_offset)
+            .inspect_err(|_| self.revalidate())
     }
 }
 
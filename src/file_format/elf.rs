@@ -8,6 +8,8 @@ use core::{
 
 use bytemuck::{Pod, Zeroable};
 
+use arrayvec::ArrayVec;
+
 use crate::{string::ArrayCString, Address, Endian, Error, FromEndian, PointerSize, Process};
 
 // Based on:
@@ -218,6 +220,8 @@ impl DynamicArrayTag {
     pub const DT_PREINIT_ARRAYSZ: Self = Self(33);
     /// The number of positive dynamic array tag values
     pub const DT_MAXPOSTAGS: Self = Self(34);
+    /// Address of the GNU style symbol hash table
+    pub const DT_GNU_HASH: Self = Self(0x6FFFFEF5);
 }
 
 /// The architecture of an ELF file.
@@ -1051,6 +1055,65 @@ impl Symbol {
     }
 }
 
+/// The fields of a program header that are shared between 32-bit and 64-bit
+/// ELF files.
+#[derive(Copy, Clone)]
+struct Segment {
+    ty: SegmentType,
+    vaddr: u64,
+    memsz: u64,
+    align: u64,
+}
+
+fn program_headers(
+    process: &Process,
+    module_address: Address,
+    is_64_bit: bool,
+) -> impl Iterator<Item = Segment> + '_ {
+    let (e_phoff, e_phentsize, e_phnum) = if is_64_bit {
+        process
+            .read::<Elf64>(module_address)
+            .map(|header| (header.e_phoff, header.e_phentsize, header.e_phnum))
+    } else {
+        process
+            .read::<Elf32>(module_address)
+            .map(|header| (header.e_phoff as u64, header.e_phentsize, header.e_phnum))
+    }
+    .unwrap_or_default();
+
+    (0..e_phnum).filter_map(move |index| {
+        let address = module_address + e_phoff + e_phentsize.wrapping_mul(index);
+        if is_64_bit {
+            let header = process.read::<ProgramHeader64>(address).ok()?;
+            Some(Segment {
+                ty: SegmentType(header.p_type),
+                vaddr: header.p_vaddr,
+                memsz: header.p_memsz,
+                align: header.p_align,
+            })
+        } else {
+            let header = process.read::<ProgramHeader32>(address).ok()?;
+            Some(Segment {
+                ty: SegmentType(header.p_type),
+                vaddr: header.p_vaddr as u64,
+                memsz: header.p_memsz as u64,
+                align: header.p_align as u64,
+            })
+        }
+    })
+}
+
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+struct SymTab32 {
+    st_name: u32,
+    st_value: u32,
+    st_size: u32,
+    st_info: u8,
+    st_other: u8,
+    st_shndx: u16,
+}
+
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 #[repr(C)]
 struct SymTab64 {
@@ -1062,91 +1125,366 @@ struct SymTab64 {
     st_size: u64,
 }
 
+/// The tables referenced by the dynamic segment of a module.
+#[derive(Copy, Clone)]
+struct DynamicTables {
+    is_64_bit: bool,
+    symtab: Address,
+    strtab: Address,
+    strsz: u64,
+    gnu_hash: Option<Address>,
+}
+
+impl DynamicTables {
+    fn read(process: &Process, module_address: Address) -> Option<Self> {
+        let header = process.read::<Header>(module_address).ok()?;
+        let is_64_bit = match Info::parse(bytemuck::bytes_of(&header))?.bitness {
+            Bitness::BITNESS_64 => true,
+            Bitness::BITNESS_32 => false,
+            _ => return None,
+        };
+
+        let (segment_address, segment_size) = program_headers(process, module_address, is_64_bit)
+            .find(|header| header.ty == SegmentType::PT_DYNAMIC)
+            .map(|header| (module_address + header.vaddr, header.memsz))?;
+
+        let entry_size = if is_64_bit {
+            size_of::<[u64; 2]>()
+        } else {
+            size_of::<[u32; 2]>()
+        };
+
+        let entries = (0..segment_size)
+            .step_by(entry_size)
+            .map_while(|offset| {
+                let address = segment_address + offset;
+                if is_64_bit {
+                    let [tag, val] = process.read::<[u64; 2]>(address).ok()?;
+                    Some((DynamicArrayTag(tag as u32), val))
+                } else {
+                    let [tag, val] = process.read::<[u32; 2]>(address).ok()?;
+                    Some((DynamicArrayTag(tag), val as u64))
+                }
+            })
+            .take_while(|&(tag, _)| tag != DynamicArrayTag::DT_NULL);
+
+        // The dynamic linker usually relocates the addresses stored in the
+        // dynamic segment, but not all of them do, so they may still be
+        // relative to the module.
+        let resolve = |val: u64| {
+            if val < module_address.value() {
+                module_address + val
+            } else {
+                Address::new(val)
+            }
+        };
+
+        let (mut symtab, mut strtab, mut strsz, mut gnu_hash) = (None, None, None, None);
+        for (tag, val) in entries {
+            match tag {
+                DynamicArrayTag::DT_SYMTAB => symtab = Some(resolve(val)),
+                DynamicArrayTag::DT_STRTAB => strtab = Some(resolve(val)),
+                DynamicArrayTag::DT_STRSZ => strsz = Some(val),
+                DynamicArrayTag::DT_GNU_HASH => gnu_hash = Some(resolve(val)),
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            is_64_bit,
+            symtab: symtab?,
+            strtab: strtab?,
+            strsz: strsz?,
+            gnu_hash,
+        })
+    }
+
+    fn symbol(&self, process: &Process, module_address: Address, index: u64) -> Option<Symbol> {
+        let (st_name, st_value, st_size) = if self.is_64_bit {
+            let table = process
+                .read::<SymTab64>(self.symtab + index * size_of::<SymTab64>() as u64)
+                .ok()?;
+            (table.st_name, table.st_value, table.st_size)
+        } else {
+            let table = process
+                .read::<SymTab32>(self.symtab + index * size_of::<SymTab32>() as u64)
+                .ok()?;
+            (table.st_name, table.st_value as u64, table.st_size as u64)
+        };
+
+        if st_name as u64 >= self.strsz {
+            return None;
+        }
+
+        Some(Symbol {
+            address: module_address + st_value,
+            size: st_size,
+            name_addr: self.strtab + st_name,
+        })
+    }
+}
+
 /// Recovers and iterates over the exported symbols for a given module.
 /// Returns an empty iterator if no symbols are exported into the current module.
 ///
-/// By using this function, the user must be aware of the following limitations:
-/// - Only allocatable symbols and symbols used by the dynamic linker are exported
-/// (.symtab is not loaded in memory at runtime)
-/// - Only 64-bit ELFs are supported (an empty iterator will be returned for 32-bit ELFs)
+/// By using this function, the user must be aware that only allocatable
+/// symbols and symbols used by the dynamic linker are exported, as `.symtab`
+/// is not loaded in memory at runtime.
 pub fn symbols(
     process: &Process,
     module_address: Address,
 ) -> impl FusedIterator<Item = Symbol> + '_ {
-    let header = process.read::<Elf64>(module_address);
+    let tables = DynamicTables::read(process, module_address);
 
-    // Only 64 bit ELF is supported
-    let is_64_bit = match header {
-        Ok(x) => Info::parse(bytemuck::bytes_of(&x)).is_some_and(|info| info.bitness.is_64()),
-        _ => false,
-    };
+    let mut index = 0;
+    iter::from_fn(move || {
+        let symbol = tables?.symbol(process, module_address, index)?;
+        index += 1;
+        Some(symbol)
+    })
+    .fuse()
+}
 
-    let e_phnum = match (is_64_bit, &header) {
-        (true, Ok(x)) => x.e_phnum,
-        _ => 0,
-    };
+const MAX_HASH_CHAIN_LENGTH: u32 = 0x10000;
 
-    let e_phoff = match (is_64_bit, &header) {
-        (true, Ok(x)) => Some(x.e_phoff),
-        _ => None,
+/// Looks up the address of an exported symbol of a module by its name. If the
+/// module has a GNU hash table, it's used to look up the symbol directly. If
+/// the module doesn't have one, or the symbol isn't found through it, all the
+/// [`symbols`] are searched. Names that are longer than 127 bytes are not
+/// supported.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{file_format::elf, Process};
+/// # fn example(process: &Process) -> Option<()> {
+/// let module_address = process.get_module_address("libmono.so").ok()?;
+/// let address = elf::find_address(process, module_address, "mono_get_root_domain")?;
+/// # Some(())
+/// # }
+/// ```
+pub fn find_address(
+    process: &Process,
+    module_address: impl Into<Address>,
+    name: &str,
+) -> Option<Address> {
+    let module_address = module_address.into();
+    let tables = DynamicTables::read(process, module_address)?;
+    let matches = |symbol: &Symbol| {
+        symbol
+            .get_name::<128>(process)
+            .is_ok_and(|n| n.matches(name))
     };
 
-    let e_phentsize = match (is_64_bit, &header) {
-        (true, Ok(x)) => Some(x.e_phentsize),
-        _ => None,
-    };
+    find_address_by_gnu_hash(process, module_address, &tables, name, matches).or_else(|| {
+        symbols(process, module_address)
+            .find(matches)
+            .map(|s| s.address)
+    })
+}
 
-    let mut program_headers = (0..e_phnum).filter_map(|index| {
-        process
-            .read::<ProgramHeader64>(module_address + e_phoff? + e_phentsize?.wrapping_mul(index))
-            .ok()
-    });
-
-    let (segment_address, segment_size) = match program_headers
-        .find(|p_header| SegmentType(p_header.p_type) == SegmentType::PT_DYNAMIC)
-    {
-        Some(x) => (Some(x.p_vaddr), x.p_memsz),
-        _ => (None, 0),
-    };
+fn find_address_by_gnu_hash(
+    process: &Process,
+    module_address: Address,
+    tables: &DynamicTables,
+    name: &str,
+    matches: impl Fn(&Symbol) -> bool,
+) -> Option<Address> {
+    let gnu_hash = tables.gnu_hash?;
+    let [nbuckets, symoffset, bloom_size, bloom_shift] = process.read::<[u32; 4]>(gnu_hash).ok()?;
+    if nbuckets == 0 || bloom_size == 0 {
+        return None;
+    }
 
-    let entries = || {
-        (0..segment_size)
-            .step_by(size_of::<[u64; 2]>())
-            .filter_map(|entry| {
-                process
-                    .read::<[u64; 2]>(module_address + segment_address? + entry)
-                    .ok()
-            })
+    let hash = name
+        .bytes()
+        .fold(5381u32, |h, c| h.wrapping_mul(33).wrapping_add(c as u32));
+
+    // The bloom filter quickly rules out most of the symbols that aren't in
+    // the table. Its words are as wide as the pointers of the module.
+    let bloom_address = gnu_hash + size_of::<[u32; 4]>() as u64;
+    let (bloom_word, word_bits, word_size) = if tables.is_64_bit {
+        let index = (hash / 64) % bloom_size;
+        let word = process.read::<u64>(bloom_address + index as u64 * 8).ok()?;
+        (word, 64, 8)
+    } else {
+        let index = (hash / 32) % bloom_size;
+        let word = process.read::<u32>(bloom_address + index as u64 * 4).ok()?;
+        (word as u64, 32, 4)
     };
+    let mask = (1 << (hash % word_bits)) | (1 << ((hash >> bloom_shift) % word_bits));
+    if bloom_word & mask != mask {
+        return None;
+    }
 
-    let symtab = entries()
-        .find(|&[val, _]| DynamicArrayTag(val as u32) == DynamicArrayTag::DT_SYMTAB)
-        .map(|[_, val]| Address::new(val));
-    let strtab = entries()
-        .find(|&[val, _]| DynamicArrayTag(val as u32) == DynamicArrayTag::DT_STRTAB)
-        .map(|[_, val]| Address::new(val));
-    let strsz = entries()
-        .find(|&[val, _]| DynamicArrayTag(val as u32) == DynamicArrayTag::DT_STRSZ)
-        .map(|[_, val]| val);
-
-    let mut offset = 0;
-    iter::from_fn(move || {
-        let table = process.read::<SymTab64>(symtab? + offset).ok()?;
-        if table.st_name as u64 >= strsz? {
-            None
-        } else {
-            let f_address = module_address + table.st_value;
-            let f_size = table.st_size;
-            let f_name = strtab? + table.st_name;
+    let buckets_address = bloom_address + bloom_size as u64 * word_size;
+    let chain_address = buckets_address + nbuckets as u64 * 4;
 
-            offset += size_of::<SymTab64>() as u64;
+    let first = process
+        .read::<u32>(buckets_address + (hash % nbuckets) as u64 * 4)
+        .ok()?;
+    if first < symoffset {
+        return None;
+    }
 
-            Some(Symbol {
-                address: f_address,
-                size: f_size,
-                name_addr: f_name,
-            })
+    for index in first..first.saturating_add(MAX_HASH_CHAIN_LENGTH) {
+        let chain_hash = process
+            .read::<u32>(chain_address + (index - symoffset) as u64 * 4)
+            .ok()?;
+        if chain_hash | 1 == hash | 1 {
+            let symbol = tables.symbol(process, module_address, index as u64)?;
+            if matches(&symbol) {
+                return Some(symbol.address);
+            }
         }
-    })
-    .fuse()
+        // The lowest bit marks the end of the chain.
+        if chain_hash & 1 != 0 {
+            break;
+        }
+    }
+    None
+}
+
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// Reads the GNU build ID of a module. The build ID is a hash that the linker
+/// stores in a note segment of the module. It uniquely identifies the build of
+/// the module, which makes it a reliable way to detect which version of a game
+/// is running, as Linux builds rarely contain any version information
+/// otherwise. The build ID is usually 20 bytes long. Returns [`None`] if the
+/// module doesn't have a build ID or if it's longer than 20 bytes.
+pub fn build_id(process: &Process, module_address: Address) -> Option<ArrayVec<u8, 20>> {
+    let is_64_bit = is_64_bit(process, module_address)?;
+
+    program_headers(process, module_address, is_64_bit)
+        .filter(|segment| segment.ty == SegmentType::PT_NOTE)
+        .find_map(|segment| {
+            // Notes are aligned to 4 bytes, unless the segment explicitly
+            // asks for 8 byte alignment.
+            let align = if segment.align == 8 { 8 } else { 4 };
+            let mut buf = [0; MAX_NOTE_SEGMENT_SIZE];
+            let buf = &mut buf[..(segment.memsz as usize).min(MAX_NOTE_SEGMENT_SIZE)];
+            process
+                .read_into_buf(module_address + segment.vaddr, buf)
+                .ok()?;
+            find_build_id(buf, align)
+        })
+}
+
+/// The maximum amount of bytes that are read from a single note segment. The
+/// build ID note is usually one of the first notes, so this is plenty.
+const MAX_NOTE_SEGMENT_SIZE: usize = 0x400;
+
+/// Walks the notes of a note segment and returns the payload of the GNU build
+/// ID note. Each note consists of a header with the sizes of its name and its
+/// payload and its type, followed by the name and the payload. Both of them
+/// start at an offset from the start of the note that is aligned.
+fn find_build_id(mut notes: &[u8], align: usize) -> Option<ArrayVec<u8, 20>> {
+    while let Some(header) = notes.first_chunk::<12>() {
+        let [name_size, desc_size, ty] =
+            bytemuck::cast::<[u8; 12], [[u8; 4]; 3]>(*header).map(u32::from_le_bytes);
+        let desc_offset = (name_size as usize)
+            .checked_add(12)?
+            .checked_next_multiple_of(align)?;
+        let next_offset = desc_offset
+            .checked_add(desc_size as usize)?
+            .checked_next_multiple_of(align)?;
+
+        let name = notes.get(12..)?.get(..name_size as usize)?;
+        let desc = notes.get(desc_offset..)?.get(..desc_size as usize)?;
+        if ty == NT_GNU_BUILD_ID && name == b"GNU\0" {
+            return ArrayVec::try_from(desc).ok();
+        }
+
+        notes = notes.get(next_offset..)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_note(notes: &mut ArrayVec<u8, 256>, ty: u32, name: &[u8], desc: &[u8], align: usize) {
+        notes
+            .try_extend_from_slice(&(name.len() as u32).to_le_bytes())
+            .unwrap();
+        notes
+            .try_extend_from_slice(&(desc.len() as u32).to_le_bytes())
+            .unwrap();
+        notes.try_extend_from_slice(&ty.to_le_bytes()).unwrap();
+        for field in [name, desc] {
+            notes.try_extend_from_slice(field).unwrap();
+            while notes.len() % align != 0 {
+                notes.push(0);
+            }
+        }
+    }
+
+    const BUILD_ID: [u8; 20] = [
+        0x3b, 0x7a, 0x1f, 0x90, 0x2c, 0x55, 0xe4, 0x08, 0x61, 0xd2, 0x4e, 0xaa, 0x13, 0x77, 0xc0,
+        0x9f, 0x25, 0x6b, 0x81, 0xfe,
+    ];
+
+    #[test]
+    fn build_id_first_note() {
+        let mut notes = ArrayVec::new();
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"GNU\0", &BUILD_ID, 4);
+        assert_eq!(find_build_id(&notes, 4).unwrap().as_slice(), BUILD_ID);
+    }
+
+    #[test]
+    fn build_id_after_other_notes() {
+        let mut notes = ArrayVec::new();
+        // NT_GNU_ABI_TAG
+        push_note(
+            &mut notes,
+            1,
+            b"GNU\0",
+            &[0, 0, 0, 0, 3, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0],
+            4,
+        );
+        // A vendor note whose name needs padding.
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"Go\0", &[1, 2, 3, 4, 5], 4);
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"GNU\0", &BUILD_ID, 4);
+        assert_eq!(find_build_id(&notes, 4).unwrap().as_slice(), BUILD_ID);
+    }
+
+    #[test]
+    fn build_id_8_byte_alignment() {
+        let mut notes = ArrayVec::new();
+        // NT_GNU_PROPERTY_TYPE_0
+        push_note(
+            &mut notes,
+            5,
+            b"GNU\0",
+            &[2, 0, 0, 0xc0, 4, 0, 0, 0, 3, 0, 0, 0],
+            8,
+        );
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"GNU\0", &BUILD_ID[..8], 8);
+        assert_eq!(find_build_id(&notes, 8).unwrap().as_slice(), &BUILD_ID[..8]);
+    }
+
+    #[test]
+    fn build_id_missing() {
+        let mut notes = ArrayVec::new();
+        push_note(&mut notes, 1, b"GNU\0", &[0; 16], 4);
+        assert!(find_build_id(&notes, 4).is_none());
+        assert!(find_build_id(&[], 4).is_none());
+    }
+
+    #[test]
+    fn build_id_truncated() {
+        let mut notes = ArrayVec::new();
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"GNU\0", &BUILD_ID, 4);
+        assert!(find_build_id(&notes[..notes.len() - 1], 4).is_none());
+        assert!(find_build_id(&notes[..10], 4).is_none());
+    }
+
+    #[test]
+    fn build_id_too_long() {
+        let mut notes = ArrayVec::new();
+        push_note(&mut notes, NT_GNU_BUILD_ID, b"GNU\0", &[0xAB; 32], 4);
+        assert!(find_build_id(&notes, 4).is_none());
+    }
 }
@@ -1,4 +1,135 @@
 //! Support for parsing various file formats.
 
+use crate::{string::ArrayCString, Address, Error, Process};
+
 pub mod elf;
 pub mod pe;
+
+const CSTR: usize = 128;
+
+/// The file format of a module that is loaded into a process.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Format {
+    /// A Windows Portable Executable.
+    Pe,
+    /// An ELF file (Executable and Linking Format).
+    Elf,
+}
+
+impl Format {
+    /// Detects the file format of a module by looking at the magic bytes at
+    /// the start of the module. Returns [`None`] if the format is not
+    /// supported.
+    pub fn detect(process: &Process, module_address: impl Into<Address>) -> Option<Self> {
+        let magic = process.read::<[u8; 4]>(module_address.into()).ok()?;
+        if magic.starts_with(b"MZ") {
+            Some(Self::Pe)
+        } else if magic == *b"\x7fELF" {
+            Some(Self::Elf)
+        } else {
+            None
+        }
+    }
+}
+
+/// Provides access to the symbols of a module regardless of its file format.
+/// This allows auto splitters for games that are released on multiple
+/// platforms to look up symbols without having to handle each file format
+/// separately.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{file_format, Process};
+/// # fn example(process: &Process) -> Option<()> {
+/// let module_address = process.get_module_address("GameAssembly.dll").ok()?;
+/// let symbols = file_format::symbols(process, module_address)?;
+/// let address = symbols.find_address(process, "il2cpp_domain_get")?;
+/// # Some(())
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModuleSymbols {
+    format: Format,
+    module_address: Address,
+}
+
+/// Detects the file format of a module and provides access to its symbols.
+/// Returns [`None`] if the file format of the module is not supported.
+pub fn symbols(process: &Process, module_address: impl Into<Address>) -> Option<ModuleSymbols> {
+    let module_address = module_address.into();
+    Some(ModuleSymbols {
+        format: Format::detect(process, module_address)?,
+        module_address,
+    })
+}
+
+impl ModuleSymbols {
+    /// Returns the file format of the module.
+    pub const fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the address of the module.
+    pub const fn module_address(&self) -> Address {
+        self.module_address
+    }
+
+    /// Iterates over the symbols of the module. Check [`pe::symbols`] and
+    /// [`elf::symbols`] for the limitations of the individual file formats.
+    pub fn iter<'a>(&self, process: &'a Process) -> impl Iterator<Item = Symbol> + 'a {
+        let (pe, elf) = match self.format {
+            Format::Pe => (Some(pe::symbols(process, self.module_address)), None),
+            Format::Elf => (None, Some(elf::symbols(process, self.module_address))),
+        };
+        pe.into_iter()
+            .flatten()
+            .map(Symbol::Pe)
+            .chain(elf.into_iter().flatten().map(Symbol::Elf))
+    }
+
+    /// Looks up the address of the symbol with the given name. Names that are
+    /// longer than 127 bytes are not supported.
+    pub fn find_address(&self, process: &Process, name: &str) -> Option<Address> {
+        if self.format == Format::Elf {
+            return elf::find_address(process, self.module_address, name);
+        }
+        self.iter(process)
+            .find(|symbol| {
+                symbol
+                    .get_name::<CSTR>(process)
+                    .is_ok_and(|n| n.matches(name))
+            })
+            .map(|symbol| symbol.address())
+    }
+}
+
+/// A symbol of a module, independent of the file format of the module.
+pub enum Symbol {
+    /// A symbol exported by a Windows Portable Executable.
+    Pe(pe::Symbol),
+    /// A symbol of an ELF file.
+    Elf(elf::Symbol),
+}
+
+impl Symbol {
+    /// The address associated with the symbol.
+    pub const fn address(&self) -> Address {
+        match self {
+            Self::Pe(symbol) => symbol.address,
+            Self::Elf(symbol) => symbol.address,
+        }
+    }
+
+    /// Tries to retrieve the name of the symbol.
+    pub fn get_name<const CAP: usize>(
+        &self,
+        process: &Process,
+    ) -> Result<ArrayCString<CAP>, Error> {
+        match self {
+            Self::Pe(symbol) => symbol.get_name(process),
+            Self::Elf(symbol) => symbol.get_name(process),
+        }
+    }
+}
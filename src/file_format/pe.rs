@@ -1,10 +1,15 @@
 //! Support for parsing Windows Portable Executables.
 
-use core::{fmt, mem};
+use core::{fmt, mem, ops};
 
 use bytemuck::{Pod, Zeroable};
 
-use crate::{string::ArrayCString, Address, Error, FromEndian, PointerSize, Process};
+use crate::{
+    string::{ArrayCString, ArrayString},
+    Address, Error, FromEndian, PointerSize, Process,
+};
+
+const CSTR: usize = 128;
 
 // Reference:
 // https://learn.microsoft.com/en-us/windows/win32/debug/pe-format
@@ -95,6 +100,140 @@ struct OptionalCOFFHeader {
     // There's more but those vary depending on whether it's PE or PE+.
 }
 
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct DataDirectory {
+    virtual_address: u32,
+    size: u32,
+}
+
+impl DataDirectory {
+    const EXPORT_TABLE: u32 = 0;
+    const IMPORT_TABLE: u32 = 1;
+    const RESOURCE_TABLE: u32 = 2;
+    const DEBUG: u32 = 6;
+    const TLS_TABLE: u32 = 9;
+
+    const fn contains(&self, rva: u32) -> bool {
+        rva >= self.virtual_address && rva - self.virtual_address < self.size
+    }
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct ExportDirectory {
+    characteristics: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    name: u32,
+    base: u32,
+    number_of_functions: u32,
+    number_of_names: u32,
+    address_of_functions: u32,
+    address_of_names: u32,
+    address_of_name_ordinals: u32,
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct ImportDescriptor {
+    original_first_thunk: u32,
+    time_date_stamp: u32,
+    forwarder_chain: u32,
+    name: u32,
+    first_thunk: u32,
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct ResourceDirectory {
+    characteristics: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    number_of_named_entries: u16,
+    number_of_id_entries: u16,
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct ResourceDirectoryEntry {
+    name_or_id: u32,
+    offset_to_data: u32,
+}
+
+impl ResourceDirectoryEntry {
+    const SUBDIRECTORY: u32 = 1 << 31;
+
+    const fn subdirectory(&self) -> Option<u32> {
+        if self.offset_to_data & Self::SUBDIRECTORY != 0 {
+            Some(self.offset_to_data & !Self::SUBDIRECTORY)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct ResourceDataEntry {
+    offset_to_data: u32,
+    size: u32,
+    code_page: u32,
+    reserved: u32,
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct FixedFileInfo {
+    signature: u32,
+    struct_version: u32,
+    file_version_ms: u32,
+    file_version_ls: u32,
+    product_version_ms: u32,
+    product_version_ls: u32,
+    file_flags_mask: u32,
+    file_flags: u32,
+    file_os: u32,
+    file_type: u32,
+    file_subtype: u32,
+    file_date_ms: u32,
+    file_date_ls: u32,
+}
+
+#[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct DebugDirectory {
+    characteristics: u32,
+    time_date_stamp: u32,
+    major_version: u16,
+    minor_version: u16,
+    ty: u32,
+    size_of_data: u32,
+    address_of_raw_data: u32,
+    pointer_to_raw_data: u32,
+}
+
+impl DebugDirectory {
+    const TYPE_CODEVIEW: u32 = 2;
+}
+
+#[derive(Copy, Clone, Zeroable, Pod, Default)]
+#[repr(C)]
+struct SectionHeader {
+    name: ArrayCString<8>,
+    virtual_size: u32,
+    virtual_address: u32,
+    size_of_raw_data: u32,
+    pointer_to_raw_data: u32,
+    pointer_to_relocations: u32,
+    pointer_to_line_numbers: u32,
+    number_of_relocations: u16,
+    number_of_line_numbers: u16,
+    characteristics: u32,
+}
+
 #[derive(Debug, Copy, Clone, Zeroable, Pod, Default)]
 #[repr(C)]
 struct ExportedSymbolsTableDef {
@@ -285,6 +424,56 @@ fn read_coff_header(process: &Process, module_address: Address) -> Option<(COFFH
     Some((coff_header, coff_header_address))
 }
 
+fn read_pointer_size(process: &Process, module_address: Address) -> Option<PointerSize> {
+    let (_, coff_header_address) = read_coff_header(process, module_address)?;
+    let magic = process
+        .read::<u16>(coff_header_address + mem::size_of::<COFFHeader>() as u64)
+        .ok()?;
+    match magic {
+        0x10b => Some(PointerSize::Bit32),
+        0x20b => Some(PointerSize::Bit64),
+        _ => None,
+    }
+}
+
+fn read_data_directory(
+    process: &Process,
+    module_address: Address,
+    index: u32,
+) -> Option<DataDirectory> {
+    let (coff_header, coff_header_address) = read_coff_header(process, module_address)?;
+    let optional_header_address = coff_header_address + mem::size_of::<COFFHeader>() as u64;
+
+    // The data directories follow the fixed fields of the optional header,
+    // which are larger for PE32+.
+    let (count_offset, directories_offset) = match read_pointer_size(process, module_address)? {
+        PointerSize::Bit64 => (0x6C, 0x70),
+        _ => (0x5C, 0x60),
+    };
+
+    let count = process
+        .read::<u32>(optional_header_address + count_offset as u64)
+        .ok()?;
+    let directory_offset = directories_offset + index * mem::size_of::<DataDirectory>() as u32;
+
+    if index >= count
+        || directory_offset as usize + mem::size_of::<DataDirectory>()
+            > coff_header.size_of_optional_header as usize
+    {
+        return None;
+    }
+
+    let directory = process
+        .read::<DataDirectory>(optional_header_address + directory_offset)
+        .ok()?;
+
+    if directory.virtual_address == 0 {
+        return None;
+    }
+
+    Some(directory)
+}
+
 /// A symbol exported into the current module.
 pub struct Symbol {
     /// The address associated with the current symbol
@@ -356,3 +545,814 @@ pub fn symbols(
         })
     })
 }
+
+/// The maximum amount of forwarders that are followed when resolving an
+/// [`Export`]. This prevents endless loops for forwarders that refer to each
+/// other.
+const MAX_FORWARDER_DEPTH: usize = 8;
+
+/// An export of a module (`exe` or `dll`). An export either points to code or
+/// data inside the module, or it is forwarded to an export of a different
+/// module.
+pub struct Export {
+    /// The relative virtual address of the export. For forwarded exports this
+    /// points to the forwarder string instead of code or data.
+    pub rva: u32,
+    /// The ordinal of the export.
+    pub ordinal: u32,
+    /// Whether the export is forwarded to an export of a different module.
+    pub is_forwarded: bool,
+    module_address: Address,
+    name_rva: u32,
+}
+
+impl Export {
+    /// Tries to retrieve the name of the export. Exports that are only
+    /// exported by their ordinal don't have a name.
+    pub fn get_name<const CAP: usize>(
+        &self,
+        process: &Process,
+    ) -> Result<ArrayCString<CAP>, Error> {
+        if self.name_rva == 0 {
+            return Err(Error {});
+        }
+        process.read(self.module_address + self.name_rva)
+    }
+
+    /// Tries to retrieve the forwarder string of the export, such as
+    /// `NTDLL.RtlGetVersion`. Returns an error if the export is not
+    /// forwarded.
+    pub fn get_forwarder<const CAP: usize>(
+        &self,
+        process: &Process,
+    ) -> Result<ArrayCString<CAP>, Error> {
+        if !self.is_forwarded {
+            return Err(Error {});
+        }
+        process.read(self.module_address + self.rva)
+    }
+
+    /// Resolves the address of the export. Forwarded exports are followed into
+    /// the modules they refer to, which need to be loaded into the process.
+    /// Returns [`None`] if a forwarder can't be resolved or if there are more
+    /// than 8 forwarders in a row.
+    pub fn resolve(&self, process: &Process) -> Option<Address> {
+        if !self.is_forwarded {
+            return Some(self.module_address + self.rva);
+        }
+        let mut forwarder = self.get_forwarder::<CSTR>(process).ok()?;
+        for _ in 0..MAX_FORWARDER_DEPTH {
+            let export = follow_forwarder(process, &forwarder)?;
+            if !export.is_forwarded {
+                return Some(export.module_address + export.rva);
+            }
+            forwarder = export.get_forwarder(process).ok()?;
+        }
+        None
+    }
+}
+
+fn read_export_directory(
+    process: &Process,
+    module_address: Address,
+) -> Option<(DataDirectory, ExportDirectory)> {
+    let directory = read_data_directory(process, module_address, DataDirectory::EXPORT_TABLE)?;
+    let export_directory = process
+        .read::<ExportDirectory>(module_address + directory.virtual_address)
+        .ok()?;
+    Some((directory, export_directory))
+}
+
+/// Iterates over the named exports of a module (`exe` or `dll`). Returns an
+/// empty iterator if the module doesn't export anything. Exports that are only
+/// exported by their ordinal can be looked up with
+/// [`export_by_ordinal`].
+pub fn exports(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> impl Iterator<Item = Export> + '_ {
+    let module_address: Address = module_address.into();
+    let directories = read_export_directory(process, module_address);
+    let number_of_names = directories.map_or(0, |(_, e)| e.number_of_names);
+
+    (0..number_of_names).filter_map(move |i| {
+        let (directory, export_directory) = directories?;
+        let name_rva = process
+            .read::<u32>(module_address + export_directory.address_of_names + i.wrapping_mul(4))
+            .ok()?;
+        let index = process
+            .read::<u16>(
+                module_address + export_directory.address_of_name_ordinals + i.wrapping_mul(2),
+            )
+            .ok()? as u32;
+        read_export(
+            process,
+            module_address,
+            directory,
+            export_directory,
+            index,
+            name_rva,
+        )
+    })
+}
+
+/// Looks up an export of a module (`exe` or `dll`) by its ordinal.
+pub fn export_by_ordinal(
+    process: &Process,
+    module_address: impl Into<Address>,
+    ordinal: u32,
+) -> Option<Export> {
+    let module_address: Address = module_address.into();
+    let (directory, export_directory) = read_export_directory(process, module_address)?;
+    let index = ordinal.checked_sub(export_directory.base)?;
+    read_export(
+        process,
+        module_address,
+        directory,
+        export_directory,
+        index,
+        0,
+    )
+}
+
+/// Looks up the address of an export of a module (`exe` or `dll`) by its name.
+/// Forwarded exports are resolved into the modules they refer to. Names that
+/// are longer than 127 bytes are not supported.
+pub fn find_export(
+    process: &Process,
+    module_address: impl Into<Address>,
+    name: &str,
+) -> Option<Address> {
+    exports(process, module_address)
+        .find(|export| {
+            export
+                .get_name::<CSTR>(process)
+                .is_ok_and(|n| n.matches(name))
+        })?
+        .resolve(process)
+}
+
+fn read_export(
+    process: &Process,
+    module_address: Address,
+    directory: DataDirectory,
+    export_directory: ExportDirectory,
+    index: u32,
+    name_rva: u32,
+) -> Option<Export> {
+    if index >= export_directory.number_of_functions {
+        return None;
+    }
+    let rva = process
+        .read::<u32>(
+            module_address
+                + export_directory
+                    .address_of_functions
+                    .wrapping_add(index.wrapping_mul(4)),
+        )
+        .ok()?;
+    Some(Export {
+        rva,
+        ordinal: export_directory.base.wrapping_add(index),
+        is_forwarded: directory.contains(rva),
+        module_address,
+        name_rva,
+    })
+}
+
+fn follow_forwarder(process: &Process, forwarder: &ArrayCString<CSTR>) -> Option<Export> {
+    let forwarder = forwarder.validate_utf8().ok()?;
+    let (module_name, function) = forwarder.rsplit_once('.')?;
+
+    let mut file_name = ArrayString::<CSTR>::new();
+    file_name.try_push_str(module_name).ok()?;
+    file_name.try_push_str(".dll").ok()?;
+    let module_address = process.get_module_address(&file_name).or_else(|_| {
+        file_name.make_ascii_lowercase();
+        process.get_module_address(&file_name)
+    });
+    let module_address = module_address.ok()?;
+
+    if let Some(ordinal) = function.strip_prefix('#') {
+        export_by_ordinal(process, module_address, ordinal.parse().ok()?)
+    } else {
+        exports(process, module_address).find(|export| {
+            export
+                .get_name::<CSTR>(process)
+                .is_ok_and(|n| n.matches(function))
+        })
+    }
+}
+
+/// The maximum amount of functions that are read from the import lookup table
+/// of a single DLL. This prevents reading endlessly from corrupted tables.
+const MAX_IMPORTS_PER_DLL: u32 = 0x10000;
+
+/// A function imported by a module.
+#[derive(Copy, Clone)]
+pub enum ImportedFunction {
+    /// The function is imported by its name.
+    Name(ArrayCString<128>),
+    /// The function is imported by its ordinal.
+    Ordinal(u16),
+}
+
+/// An entry of the import address table (IAT) of a module. Once the module is
+/// loaded, the IAT slot contains the address of the imported function.
+#[derive(Copy, Clone)]
+pub struct Import {
+    /// The name of the DLL that the function is imported from.
+    pub dll_name: ArrayCString<64>,
+    /// The function that is imported.
+    pub function: ImportedFunction,
+    /// The address of the slot in the import address table that holds the
+    /// address of the imported function.
+    pub iat_address: Address,
+}
+
+/// Iterates over the functions that a module (`exe` or `dll`) imports from
+/// other DLLs. Returns an empty iterator if the module doesn't import
+/// anything.
+pub fn imports(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> impl Iterator<Item = Import> + '_ {
+    let module_address: Address = module_address.into();
+    let pointer_size = read_pointer_size(process, module_address);
+    let directory = read_data_directory(process, module_address, DataDirectory::IMPORT_TABLE);
+    let count = directory.map_or(0, |d| d.size / mem::size_of::<ImportDescriptor>() as u32);
+
+    (0..count)
+        .map_while(move |i| {
+            let descriptor = process
+                .read::<ImportDescriptor>(
+                    module_address
+                        + directory?.virtual_address
+                        + i * mem::size_of::<ImportDescriptor>() as u32,
+                )
+                .ok()?;
+            // The table is terminated by a descriptor that is all zeroes.
+            if descriptor.name == 0 {
+                None
+            } else {
+                Some(descriptor)
+            }
+        })
+        .flat_map(move |descriptor| {
+            let dll_name = process
+                .read::<ArrayCString<64>>(module_address + descriptor.name)
+                .unwrap_or_default();
+            // The import lookup table is optional, but the import address
+            // table contains the same entries before the module is bound.
+            let lookup_table = match descriptor.original_first_thunk {
+                0 => descriptor.first_thunk,
+                rva => rva,
+            };
+
+            (0..MAX_IMPORTS_PER_DLL).map_while(move |i| {
+                let pointer_size = pointer_size?;
+                let offset = i * pointer_size as u32;
+                let entry = process
+                    .read_pointer(module_address + lookup_table + offset, pointer_size)
+                    .ok()?
+                    .value();
+                if entry == 0 {
+                    return None;
+                }
+                let ordinal_flag = match pointer_size {
+                    PointerSize::Bit64 => 1 << 63,
+                    _ => 1 << 31,
+                };
+                let function = if entry & ordinal_flag != 0 {
+                    ImportedFunction::Ordinal(entry as u16)
+                } else {
+                    // The name is preceded by a 2 byte hint.
+                    ImportedFunction::Name(
+                        process
+                            .read(module_address + (entry as u32).wrapping_add(2))
+                            .ok()?,
+                    )
+                };
+                Some(Import {
+                    dll_name,
+                    function,
+                    iat_address: module_address + descriptor.first_thunk + offset,
+                })
+            })
+        })
+}
+
+/// Looks up the address of the import address table (IAT) slot for a function
+/// that a module (`exe` or `dll`) imports from the given DLL. The name of the
+/// DLL is compared case-insensitively.
+pub fn find_import(
+    process: &Process,
+    module_address: impl Into<Address>,
+    dll_name: &str,
+    function_name: &str,
+) -> Option<Address> {
+    imports(process, module_address)
+        .find(|import| {
+            import
+                .dll_name
+                .as_bytes()
+                .eq_ignore_ascii_case(dll_name.as_bytes())
+                && matches!(&import.function, ImportedFunction::Name(name) if name.matches(function_name))
+        })
+        .map(|import| import.iat_address)
+}
+
+/// A section of a module (`exe` or `dll`), such as `.text` or `.data`.
+#[derive(Copy, Clone)]
+pub struct Section {
+    /// The name of the section. Names that are exactly 8 bytes long are not
+    /// nul-terminated.
+    pub name: ArrayCString<8>,
+    /// The address of the section relative to the start of the module.
+    pub virtual_address: u32,
+    /// The size of the section when it's loaded into memory.
+    pub virtual_size: u32,
+    /// The size of the section in the file on disk.
+    pub raw_size: u32,
+    /// The characteristics of the section.
+    pub characteristics: SectionCharacteristics,
+}
+
+impl Section {
+    /// Returns the address and size of the section in the process's memory
+    /// based on the address of the module that it belongs to.
+    pub fn range(&self, module_address: impl Into<Address>) -> (Address, u64) {
+        (
+            module_address.into() + self.virtual_address,
+            self.virtual_size as u64,
+        )
+    }
+}
+
+/// The characteristics of a [`Section`].
+///
+/// [Microsoft
+/// Documentation](https://learn.microsoft.com/en-us/windows/win32/debug/pe-format#section-flags)
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SectionCharacteristics(u32);
+
+impl fmt::Debug for SectionCharacteristics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SectionCharacteristics({:#x})", self.0)
+    }
+}
+
+impl SectionCharacteristics {
+    /// The section contains executable code.
+    pub const CNT_CODE: Self = Self(0x00000020);
+    /// The section contains initialized data.
+    pub const CNT_INITIALIZED_DATA: Self = Self(0x00000040);
+    /// The section contains uninitialized data.
+    pub const CNT_UNINITIALIZED_DATA: Self = Self(0x00000080);
+    /// The section can be discarded as needed.
+    pub const MEM_DISCARDABLE: Self = Self(0x02000000);
+    /// The section cannot be cached.
+    pub const MEM_NOT_CACHED: Self = Self(0x04000000);
+    /// The section is not pageable.
+    pub const MEM_NOT_PAGED: Self = Self(0x08000000);
+    /// The section can be shared in memory.
+    pub const MEM_SHARED: Self = Self(0x10000000);
+    /// The section can be executed as code.
+    pub const MEM_EXECUTE: Self = Self(0x20000000);
+    /// The section can be read.
+    pub const MEM_READ: Self = Self(0x40000000);
+    /// The section can be written to.
+    pub const MEM_WRITE: Self = Self(0x80000000);
+
+    /// Creates the characteristics from the raw bits.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bits of the characteristics.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Checks whether all of the characteristics in `other` are set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for SectionCharacteristics {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Iterates over the sections of a module (`exe` or `dll`). The iteration stops
+/// early if a section header can't be read.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{file_format::pe, Process};
+/// # fn example(process: &Process, module_address: asr::Address) {
+/// let executable_sections = pe::sections(process, module_address).filter(|section| {
+///     section
+///         .characteristics
+///         .contains(pe::SectionCharacteristics::MEM_EXECUTE)
+/// });
+/// # }
+/// ```
+pub fn sections(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> impl Iterator<Item = Section> + '_ {
+    let module_address: Address = module_address.into();
+    let headers = read_coff_header(process, module_address);
+    let number_of_sections = headers.map_or(0, |(coff_header, _)| coff_header.number_of_sections);
+
+    (0..number_of_sections).map_while(move |i| {
+        let (coff_header, coff_header_address) = headers?;
+        let section_table_address = coff_header_address
+            + mem::size_of::<COFFHeader>() as u64
+            + coff_header.size_of_optional_header;
+        let header = process
+            .read::<SectionHeader>(
+                section_table_address + i as u64 * mem::size_of::<SectionHeader>() as u64,
+            )
+            .ok()?;
+        Some(Section {
+            name: header.name,
+            virtual_address: header.virtual_address,
+            virtual_size: header.virtual_size,
+            raw_size: header.size_of_raw_data,
+            characteristics: SectionCharacteristics(header.characteristics),
+        })
+    })
+}
+
+/// Looks up a section of a module (`exe` or `dll`) by its name, such as
+/// `.text`.
+pub fn section(
+    process: &Process,
+    module_address: impl Into<Address>,
+    name: &str,
+) -> Option<Section> {
+    sections(process, module_address).find(|section| section.name.matches(name))
+}
+
+const RT_VERSION: u32 = 16;
+const VS_FIXEDFILEINFO_SIGNATURE: u32 = 0xFEEF04BD;
+
+/// Reads the file version of a module (`exe` or `dll`) from its version
+/// resource. This is the version that is shown in the properties of the file
+/// in the Windows Explorer. The four components are returned in the order
+/// major, minor, build and revision. This is often the most reliable way to
+/// detect which version of a game is running.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{file_format::pe, Process};
+/// # fn example(process: &Process, module_address: asr::Address) {
+/// match pe::file_version(process, module_address) {
+///     Some((1, 0, 2, _)) => { /* Version 1.0.2 */ }
+///     _ => { /* Unknown version */ }
+/// }
+/// # }
+/// ```
+pub fn file_version(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> Option<(u16, u16, u16, u16)> {
+    let info = read_fixed_file_info(process, module_address.into())?;
+    Some(split_version(info.file_version_ms, info.file_version_ls))
+}
+
+/// Reads the product version of a module (`exe` or `dll`) from its version
+/// resource. The four components are returned in the order major, minor, build
+/// and revision.
+pub fn product_version(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> Option<(u16, u16, u16, u16)> {
+    let info = read_fixed_file_info(process, module_address.into())?;
+    Some(split_version(
+        info.product_version_ms,
+        info.product_version_ls,
+    ))
+}
+
+const fn split_version(ms: u32, ls: u32) -> (u16, u16, u16, u16) {
+    ((ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16)
+}
+
+fn read_fixed_file_info(process: &Process, module_address: Address) -> Option<FixedFileInfo> {
+    let resources = read_data_directory(process, module_address, DataDirectory::RESOURCE_TABLE)?;
+    let resources_address = module_address + resources.virtual_address;
+
+    // The resource tree has three levels: type, name and language. We look for
+    // the version type and then simply pick the first name and language.
+    let names =
+        find_resource_entry(process, resources_address, 0, Some(RT_VERSION))?.subdirectory()?;
+    let languages = find_resource_entry(process, resources_address, names, None)?.subdirectory()?;
+    let language = find_resource_entry(process, resources_address, languages, None)?;
+    if language.subdirectory().is_some() {
+        return None;
+    }
+
+    let data_entry = process
+        .read::<ResourceDataEntry>(resources_address + language.offset_to_data)
+        .ok()?;
+    let version_info_address = module_address + data_entry.offset_to_data;
+
+    // VS_VERSIONINFO starts with its length, the length of the value and its
+    // type, followed by the UTF-16 key and padding to a 32-bit boundary.
+    let [_, value_length, _] = process.read::<[u16; 3]>(version_info_address).ok()?;
+    let key = process.read::<[u16; 16]>(version_info_address + 6).ok()?;
+    if value_length as usize != mem::size_of::<FixedFileInfo>()
+        || !key.iter().copied().eq("VS_VERSION_INFO\0".encode_utf16())
+    {
+        return None;
+    }
+
+    let info = process
+        .read::<FixedFileInfo>(version_info_address + 40)
+        .ok()?;
+    if info.signature != VS_FIXEDFILEINFO_SIGNATURE {
+        return None;
+    }
+    Some(info)
+}
+
+/// Looks up an entry of the resource directory at the given offset. If no ID
+/// is provided, the first entry is returned.
+fn find_resource_entry(
+    process: &Process,
+    resources_address: Address,
+    directory_offset: u32,
+    id: Option<u32>,
+) -> Option<ResourceDirectoryEntry> {
+    let directory_address = resources_address + directory_offset;
+    let directory = process.read::<ResourceDirectory>(directory_address).ok()?;
+    let entries_address = directory_address + mem::size_of::<ResourceDirectory>() as u64;
+    let read_entry = |index: u32| {
+        process
+            .read::<ResourceDirectoryEntry>(
+                entries_address + index as u64 * mem::size_of::<ResourceDirectoryEntry>() as u64,
+            )
+            .ok()
+    };
+
+    match id {
+        // The named entries always come first, so the entries with an ID
+        // start after them.
+        Some(id) => {
+            let start = directory.number_of_named_entries as u32;
+            (start..start + directory.number_of_id_entries as u32)
+                .map_while(read_entry)
+                .find(|entry| entry.name_or_id == id)
+        }
+        None => {
+            if directory.number_of_named_entries == 0 && directory.number_of_id_entries == 0 {
+                return None;
+            }
+            read_entry(0)
+        }
+    }
+}
+
+/// The information about the PDB file that belongs to a module. This uniquely
+/// identifies the build of the module, which makes it a reliable way to match
+/// the module to known offsets.
+#[derive(Copy, Clone)]
+pub struct PdbInfo {
+    /// The GUID of the PDB file, in the byte order that it's stored in.
+    pub guid: [u8; 16],
+    /// The age of the PDB file. This is incremented every time the PDB file is
+    /// updated without the GUID changing.
+    pub age: u32,
+    /// The path to the PDB file at the time the module was built.
+    pub path: ArrayCString<260>,
+}
+
+/// Reads the information about the PDB file of a module (`exe` or `dll`) from
+/// the CodeView entry of its debug directory. Returns [`None`] if the module
+/// has no debug directory or if it doesn't contain a CodeView entry in the
+/// `RSDS` format.
+pub fn pdb_info(process: &Process, module_address: impl Into<Address>) -> Option<PdbInfo> {
+    let module_address = module_address.into();
+    let debug = read_data_directory(process, module_address, DataDirectory::DEBUG)?;
+    let entry_size = mem::size_of::<DebugDirectory>() as u32;
+
+    (0..debug.size / entry_size)
+        .map_while(|i| {
+            process
+                .read::<DebugDirectory>(module_address + debug.virtual_address + i * entry_size)
+                .ok()
+        })
+        .filter(|entry| entry.ty == DebugDirectory::TYPE_CODEVIEW && entry.address_of_raw_data != 0)
+        .find_map(|entry| {
+            // Only the bytes that belong to the record are read, as a short
+            // record may be right at the end of the readable memory.
+            let mut record = [0; CODEVIEW_RECORD_SIZE];
+            let record = &mut record[..(entry.size_of_data as usize).min(CODEVIEW_RECORD_SIZE)];
+            process
+                .read_into_buf(module_address + entry.address_of_raw_data, record)
+                .ok()?;
+            PdbInfo::parse(record)
+        })
+}
+
+/// The size of the header of a CodeView record in the `RSDS` format, followed
+/// by the longest path that is read.
+const CODEVIEW_RECORD_SIZE: usize = 24 + 260;
+
+impl PdbInfo {
+    /// Parses a CodeView record in the `RSDS` format. The path is truncated if
+    /// the record ends before its nul terminator.
+    fn parse(record: &[u8]) -> Option<Self> {
+        let (header, path) = record.split_at_checked(24)?;
+        let (signature, header) = header.split_at(4);
+        let (guid, age) = header.split_at(16);
+        if signature != b"RSDS" {
+            return None;
+        }
+
+        let mut path_buf = [0; 260];
+        let len = path.len().min(path_buf.len());
+        path_buf[..len].copy_from_slice(&path[..len]);
+
+        Some(Self {
+            guid: guid.try_into().ok()?,
+            age: u32::from_le_bytes(age.try_into().ok()?),
+            path: bytemuck::cast(path_buf),
+        })
+    }
+}
+
+const MAX_TLS_CALLBACKS: u64 = 64;
+
+/// Reads the address of the TLS directory of a module (`exe` or `dll`) along
+/// with the pointer size of the module. The fields of the TLS directory are
+/// all pointer sized.
+fn read_tls_directory(
+    process: &Process,
+    module_address: Address,
+) -> Option<(Address, PointerSize)> {
+    let tls = read_data_directory(process, module_address, DataDirectory::TLS_TABLE)?;
+    let pointer_size = read_pointer_size(process, module_address)?;
+    Some((module_address + tls.virtual_address, pointer_size))
+}
+
+/// Iterates over the TLS callbacks of a module (`exe` or `dll`). These are
+/// functions that get called whenever a thread is created or destroyed, as well
+/// as when the module is loaded. At most 64 callbacks are returned, so a
+/// corrupted callback array can't cause the iteration to continue forever.
+pub fn tls_callbacks(
+    process: &Process,
+    module_address: impl Into<Address>,
+) -> impl Iterator<Item = Address> + '_ {
+    let callbacks = read_tls_directory(process, module_address.into()).and_then(
+        |(tls_directory, pointer_size)| {
+            let callbacks = process
+                .read_pointer(tls_directory + 3 * pointer_size as u64, pointer_size)
+                .ok()?;
+            Some((callbacks, pointer_size))
+        },
+    );
+
+    (0..MAX_TLS_CALLBACKS)
+        .map_while(move |i| {
+            let (callbacks, pointer_size) = callbacks?;
+            process
+                .read_pointer(callbacks + i * pointer_size as u64, pointer_size)
+                .ok()
+        })
+        .take_while(|callback| !callback.is_null())
+}
+
+/// Reads the address of the TLS index of a module (`exe` or `dll`). The loader
+/// stores the index of the module's TLS slot at this address. Together with the
+/// thread's TLS array, this allows locating the module's thread local storage
+/// of a specific thread.
+pub fn tls_index_address(process: &Process, module_address: impl Into<Address>) -> Option<Address> {
+    let (tls_directory, pointer_size) = read_tls_directory(process, module_address.into())?;
+    process
+        .read_pointer(tls_directory + 2 * pointer_size as u64, pointer_size)
+        .ok()
+        .filter(|address| !address.is_null())
+}
+
+const PAGE_SIZE: u64 = 0x1000;
+
+/// Calculates the size of a module (`exe` or `dll`) as it's mapped into
+/// memory, based on the extent of its sections. Packers and DRM solutions such
+/// as Steam DRM or Themida may report a size of the image that is much smaller
+/// than the memory the module actually occupies, which this function is not
+/// affected by. The size is rounded up to the next page boundary.
+///
+/// Note that a section's memory isn't necessarily committed in its entirety.
+/// Use [`committed_size`] if the entire range needs to be readable.
+pub fn mapped_size(process: &Process, module_address: impl Into<Address>) -> Option<u64> {
+    let end = sections(process, module_address)
+        .map(|section| {
+            // The loader maps the raw data if the virtual size is missing.
+            let size = if section.virtual_size != 0 {
+                section.virtual_size
+            } else {
+                section.raw_size
+            };
+            section.virtual_address as u64 + size as u64
+        })
+        .max()?;
+    Some(end.div_ceil(PAGE_SIZE) * PAGE_SIZE)
+}
+
+/// Calculates the size of a module (`exe` or `dll`) like [`mapped_size`], but
+/// additionally clamps it to the memory that is actually committed. The size
+/// ends at the first page after the start of the module that isn't part of any
+/// of the process's [`memory_ranges`](Process::memory_ranges). This is the size
+/// to use for scanning the module, such as with
+/// [`Signature::scan_process_range`](crate::signature::Signature::scan_process_range),
+/// if [`get_module_range`](Process::get_module_range) reports a size that is
+/// too small.
+pub fn committed_size(process: &Process, module_address: impl Into<Address>) -> Option<u64> {
+    let module_address: Address = module_address.into();
+    let module_end = module_address.value() + mapped_size(process, module_address)?;
+
+    // The memory ranges are not guaranteed to be sorted, so we keep looking
+    // for the range that continues where the previous one ended.
+    let mut end = module_address.value();
+    while end < module_end {
+        let Some(range_end) = process.memory_ranges().find_map(|range| {
+            let (address, size) = range.range().ok()?;
+            let range_end = address.value() + size;
+            (address.value() <= end && end < range_end).then_some(range_end)
+        }) else {
+            break;
+        };
+        end = range_end;
+    }
+
+    let size = end.min(module_end) - module_address.value();
+    if size == 0 {
+        return None;
+    }
+    Some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codeview_record(signature: &[u8; 4], path: &[u8]) -> ([u8; CODEVIEW_RECORD_SIZE], usize) {
+        let mut record = [0; CODEVIEW_RECORD_SIZE];
+        record[..4].copy_from_slice(signature);
+        for (i, byte) in record[4..20].iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        record[20..24].copy_from_slice(&3u32.to_le_bytes());
+        record[24..24 + path.len()].copy_from_slice(path);
+        (record, 24 + path.len())
+    }
+
+    #[test]
+    fn pdb_info_rsds() {
+        let (record, len) = codeview_record(b"RSDS", b"C:\\build\\Game.pdb\0");
+        let info = PdbInfo::parse(&record[..len]).unwrap();
+        assert_eq!(
+            info.guid,
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+        );
+        assert_eq!(info.age, 3);
+        assert!(info.path.matches("C:\\build\\Game.pdb"));
+    }
+
+    #[test]
+    fn pdb_info_ignores_trailing_bytes() {
+        let (record, _) = codeview_record(b"RSDS", b"Game.pdb\0garbage");
+        let info = PdbInfo::parse(&record).unwrap();
+        assert!(info.path.matches("Game.pdb"));
+    }
+
+    #[test]
+    fn pdb_info_unterminated_path() {
+        let (record, len) = codeview_record(b"RSDS", b"Game.pdb");
+        let info = PdbInfo::parse(&record[..len]).unwrap();
+        assert!(info.path.matches("Game.pdb"));
+    }
+
+    #[test]
+    fn pdb_info_rejects_other_formats() {
+        let (record, len) = codeview_record(b"NB10", b"Game.pdb\0");
+        assert!(PdbInfo::parse(&record[..len]).is_none());
+    }
+
+    #[test]
+    fn pdb_info_rejects_truncated_records() {
+        let (record, _) = codeview_record(b"RSDS", b"");
+        assert!(PdbInfo::parse(&record[..23]).is_none());
+        assert!(PdbInfo::parse(&[]).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The result of [`select2`], which is either the output of the first or the
+/// second future.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Either<A, B> {
+    /// The first future resolved first.
+    Left(A),
+    /// The second future resolved first.
+    Right(B),
+}
+
+/// A future that polls two futures and resolves as soon as either of them
+/// resolves. Check [`select2`] for more information.
+#[must_use = "You need to await this future."]
+pub struct Select2<A, B> {
+    a: Option<A>,
+    b: Option<B>,
+}
+
+impl<A: Future, B: Future> Future for Select2<A, B> {
+    type Output = Either<A::Output, B::Output>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: We are simply projecting the Pin to the inner futures. They
+        // are only ever dropped in place and never moved out.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let (Some(a), Some(b)) = (&mut this.a, &mut this.b) else {
+                panic!("The future was polled after it resolved.");
+            };
+            let output = if let Poll::Ready(output) = Pin::new_unchecked(a).poll(cx) {
+                Either::Left(output)
+            } else if let Poll::Ready(output) = Pin::new_unchecked(b).poll(cx) {
+                Either::Right(output)
+            } else {
+                return Poll::Pending;
+            };
+            this.a = None;
+            this.b = None;
+            Poll::Ready(output)
+        }
+    }
+}
+
+/// Polls two futures on every tick and resolves with the output of whichever
+/// resolves first. The first future is polled first, so it wins if both
+/// resolve on the same tick. The other future is dropped immediately, so it
+/// isn't polled again and any cleanup it does on drop happens right away.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{future::{select2, Either}, Process};
+/// # async fn example(process: &Process) {
+/// match select2(
+///     process.wait_module_range("GameAssembly.dll"),
+///     process.wait_module_range("UnityPlayer.dll"),
+/// )
+/// .await
+/// {
+///     Either::Left((address, size)) => {
+///         // TODO: The game uses IL2CPP.
+///     }
+///     Either::Right((address, size)) => {
+///         // TODO: The game uses Mono.
+///     }
+/// }
+/// # }
+/// ```
+///
+/// This can also be used to give up on a signature scan after a certain
+/// amount of ticks:
+///
+/// ```no_run
+/// # use asr::{future::{next_tick, select2, Either}, signature::Signature, Address, Process};
+/// # async fn example(process: &Process, module: (Address, u64)) {
+/// static SIG: Signature<4> = Signature::new("DE AD BE EF");
+/// let give_up = async {
+///     for _ in 0..600 {
+///         next_tick().await;
+///     }
+/// };
+/// match select2(SIG.wait_scan_process_range(process, module), give_up).await {
+///     Either::Left(address) => {
+///         // TODO: Do something with the address.
+///     }
+///     Either::Right(()) => {
+///         // TODO: The signature wasn't found in time.
+///     }
+/// }
+/// # }
+/// ```
+///
+/// Or to stop waiting for the game once its launcher closes:
+///
+/// ```no_run
+/// # use asr::{future::{next_tick, select2, Either}, Process};
+/// # async fn example(launcher: &Process) {
+/// let launcher_closed = launcher.until_closes(async {
+///     loop {
+///         next_tick().await;
+///     }
+/// });
+/// if let Either::Right(game) = select2(launcher_closed, Process::wait_attach("Game.exe")).await {
+///     // TODO: Do something with the game.
+/// }
+/// # }
+/// ```
+pub const fn select2<A: Future, B: Future>(a: A, b: B) -> Select2<A, B> {
+    Select2 {
+        a: Some(a),
+        b: Some(b),
+    }
+}
+
+enum MaybeDone<F: Future> {
+    Pending(F),
+    Done(F::Output),
+    Taken,
+}
+
+impl<F: Future> MaybeDone<F> {
+    /// Polls the future if it hasn't resolved yet. Returns [`true`] if the
+    /// output is available.
+    ///
+    /// # Safety
+    ///
+    /// The value needs to be pinned.
+    unsafe fn poll(&mut self, cx: &mut Context<'_>) -> bool {
+        if let MaybeDone::Pending(future) = self {
+            match Pin::new_unchecked(future).poll(cx) {
+                Poll::Ready(output) => *self = MaybeDone::Done(output),
+                Poll::Pending => return false,
+            }
+        }
+        true
+    }
+
+    fn take(&mut self) -> F::Output {
+        match core::mem::replace(self, MaybeDone::Taken) {
+            MaybeDone::Done(output) => output,
+            _ => panic!("The future was polled after it resolved."),
+        }
+    }
+}
+
+/// A future that polls two futures until both of them resolved. Check
+/// [`join`] for more information.
+#[must_use = "You need to await this future."]
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: We are simply projecting the Pin to the inner futures. They
+        // are only ever dropped in place and never moved out. Only their
+        // outputs are moved out.
+        unsafe {
+            let this = self.get_unchecked_mut();
+            let a_done = this.a.poll(cx);
+            let b_done = this.b.poll(cx);
+            if a_done && b_done {
+                Poll::Ready((this.a.take(), this.b.take()))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Polls two futures on every tick until both of them resolved and then
+/// resolves with both of their outputs. A future that already resolved isn't
+/// polled again.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{future::join, Process};
+/// # async fn example(process: &Process) {
+/// let ((game, _), (player, _)) = join(
+///     process.wait_module_range("Game.dll"),
+///     process.wait_module_range("Player.dll"),
+/// )
+/// .await;
+/// # }
+/// ```
+pub const fn join<A: Future, B: Future>(a: A, b: B) -> Join<A, B> {
+    Join {
+        a: MaybeDone::Pending(a),
+        b: MaybeDone::Pending(b),
+    }
+}
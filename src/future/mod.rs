@@ -39,6 +39,19 @@
 //! # }
 //! ```
 //!
+//! Looking for processes every tick can be expensive if the game isn't running
+//! for a long time. The [`retry_with_backoff`] function waits longer and longer
+//! between each try instead:
+//!
+//! ```no_run
+//! # use asr::{Process, future::{retry_with_backoff, BackoffOptions}};
+//! # async fn example() {
+//! let process = retry_with_backoff(BackoffOptions::new(), || {
+//!    ["a.exe", "b.exe"].into_iter().find_map(Process::attach)
+//! }).await.unwrap();
+//! # }
+//! ```
+//!
 //! # Example
 //!
 //! Here is a full example of how an auto splitter could look like using the
@@ -110,9 +123,12 @@ use core::{
 };
 
 #[cfg(feature = "signature")]
-use crate::signature::Signature;
+use crate::signature::{Signature, SignatureMatches};
 use crate::{Address, Process};
 
+mod combinators;
+pub use self::combinators::*;
+
 #[cfg(target_os = "wasi")]
 mod time;
 #[cfg(target_os = "wasi")]
@@ -141,6 +157,23 @@ impl Future for NextTick {
     }
 }
 
+/// A future that yields back to the runtime for a certain amount of ticks.
+#[must_use = "You need to await this future."]
+pub struct SleepTicks(u64);
+
+impl Future for SleepTicks {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            Poll::Ready(())
+        } else {
+            self.0 -= 1;
+            Poll::Pending
+        }
+    }
+}
+
 /// A future that retries the given function until it returns [`Some`], yielding
 /// back to the runtime between each call.
 #[must_use = "You need to await this future."]
@@ -178,6 +211,129 @@ pub const fn next_tick() -> NextTick {
     NextTick(false)
 }
 
+/// Options that control how often [`retry_with_backoff`] retries. The delays
+/// are specified in ticks, so they scale with the tick rate. A delay is the
+/// amount of ticks that are skipped between two tries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BackoffOptions {
+    /// The amount of ticks to skip after the first try failed. The default is
+    /// 1 tick.
+    pub initial_delay: u64,
+    /// The factor the delay is multiplied by after every failed try. The
+    /// default is 2.
+    pub multiplier: u64,
+    /// The maximum amount of ticks to skip between two tries. The default is
+    /// 120 ticks, which is a second at the default tick rate.
+    pub max_delay: u64,
+    /// The maximum amount of tries before giving up. The default is to never
+    /// give up.
+    pub max_attempts: Option<u64>,
+}
+
+impl BackoffOptions {
+    /// Creates the default options.
+    pub const fn new() -> Self {
+        Self {
+            initial_delay: 1,
+            multiplier: 2,
+            max_delay: 120,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Default for BackoffOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A future that retries the given function with an increasing delay until it
+/// returns [`Some`] or the maximum amount of tries is reached. Check
+/// [`retry_with_backoff`] for more information.
+#[must_use = "You need to await this future."]
+pub struct RetryWithBackoff<F> {
+    f: F,
+    options: BackoffOptions,
+    delay: u64,
+    skip: u64,
+    attempts: u64,
+}
+
+impl<O: IntoOption, F: FnMut() -> O + Unpin> Future for RetryWithBackoff<F> {
+    type Output = Option<O::T>;
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if this.skip != 0 {
+            this.skip -= 1;
+            return Poll::Pending;
+        }
+        if let Some(t) = (this.f)().into_option() {
+            return Poll::Ready(Some(t));
+        }
+        this.attempts += 1;
+        if this
+            .options
+            .max_attempts
+            .is_some_and(|max| this.attempts >= max)
+        {
+            return Poll::Ready(None);
+        }
+        this.skip = this.delay;
+        this.delay = this
+            .delay
+            .saturating_mul(this.options.multiplier)
+            .min(this.options.max_delay);
+        Poll::Pending
+    }
+}
+
+/// A future that retries the given function every tick until it returns
+/// [`Some`] or the deadline is reached. Check [`retry_until`] for more
+/// information.
+#[must_use = "You need to await this future."]
+pub struct RetryUntil<F> {
+    f: F,
+    ticks_left: u64,
+}
+
+impl<O: IntoOption, F: FnMut() -> O + Unpin> Future for RetryUntil<F> {
+    type Output = Option<O::T>;
+
+    fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Some(t) = (self.f)().into_option() {
+            return Poll::Ready(Some(t));
+        }
+        if self.ticks_left <= 1 {
+            return Poll::Ready(None);
+        }
+        self.ticks_left -= 1;
+        Poll::Pending
+    }
+}
+
+/// Yields back to the runtime for the given amount of ticks and then continues.
+/// Unlike `sleep`, which waits for an amount of time, this
+/// depends on the tick rate. Sleeping for a single tick is the same as
+/// [`next_tick`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::future::sleep_ticks;
+/// # async fn example() {
+/// loop {
+///     // TODO: Do something every 10 ticks.
+///     sleep_ticks(10).await;
+/// }
+/// # }
+/// ```
+pub const fn sleep_ticks(ticks: u64) -> SleepTicks {
+    SleepTicks(ticks)
+}
+
 /// Retries the given function until it returns [`Some`] or [`Ok`], yielding
 /// back to the runtime between each call.
 ///
@@ -209,6 +365,63 @@ pub const fn retry<O: IntoOption, F: FnMut() -> O + Unpin>(f: F) -> Retry<F> {
     Retry { f }
 }
 
+/// Retries the given function until it returns [`Some`] or [`Ok`], waiting
+/// longer and longer between each call. This is useful for expensive checks,
+/// such as looking for a process that may not be running for a long time.
+/// Every failed try multiplies the delay until the maximum delay is reached.
+/// Resolves to [`None`] if the maximum amount of tries is reached. The state
+/// of the backoff belongs to the returned future, so every call starts over
+/// with the initial delay.
+///
+/// The delays are specified in ticks, so they take longer at lower tick rates.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{future::{retry_with_backoff, BackoffOptions}, Process};
+/// # async fn example() {
+/// let process = retry_with_backoff(BackoffOptions::new(), || Process::attach("MyGame.exe"))
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+pub const fn retry_with_backoff<O: IntoOption, F: FnMut() -> O + Unpin>(
+    options: BackoffOptions,
+    f: F,
+) -> RetryWithBackoff<F> {
+    RetryWithBackoff {
+        f,
+        options,
+        delay: options.initial_delay,
+        skip: 0,
+        attempts: 0,
+    }
+}
+
+/// Retries the given function every tick until it returns [`Some`] or [`Ok`].
+/// Resolves to [`None`] if the function didn't succeed within the given
+/// amount of ticks. The function is called at least once.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{future::retry_until, Process};
+/// # async fn example(process: &Process) {
+/// if let Some(module) = retry_until(600, || process.get_module_address("Game.dll")).await {
+///     // TODO: Do something with the module.
+/// }
+/// # }
+/// ```
+pub const fn retry_until<O: IntoOption, F: FnMut() -> O + Unpin>(
+    deadline_ticks: u64,
+    f: F,
+) -> RetryUntil<F> {
+    RetryUntil {
+        f,
+        ticks_left: deadline_ticks,
+    }
+}
+
 /// A trait for types that can be converted into an [`Option`].
 // TODO: Replace this with `Try` once that is stable.
 pub trait IntoOption {
@@ -248,10 +461,56 @@ impl Process {
     }
 
     /// Asynchronously awaits the address and size of a module in the process,
-    /// yielding back to the runtime between each try.
+    /// yielding back to the runtime between each try. This keeps waiting if
+    /// the process closes, so it should usually be awaited within
+    /// [`until_closes`](Self::until_closes).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::Process;
+    /// # async fn example(process: &Process) {
+    /// process
+    ///     .until_closes(async {
+    ///         let (address, size) = process.wait_module_range("GameAssembly.dll").await;
+    ///         // TODO: Do something with the module.
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
     pub async fn wait_module_range(&self, name: &str) -> (Address, u64) {
         retry(|| self.get_module_range(name)).await
     }
+
+    /// Asynchronously awaits the address and size of any of the modules with
+    /// the given names, yielding back to the runtime between each try. The
+    /// names are checked in order, so the first name that is loaded is
+    /// returned along with its address and size. This is useful if the name of
+    /// the module differs between versions or platforms.
+    pub async fn wait_any_module_range<'name>(
+        &self,
+        names: &[&'name str],
+    ) -> (&'name str, Address, u64) {
+        retry(|| {
+            names.iter().find_map(|&name| {
+                let (address, size) = self.get_module_range(name).ok()?;
+                Some((name, address, size))
+            })
+        })
+        .await
+    }
+
+    /// Asynchronously awaits the address and size of a module in the process,
+    /// yielding back to the runtime between each try. Returns [`None`] if the
+    /// module didn't load within the given duration.
+    #[cfg(target_os = "wasi")]
+    pub async fn wait_module_range_timeout(
+        &self,
+        name: &str,
+        duration: core::time::Duration,
+    ) -> Option<(Address, u64)> {
+        timeout(duration, self.wait_module_range(name)).await
+    }
 }
 
 #[cfg(feature = "signature")]
@@ -268,6 +527,56 @@ impl<const N: usize> Signature<N> {
         let addr = addr.into();
         retry(|| self.scan_process_range(process, (addr, len))).await
     }
+
+    /// Asynchronously scans a process for the signature, yielding back to the
+    /// runtime whenever 4 MiB were scanned without finding the signature. This
+    /// prevents a large scan from blocking the auto splitter for too long. If
+    /// the signature is found, the address of the start of the signature is
+    /// returned. Returns [`None`] if the signature isn't found or the process
+    /// closes during the scan. Use
+    /// [`scan_with_options`](Self::scan_with_options) and
+    /// [`next_match`](SignatureMatches::next_match) to configure the amount of
+    /// bytes scanned per tick.
+    pub async fn scan_process_range_async(
+        &self,
+        process: &Process,
+        range: (impl Into<Address>, u64),
+    ) -> Option<Address> {
+        self.scan_iter(process, range).next_match().await
+    }
+}
+
+#[cfg(feature = "signature")]
+impl<const N: usize> SignatureMatches<'_, N> {
+    /// Asynchronously awaits the next match of the signature, yielding back to
+    /// the runtime whenever [`bytes_per_tick`](crate::signature::ScanOptions::bytes_per_tick)
+    /// bytes were scanned without finding a match. The progress is kept
+    /// between ticks. Returns [`None`] if there are no more matches or the
+    /// process closes during the scan.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{signature::Signature, Address, Process};
+    /// # async fn example(process: &Process, module: (Address, u64)) {
+    /// static SIG: Signature<7> = Signature::new("48 8B 05 ?? ?? ?? ??");
+    /// let mut matches = SIG.scan_iter(process, module);
+    /// while let Some(address) = matches.next_match().await {
+    ///     // TODO: Do something with the address.
+    /// }
+    /// # }
+    /// ```
+    pub async fn next_match(&mut self) -> Option<Address> {
+        loop {
+            if !self.process().is_open() {
+                return None;
+            }
+            if let Some(address) = self.next_within_tick() {
+                return address;
+            }
+            next_tick().await;
+        }
+    }
 }
 
 /// A future that executes a future until the process closes.
@@ -363,6 +672,7 @@ macro_rules! async_main {
             if unsafe { FINISHED } {
                 return;
             }
+            $crate::time_util::record_tick();
 
             static VTABLE: RawWakerVTable = RawWakerVTable::new(
                 |_| RawWaker::new(ptr::null(), &VTABLE),
@@ -398,6 +708,7 @@ macro_rules! async_main {
             if unsafe { FINISHED } {
                 return;
             }
+            $crate::time_util::record_tick();
 
             static VTABLE: RawWakerVTable = RawWakerVTable::new(
                 |_| RawWaker::new(ptr::null(), &VTABLE),
@@ -136,7 +136,10 @@ impl<F: Future> Future for Timeout<F> {
 }
 
 /// A future that resolves to [`None`] after a certain amount of time, if the
-/// provided future has not resolved yet.
+/// provided future has not resolved yet. Once the time has passed, the
+/// provided future is not polled anymore. It is dropped along with the
+/// returned future. If the provided future would resolve on the same tick
+/// that the time passes, the timeout takes precedence.
 ///
 /// # Example
 ///
@@ -152,6 +155,16 @@ impl<F: Future> Future for Timeout<F> {
 ///   // the future timed out
 /// }
 /// ```
+///
+/// This can be used to give up on a signature scan after some time:
+///
+/// ```no_run
+/// static SIG: Signature<4> = Signature::new("DE AD BE EF");
+/// let scan = SIG.wait_scan_process_range(&process, module_range);
+/// if let Some(address) = timeout(Duration::from_secs(10), scan).await {
+///     // do something with the address
+/// }
+/// ```
 pub fn timeout<F: Future>(duration: Duration, future: F) -> Timeout<F> {
     Timeout {
         sleep: sleep(duration),
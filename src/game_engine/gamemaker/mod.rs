@@ -0,0 +1,514 @@
+//! Support for games using GameMaker.
+//!
+//! Games exported with the VM and with YYC (YoYo Compiler) both use the same
+//! runner, so the current room and the global variables are stored the same
+//! way for both. Only the 64-bit Windows runners are supported. The
+//! signatures used for finding the runner's globals haven't been verified
+//! against specific runner builds yet, so [`Module::attach`] only accepts
+//! matches whose targets hold the structures that are expected there.
+//!
+//! # Example
+//!
+//! Splitting whenever the room changes only needs a [`Watcher`](crate::watcher::Watcher):
+//!
+//! ```no_run
+//! # async fn example(process: asr::Process) {
+//! use asr::{
+//!     future::next_tick,
+//!     game_engine::gamemaker::{Module, Version},
+//!     timer,
+//!     watcher::Watcher,
+//! };
+//!
+//! let main_module = process.wait_module_range("Game.exe").await;
+//! let module = Module::wait_attach(&process, Version::V2023, main_module).await;
+//!
+//! let mut room = Watcher::<i32>::new();
+//! loop {
+//!     if room.update(module.current_room_id(&process).ok()).is_some_and(|r| r.changed()) {
+//!         timer::split();
+//!     }
+//!     next_tick().await;
+//! }
+//! # }
+//! ```
+
+// References:
+// https://github.com/YoYoGames/GameMaker-HTML5/blob/develop/scripts/yyRoom.js
+// https://github.com/UnderminersTeam/UndertaleModTool/wiki
+
+use core::iter::{self, FusedIterator};
+
+use crate::{
+    file_format::pe, future::retry, signature::Signature, string::ArrayCString, Address, Error,
+    PointerSize, Process,
+};
+
+const CSTR: usize = 128;
+
+/// The maximum amount of elements that are iterated for a single hash map.
+/// This protects against reading garbage if a map is read while it is being
+/// resized.
+const MAX_HASH_MAP_SIZE: u32 = 0x100000;
+
+/// The mask that removes the flags from the kind of an `RValue`.
+const RVALUE_KIND_MASK: u32 = 0x00FF_FFFF;
+
+const KIND_REAL: u32 = 0;
+const KIND_STRING: u32 = 1;
+const KIND_INT32: u32 = 7;
+const KIND_INT64: u32 = 10;
+const KIND_BOOL: u32 = 13;
+
+/// Represents access to a GameMaker game.
+pub struct Module {
+    pointer_size: PointerSize,
+    offsets: &'static Offsets,
+    current_room: Address,
+    room_names: Address,
+    global_instance: Address,
+    variable_names: Address,
+}
+
+impl Module {
+    /// Tries attaching to a GameMaker game. The runner version needs to be
+    /// correct for this function to work. The first room and the global
+    /// instance are only set up once the game has started, so this returns
+    /// [`None`] before that.
+    pub fn attach(
+        process: &Process,
+        version: Version,
+        main_module_range: (Address, u64),
+    ) -> Option<Self> {
+        let pointer_size = pe::MachineType::read(process, main_module_range.0)?.pointer_size()?;
+        let offsets = Offsets::new(version, pointer_size)?;
+
+        // None of the signatures below have been verified against specific
+        // runner builds yet. They are short enough to also match unrelated
+        // code, so every match is resolved and checked for the structure that
+        // is expected at its target, and the first one that fits is used. If
+        // no match fits, the globals are treated as unresolved.
+
+        // The array of room names, which consists of the amount of rooms,
+        // followed by a pointer to the names. The bounds check loads the
+        // amount and the pointer right after each other:
+        // cmp reg, [amount]; jge; mov reg, [names]
+        const ROOM_NAMES: [Signature<15>; 2] = [
+            Signature::new("3B 0D ?? ?? ?? ?? 7D ?? 48 8B 05 ?? ?? ?? ??"),
+            Signature::new("3B 05 ?? ?? ?? ?? 7D ?? 48 8B 0D ?? ?? ?? ??"),
+        ];
+        let room_names = ROOM_NAMES.iter().find_map(|sig| {
+            sig.scan_iter(process, main_module_range).find_map(|addr| {
+                let amount = rip_relative(process, addr + 2)?;
+                let names = rip_relative(process, addr + 11)?;
+                (names == amount + offsets.array_data).then_some(amount)
+            })
+        })?;
+        let room_amount = process.read::<i32>(room_names).ok()?;
+
+        // The index of the room that is currently running. It's `-1` while
+        // the game starts up, which can't be told apart from unrelated data,
+        // so the index needs to resolve to the name of a room.
+        const CURRENT_ROOM: [Signature<10>; 2] = [
+            Signature::new("8B 05 ?? ?? ?? ?? 89 44 24 ??"),
+            Signature::new("89 3D ?? ?? ?? ?? 48 63 C7 48"),
+        ];
+        let current_room =
+            find_rip_relative(process, main_module_range, &CURRENT_ROOM, 2, |addr| {
+                process.read::<i32>(addr).is_ok_and(|room| {
+                    (0..room_amount).contains(&room)
+                        && read_room_name::<CSTR>(process, offsets, pointer_size, room_names, room)
+                            .is_ok_and(|name| is_asset_name(name.as_bytes()))
+                })
+            })?;
+
+        // The pointer to the global instance, which holds the variables of
+        // the `global` object: mov reg, [global_instance]
+        const GLOBAL_INSTANCE: [Signature<14>; 2] = [
+            Signature::new("48 8B 05 ?? ?? ?? ?? 48 8B 48 ?? 48 85 C9"),
+            Signature::new("48 8B 0D ?? ?? ?? ?? 8B D7 E8 ?? ?? ?? ??"),
+        ];
+        let global_instance =
+            find_rip_relative(process, main_module_range, &GLOBAL_INSTANCE, 3, |addr| {
+                process
+                    .read_pointer(addr, pointer_size)
+                    .ok()
+                    .filter(|instance| !instance.is_null())
+                    .and_then(|instance| {
+                        process
+                            .read_pointer(instance + offsets.instance_yyvars, pointer_size)
+                            .ok()
+                    })
+                    .filter(|yyvars| !yyvars.is_null())
+                    .is_some_and(|yyvars| is_hash_map(process, offsets, pointer_size, yyvars))
+            })?;
+
+        // The hash map from the names of variables to their slots, which is
+        // stored in place: lea rcx, [variable_names]; mov rdx, reg; call
+        const VARIABLE_NAMES: [Signature<14>; 2] = [
+            Signature::new("48 8D 0D ?? ?? ?? ?? 48 8B D3 E8 ?? ?? ??"),
+            Signature::new("48 8D 0D ?? ?? ?? ?? 48 8B D7 E8 ?? ?? ??"),
+        ];
+        let variable_names =
+            find_rip_relative(process, main_module_range, &VARIABLE_NAMES, 3, |addr| {
+                is_hash_map(process, offsets, pointer_size, addr)
+            })?;
+
+        Some(Self {
+            pointer_size,
+            offsets,
+            current_room,
+            room_names,
+            global_instance,
+            variable_names,
+        })
+    }
+
+    /// Tries attaching to a GameMaker game. The runner version needs to be
+    /// correct for this function to work.
+    ///
+    /// This is the `await`able version of the [`attach`](Self::attach)
+    /// function, yielding back to the runtime between each try.
+    pub async fn wait_attach(
+        process: &Process,
+        version: Version,
+        main_module_range: (Address, u64),
+    ) -> Self {
+        retry(|| Self::attach(process, version, main_module_range)).await
+    }
+
+    /// Reads the index of the room that is currently running. This is `-1`
+    /// while the game starts up.
+    pub fn current_room_id(&self, process: &Process) -> Result<i32, Error> {
+        process.read(self.current_room)
+    }
+
+    /// Reads the name of the room with the index given, such as `rm_title`.
+    pub fn room_name<const N: usize>(
+        &self,
+        process: &Process,
+        id: i32,
+    ) -> Result<ArrayCString<N>, Error> {
+        read_room_name(
+            process,
+            self.offsets,
+            self.pointer_size,
+            self.room_names,
+            id,
+        )
+    }
+
+    /// Reads the name of the room that is currently running.
+    pub fn current_room_name<const N: usize>(
+        &self,
+        process: &Process,
+    ) -> Result<ArrayCString<N>, Error> {
+        self.room_name(process, self.current_room_id(process)?)
+    }
+
+    /// Reads the global variable with the name given, such as `deaths` for
+    /// `global.deaths`. The name is first looked up to find the slot of the
+    /// variable, which is then looked up in the variables of the global
+    /// instance.
+    pub fn get_global<const N: usize>(
+        &self,
+        process: &Process,
+        name: &str,
+    ) -> Result<GmValue<N>, Error> {
+        let slot = self
+            .hash_map_entries(
+                process,
+                self.variable_names,
+                (self.offsets.name_entry_size, self.offsets.name_entry_hash),
+            )
+            .find_map(|entry| {
+                let key =
+                    process.read_pointer(entry + self.offsets.name_entry_key, self.pointer_size);
+                key.ok()
+                    .and_then(|key| process.read::<ArrayCString<CSTR>>(key).ok())
+                    .filter(|key| key.matches(name))?;
+                process.read::<i32>(entry).ok()
+            })
+            .ok_or(Error {})?;
+
+        let instance = process.read_pointer(self.global_instance, self.pointer_size)?;
+        if instance.is_null() {
+            return Err(Error {});
+        }
+        let yyvars =
+            process.read_pointer(instance + self.offsets.instance_yyvars, self.pointer_size)?;
+        if yyvars.is_null() {
+            return Err(Error {});
+        }
+
+        let value = self
+            .hash_map_entries(
+                process,
+                yyvars,
+                (self.offsets.var_entry_size, self.offsets.var_entry_hash),
+            )
+            .find_map(|entry| {
+                if process
+                    .read::<i32>(entry + self.offsets.var_entry_key)
+                    .ok()?
+                    != slot
+                {
+                    return None;
+                }
+                process.read_pointer(entry, self.pointer_size).ok()
+            })
+            .ok_or(Error {})?;
+
+        GmValue::read(process, self, value)
+    }
+
+    /// Iterates over the addresses of the used entries of a `CHashMap`. The
+    /// entries are stored in an array, where each entry has a hash after its
+    /// value and key. A hash of `0` marks an unused entry.
+    fn hash_map_entries<'a>(
+        &'a self,
+        process: &'a Process,
+        hash_map: Address,
+        (entry_size, entry_hash): (u8, u8),
+    ) -> impl FusedIterator<Item = Address> + 'a {
+        let (size, elements) = match (
+            process.read::<u32>(hash_map),
+            process.read_pointer(hash_map + self.offsets.hash_map_elements, self.pointer_size),
+        ) {
+            (Ok(size), Ok(elements)) if size <= MAX_HASH_MAP_SIZE => (size, elements),
+            _ => (0, Address::NULL),
+        };
+
+        let mut index = 0;
+        iter::from_fn(move || {
+            while index < size {
+                let entry = elements + (index as u64).wrapping_mul(entry_size as u64);
+                index += 1;
+                match process.read::<u32>(entry + entry_hash) {
+                    Ok(0) => continue,
+                    Ok(_) => return Some(entry),
+                    Err(_) => return None,
+                }
+            }
+            None
+        })
+        .fuse()
+    }
+}
+
+/// Reads the name of the room with the index given from the array of room
+/// names.
+fn read_room_name<const N: usize>(
+    process: &Process,
+    offsets: &Offsets,
+    pointer_size: PointerSize,
+    room_names: Address,
+    id: i32,
+) -> Result<ArrayCString<N>, Error> {
+    let len = process.read::<i32>(room_names)?;
+    if id < 0 || id >= len {
+        return Err(Error {});
+    }
+    let names = process.read_pointer(room_names + offsets.array_data, pointer_size)?;
+    let name = process.read_pointer(
+        names + (id as u64).wrapping_mul(pointer_size as u64),
+        pointer_size,
+    )?;
+    if name.is_null() {
+        return Err(Error {});
+    }
+    process.read(name)
+}
+
+/// Checks whether the bytes given are the name of an asset, such as a room.
+/// GameMaker only allows letters, digits and underscores in them. The name
+/// also needs to end before the buffer it was read into does.
+fn is_asset_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && name.len() < CSTR
+        && name.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+/// Resolves the rip-relative displacement at the address given. The
+/// displacement needs to be the last part of its instruction.
+fn rip_relative(process: &Process, displacement: Address) -> Option<Address> {
+    Some(displacement + 0x4 + process.read::<i32>(displacement).ok()?)
+}
+
+/// Resolves the rip-relative operand of the first match of any of the
+/// signatures whose target passes the check given.
+fn find_rip_relative<const N: usize>(
+    process: &Process,
+    main_module_range: (Address, u64),
+    signatures: &[Signature<N>],
+    displacement_offset: u64,
+    check: impl Fn(Address) -> bool,
+) -> Option<Address> {
+    signatures.iter().find_map(|sig| {
+        sig.scan_iter(process, main_module_range)
+            .filter_map(|addr| rip_relative(process, addr + displacement_offset))
+            .find(|&addr| check(addr))
+    })
+}
+
+/// Checks whether the address given looks like a `CHashMap`. Its size is a
+/// power of two and the mask that follows the amount of used entries is the
+/// size minus one.
+fn is_hash_map(
+    process: &Process,
+    offsets: &Offsets,
+    pointer_size: PointerSize,
+    address: Address,
+) -> bool {
+    let (Ok(size), Ok(mask), Ok(elements)) = (
+        process.read::<u32>(address),
+        process.read::<u32>(address + offsets.hash_map_mask),
+        process.read_pointer(address + offsets.hash_map_elements, pointer_size),
+    ) else {
+        return false;
+    };
+    size.is_power_of_two() && size <= MAX_HASH_MAP_SIZE && mask == size - 1 && !elements.is_null()
+}
+
+/// A value of a GameMaker variable. Strings are read into an
+/// [`ArrayCString`] of capacity `N`.
+#[derive(Copy, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum GmValue<const N: usize> {
+    /// A number. GameMaker stores all numbers as doubles unless they come
+    /// from an extension or a function that returns an integer.
+    Real(f64),
+    /// A string.
+    String(ArrayCString<N>),
+    /// A boolean.
+    Bool(bool),
+}
+
+impl<const N: usize> GmValue<N> {
+    /// Reads an `RValue`, which consists of the value itself, followed by
+    /// flags and the kind of the value.
+    fn read(process: &Process, module: &Module, address: Address) -> Result<Self, Error> {
+        let kind = process.read::<u32>(address + module.offsets.rvalue_kind)? & RVALUE_KIND_MASK;
+        Ok(match kind {
+            KIND_REAL => Self::Real(process.read(address)?),
+            KIND_INT32 => Self::Real(process.read::<i32>(address)? as f64),
+            KIND_INT64 => Self::Real(process.read::<i64>(address)? as f64),
+            KIND_BOOL => Self::Bool(process.read::<f64>(address)? != 0.0),
+            KIND_STRING => {
+                // The string is reference counted, with the pointer to the
+                // characters being the first field.
+                let string = process.read_pointer(address, module.pointer_size)?;
+                let chars = process.read_pointer(string, module.pointer_size)?;
+                if chars.is_null() {
+                    return Err(Error {});
+                }
+                Self::String(process.read(chars)?)
+            }
+            _ => return Err(Error {}),
+        })
+    }
+
+    /// Returns the number if the value is a number.
+    pub const fn as_real(&self) -> Option<f64> {
+        match self {
+            Self::Real(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if the value is a string.
+    pub const fn as_string(&self) -> Option<&ArrayCString<N>> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if the value is a boolean.
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+struct Offsets {
+    array_data: u8,
+    hash_map_mask: u8,
+    hash_map_elements: u8,
+    name_entry_key: u8,
+    name_entry_hash: u8,
+    name_entry_size: u8,
+    var_entry_key: u8,
+    var_entry_hash: u8,
+    var_entry_size: u8,
+    instance_yyvars: u8,
+    rvalue_kind: u8,
+}
+
+impl Offsets {
+    const fn new(version: Version, pointer_size: PointerSize) -> Option<&'static Self> {
+        match pointer_size {
+            PointerSize::Bit64 => Some(match version {
+                Version::V2022 => &Self {
+                    array_data: 0x8,
+                    hash_map_mask: 0x8,
+                    hash_map_elements: 0x10,
+                    name_entry_key: 0x8,
+                    name_entry_hash: 0x10,
+                    name_entry_size: 0x18,
+                    var_entry_key: 0x8,
+                    var_entry_hash: 0xC,
+                    var_entry_size: 0x10,
+                    instance_yyvars: 0x48,
+                    rvalue_kind: 0xC,
+                },
+                Version::V2023 => &Self {
+                    array_data: 0x8,
+                    hash_map_mask: 0x8,
+                    hash_map_elements: 0x10,
+                    name_entry_key: 0x8,
+                    name_entry_hash: 0x10,
+                    name_entry_size: 0x18,
+                    var_entry_key: 0x8,
+                    var_entry_hash: 0xC,
+                    var_entry_size: 0x10,
+                    instance_yyvars: 0x50,
+                    rvalue_kind: 0xC,
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[allow(missing_docs)]
+/// The version of the GameMaker runner used by the game
+pub enum Version {
+    V2022,
+    V2023,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_names() {
+        assert!(is_asset_name(b"rm_title"));
+        assert!(is_asset_name(b"Room1"));
+        assert!(is_asset_name(b"_"));
+    }
+
+    #[test]
+    fn not_asset_names() {
+        assert!(!is_asset_name(b""));
+        assert!(!is_asset_name(b"rm title"));
+        assert!(!is_asset_name(b"rm-title"));
+        assert!(!is_asset_name(b"\x01\x7F"));
+        assert!(!is_asset_name(&[b'a'; CSTR]));
+    }
+}
@@ -0,0 +1,355 @@
+//! Support for games using the Godot engine.
+//!
+//! The engine keeps all of its nodes in a tree that is owned by the
+//! [`SceneTree`]. Games written in GDScript and C# both use the same tree, so
+//! traversing it works the same way for both.
+//!
+//! Only the 64-bit layouts of Godot 3.5 and 4.2 are currently supported. For
+//! Godot 3.5, [`Variant`]s along with strings, `Array`s and `Dictionary`s can
+//! be read, but the layout of its nodes isn't covered yet, so the
+//! [`SceneTree`] can't be traversed.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # async fn example(process: asr::Process) {
+//! use asr::game_engine::godot::{Module, Version};
+//!
+//! let main_module = process.wait_module_range("Game.exe").await;
+//! let module = Module::wait_attach(&process, Version::V4_2, main_module).await;
+//! let scene_tree = module.wait_get_scene_tree(&process).await;
+//!
+//! if let Some(player) = scene_tree.find_node_by_path(&process, &module, "/root/World/Player") {
+//!     // TODO: Do something with the player.
+//! }
+//!
+//! if let Some(scene) = scene_tree.get_current_scene(&process, &module) {
+//!     if let Ok(name) = scene.get_name::<64>(&process, &module) {
+//!         // TODO: Do something with the name of the current scene.
+//!     }
+//! }
+//! # }
+//! ```
+
+// References:
+// https://github.com/godotengine/godot/blob/4.2/scene/main/scene_tree.h
+// https://github.com/godotengine/godot/blob/4.2/scene/main/node.h
+// https://github.com/godotengine/godot/blob/4.2/core/string/string_name.h
+// https://github.com/godotengine/godot/blob/4.2/core/templates/hash_map.h
+
+use crate::{
+    file_format::{elf, pe},
+    future::retry,
+    signature::Signature,
+    string::{ArrayCString, ArrayString},
+    Address, Error, PointerSize, Process,
+};
+
+mod scene;
+mod variant;
+pub use self::{scene::*, variant::*};
+
+const CSTR: usize = 128;
+
+/// The mangled name of `SceneTree::singleton`. Both the Linux builds and the
+/// Windows builds, which are compiled with MinGW, use the Itanium mangling.
+const SCENE_TREE_SINGLETON: &str = "_ZN9SceneTree9singletonE";
+
+/// Represents access to a Godot game.
+pub struct Module {
+    pointer_size: PointerSize,
+    version: Version,
+    offsets: &'static Offsets,
+    char_size: u8,
+    scene_tree: Address,
+}
+
+impl Module {
+    /// Tries attaching to a Godot game. The Godot version needs to be correct
+    /// for this function to work. The `SceneTree` is looked up through the
+    /// symbols of the main module first. Release builds usually don't have
+    /// them, so it's then found by a signature of the `SceneTree` constructor.
+    pub fn attach(
+        process: &Process,
+        version: Version,
+        main_module_range: (Address, u64),
+    ) -> Option<Self> {
+        let main_module_address = main_module_range.0;
+
+        let (pointer_size, is_pe) = match pe::MachineType::read(process, main_module_address) {
+            Some(machine_type) => (machine_type.pointer_size()?, true),
+            None => match elf::is_64_bit(process, main_module_address)? {
+                true => (PointerSize::Bit64, false),
+                false => (PointerSize::Bit32, false),
+            },
+        };
+        let offsets = Offsets::new(version, pointer_size)?;
+
+        // Godot 3 uses `wchar_t` for its strings, which is 16-bit on Windows
+        // and 32-bit on Linux. Godot 4 always uses `char32_t`.
+        let char_size = match version {
+            Version::V3_5 if is_pe => 2,
+            _ => 4,
+        };
+
+        let symbol = if is_pe {
+            pe::find_export(process, main_module_address, SCENE_TREE_SINGLETON)
+        } else {
+            elf::find_address(process, main_module_address, SCENE_TREE_SINGLETON)
+        };
+
+        let scene_tree = match symbol {
+            Some(symbol) => symbol,
+            None => {
+                // if (singleton == nullptr) { singleton = this; }
+                const SCENE_TREE: Signature<13> =
+                    Signature::new("48 83 3D ?? ?? ?? ?? 00 75 ?? 48 89 ?D");
+
+                let addr = SCENE_TREE.scan_process_range(process, main_module_range)? + 3;
+                addr + 0x5 + process.read::<i32>(addr).ok()?
+            }
+        };
+
+        Some(Self {
+            pointer_size,
+            version,
+            offsets,
+            char_size,
+            scene_tree,
+        })
+    }
+
+    /// Tries attaching to a Godot game. The Godot version needs to be correct
+    /// for this function to work.
+    ///
+    /// This is the `await`able version of the [`attach`](Self::attach)
+    /// function, yielding back to the runtime between each try.
+    pub async fn wait_attach(
+        process: &Process,
+        version: Version,
+        main_module_range: (Address, u64),
+    ) -> Self {
+        retry(|| Self::attach(process, version, main_module_range)).await
+    }
+
+    /// Returns the version of Godot that the module was attached with.
+    pub const fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the memory pointer to `SceneTree::singleton`.
+    pub const fn scene_tree(&self) -> Address {
+        self.scene_tree
+    }
+
+    /// Returns the offsets of the nodes, which are only known for some
+    /// versions.
+    const fn node_offsets(&self) -> Option<&'static NodeOffsets> {
+        self.offsets.nodes.as_ref()
+    }
+
+    /// Returns the current `SceneTree`. It is created shortly after the game
+    /// starts, so this returns [`None`] before that.
+    pub fn get_scene_tree(&self, process: &Process) -> Option<SceneTree> {
+        match process.read_pointer(self.scene_tree, self.pointer_size) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(SceneTree { address: val }),
+        }
+    }
+
+    /// Returns the current `SceneTree`.
+    ///
+    /// This is the `await`able version of the
+    /// [`get_scene_tree`](Self::get_scene_tree) function, yielding back to the
+    /// runtime between each try.
+    pub async fn wait_get_scene_tree(&self, process: &Process) -> SceneTree {
+        retry(|| self.get_scene_tree(process)).await
+    }
+
+    /// Reads a `StringName`, which is a pointer to shared data that either
+    /// points to a static C string or contains a `String`.
+    fn read_string_name<const N: usize>(
+        &self,
+        process: &Process,
+        address: Address,
+    ) -> Result<ArrayString<N>, Error> {
+        let data = process.read_pointer(address, self.pointer_size)?;
+        if data.is_null() {
+            return Ok(ArrayString::new());
+        }
+
+        let cname =
+            process.read_pointer(data + self.offsets.string_name_cname, self.pointer_size)?;
+        if !cname.is_null() {
+            return Ok(process.read::<ArrayCString<N>>(cname)?.to_array_string());
+        }
+
+        self.read_string(process, data + self.offsets.string_name_name)
+    }
+
+    /// Reads a `String`, which is a pointer to nul-terminated copy-on-write
+    /// data. The characters are converted to UTF-8. Strings that don't fit
+    /// into `N` bytes are truncated.
+    fn read_string<const N: usize>(
+        &self,
+        process: &Process,
+        address: Address,
+    ) -> Result<ArrayString<N>, Error> {
+        let data = process.read_pointer(address, self.pointer_size)?;
+        let mut string = ArrayString::new();
+        if data.is_null() {
+            return Ok(string);
+        }
+
+        if self.char_size == 2 {
+            let buf = process.read::<[u16; N]>(data)?;
+            let len = buf.iter().position(|&c| c == 0).unwrap_or(N);
+            char::decode_utf16(buf[..len].iter().copied())
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .try_for_each(|c| string.try_push(c))
+                .ok();
+        } else {
+            let buf = process.read::<[u32; N]>(data)?;
+            buf.iter()
+                .take_while(|&&c| c != 0)
+                .map(|&c| char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+                .try_for_each(|c| string.try_push(c))
+                .ok();
+        }
+        Ok(string)
+    }
+}
+
+struct Offsets {
+    string_name_cname: u8,
+    string_name_name: u8,
+    variant_size: u8,
+    variant_data: u8,
+    variant_object: u8,
+    /// The entries of a `Dictionary`. Godot 4 stores them in a `HashMap`,
+    /// while Godot 3 stores them in a `List` that is behind a pointer.
+    dictionary_entries: u8,
+    dictionary_entries_is_pointer: bool,
+    dictionary_head_element: u8,
+    dictionary_num_elements: u8,
+    dictionary_element_next: u8,
+    dictionary_element_key: u8,
+    array_elements: u8,
+    cow_data_size: u8,
+    nodes: Option<NodeOffsets>,
+}
+
+struct NodeOffsets {
+    scene_tree_root: u16,
+    scene_tree_current_scene: u16,
+    node_parent: u16,
+    node_children: u16,
+    node_name: u16,
+    hash_map_head_element: u8,
+    hash_map_element_next: u8,
+    hash_map_element_value: u8,
+}
+
+impl Offsets {
+    /// Godot 3.5, where a `Dictionary` is an `OrderedHashMap` that iterates
+    /// over a linked `List` of key value pairs. Release builds store the
+    /// pointer to an `Object` as the first field of its variant data.
+    const V3_5: Self = Self {
+        string_name_cname: 0x8,
+        string_name_name: 0x10,
+        variant_size: 0x18,
+        variant_data: 0x8,
+        variant_object: 0x0,
+        dictionary_entries: 0x18,
+        dictionary_entries_is_pointer: true,
+        dictionary_head_element: 0x0,
+        dictionary_num_elements: 0x10,
+        dictionary_element_next: 0x30,
+        dictionary_element_key: 0x0,
+        array_elements: 0x10,
+        cow_data_size: 0x4,
+        nodes: None,
+    };
+
+    /// Godot 4.2, where a `Dictionary` is a `HashMap` whose elements are
+    /// linked in the order they were inserted.
+    const V4_2: Self = Self {
+        string_name_cname: 0x8,
+        string_name_name: 0x10,
+        variant_size: 0x18,
+        variant_data: 0x8,
+        variant_object: 0x8,
+        dictionary_entries: 0x10,
+        dictionary_entries_is_pointer: false,
+        dictionary_head_element: 0x18,
+        dictionary_num_elements: 0x2C,
+        dictionary_element_next: 0x0,
+        dictionary_element_key: 0x10,
+        array_elements: 0x10,
+        cow_data_size: 0x4,
+        nodes: Some(NodeOffsets {
+            scene_tree_root: 0x2B0,
+            scene_tree_current_scene: 0x3C0,
+            node_parent: 0x128,
+            node_children: 0x138,
+            node_name: 0x1D0,
+            hash_map_head_element: 0x18,
+            hash_map_element_next: 0x0,
+            hash_map_element_value: 0x18,
+        }),
+    };
+
+    const fn new(version: Version, pointer_size: PointerSize) -> Option<&'static Self> {
+        match pointer_size {
+            PointerSize::Bit64 => Some(match version {
+                Version::V3_5 => &Self::V3_5,
+                Version::V4_2 => &Self::V4_2,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+#[allow(missing_docs)]
+/// The version of Godot used by the game
+pub enum Version {
+    V3_5,
+    V4_2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        for version in [Version::V3_5, Version::V4_2] {
+            let offsets = Offsets::new(version, PointerSize::Bit64).unwrap();
+            // Both versions store the type of a variant in front of 16 bytes
+            // of data that are aligned to 8 bytes.
+            assert_eq!(offsets.variant_data, 0x8);
+            assert_eq!(offsets.variant_size, 0x18);
+            assert!(Offsets::new(version, PointerSize::Bit32).is_none());
+        }
+        assert!(Offsets::new(Version::V3_5, PointerSize::Bit64)
+            .unwrap()
+            .nodes
+            .is_none());
+        assert!(Offsets::new(Version::V4_2, PointerSize::Bit64)
+            .unwrap()
+            .nodes
+            .is_some());
+    }
+
+    #[test]
+    fn dictionary_elements() {
+        // The key and value of a Godot 3 element are followed by the links.
+        let offsets = Offsets::new(Version::V3_5, PointerSize::Bit64).unwrap();
+        assert_eq!(
+            offsets.dictionary_element_next,
+            offsets.dictionary_element_key + 2 * offsets.variant_size
+        );
+    }
+}
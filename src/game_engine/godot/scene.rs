@@ -0,0 +1,196 @@
+//! Support for traversing the tree of nodes in Godot games.
+
+use core::iter::{self, FusedIterator};
+
+use crate::{string::ArrayString, Address, Error, Process};
+
+use super::{Module, CSTR};
+
+/// The maximum amount of children that are iterated for a single node. This
+/// protects against reading garbage if a node is read while it is being freed.
+const MAX_CHILDREN: usize = 0x10000;
+
+/// The `SceneTree` owns the tree of nodes of the game. Its root is a `Window`
+/// called `root`, which contains the current scene and all the autoloaded
+/// nodes.
+///
+// Docs:
+// - https://docs.godotengine.org/en/4.2/classes/class_scenetree.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SceneTree {
+    pub(super) address: Address,
+}
+
+impl SceneTree {
+    /// Returns the address of the `SceneTree`.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the root node of the tree. This returns [`None`] for versions
+    /// of Godot whose node layout isn't known.
+    pub fn get_root(&self, process: &Process, module: &Module) -> Option<Node> {
+        Node::read(
+            process,
+            module,
+            self.address + module.node_offsets()?.scene_tree_root,
+        )
+    }
+
+    /// Returns the root node of the scene that is currently running. This
+    /// returns [`None`] while the scene is being changed.
+    pub fn get_current_scene(&self, process: &Process, module: &Module) -> Option<Node> {
+        Node::read(
+            process,
+            module,
+            self.address + module.node_offsets()?.scene_tree_current_scene,
+        )
+    }
+
+    /// Looks up a node by its absolute path, such as `/root/World/Player`. The
+    /// first name of the path is the name of the root node, which is always
+    /// `root`. The leading `/` is optional.
+    pub fn find_node_by_path(
+        &self,
+        process: &Process,
+        module: &Module,
+        path: &str,
+    ) -> Option<Node> {
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let (root_name, rest) = path.split_once('/').unwrap_or((path, ""));
+
+        let root = self.get_root(process, module)?;
+        if !root
+            .get_name::<CSTR>(process, module)
+            .is_ok_and(|name| name.as_str() == root_name)
+        {
+            return None;
+        }
+        root.find_node_by_path(process, module, rest)
+    }
+}
+
+/// A `Node` is the building block of every scene in Godot. Nodes form a tree,
+/// where each node has a name that is unique among its siblings.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::godot::{Module, Node}, Process};
+/// # fn example(process: &Process, module: &Module, scene: Node) {
+/// for child in scene.children(process, module) {
+///     if let Ok(name) = child.get_name::<64>(process, module) {
+///         // TODO: Do something with the name of the child.
+///     }
+/// }
+/// # }
+/// ```
+///
+// Docs:
+// - https://docs.godotengine.org/en/4.2/classes/class_node.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Node {
+    address: Address,
+}
+
+impl Node {
+    fn read(process: &Process, module: &Module, address: Address) -> Option<Self> {
+        match process.read_pointer(address, module.pointer_size) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(Self { address: val }),
+        }
+    }
+
+    /// Creates a node from its address, such as the address of an
+    /// [`Object`](super::Variant::Object) that is known to be a node.
+    pub const fn new(address: Address) -> Self {
+        Self { address }
+    }
+
+    /// Returns the address of the node.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Reads the name of the node. Names that don't fit into `N` bytes are
+    /// truncated.
+    pub fn get_name<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Result<ArrayString<N>, Error> {
+        let offsets = module.node_offsets().ok_or(Error {})?;
+        module.read_string_name(process, self.address + offsets.node_name)
+    }
+
+    /// Returns the parent of the node, if it has one.
+    pub fn get_parent(&self, process: &Process, module: &Module) -> Option<Node> {
+        Node::read(
+            process,
+            module,
+            self.address + module.node_offsets()?.node_parent,
+        )
+    }
+
+    /// Iterates over the children of the node. The children are stored in a
+    /// `HashMap` from their names to the nodes, which keeps them in the order
+    /// in which they were added.
+    pub fn children<'a>(
+        &self,
+        process: &'a Process,
+        module: &'a Module,
+    ) -> impl FusedIterator<Item = Node> + 'a {
+        let offsets = module.node_offsets();
+        let mut element = offsets
+            .and_then(|offsets| {
+                process
+                    .read_pointer(
+                        self.address + offsets.node_children + offsets.hash_map_head_element,
+                        module.pointer_size,
+                    )
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        iter::from_fn(move || {
+            if element.is_null() {
+                return None;
+            }
+            let current = element;
+            element = process
+                .read_pointer(
+                    current + offsets?.hash_map_element_next,
+                    module.pointer_size,
+                )
+                .unwrap_or_default();
+            Some(current)
+        })
+        .take(MAX_CHILDREN)
+        .filter_map(move |element| {
+            Node::read(process, module, element + offsets?.hash_map_element_value)
+        })
+        .fuse()
+    }
+
+    /// Returns the child of the node with the name specified.
+    pub fn get_child(&self, process: &Process, module: &Module, name: &str) -> Option<Node> {
+        self.children(process, module).find(|child| {
+            child
+                .get_name::<CSTR>(process, module)
+                .is_ok_and(|n| n.as_str() == name)
+        })
+    }
+
+    /// Looks up a node by its path relative to this node, such as
+    /// `World/Player`. An empty path returns this node.
+    pub fn find_node_by_path(
+        &self,
+        process: &Process,
+        module: &Module,
+        path: &str,
+    ) -> Option<Node> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(*self, |node, name| node.get_child(process, module, name))
+    }
+}
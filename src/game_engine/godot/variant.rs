@@ -0,0 +1,429 @@
+//! Support for reading Godot's dynamically typed values.
+
+use core::iter::{self, FusedIterator};
+
+use crate::{string::ArrayString, Address, Error, Process};
+
+use super::{Module, Version, CSTR};
+
+/// The maximum amount of elements that are iterated for a single `Array` or
+/// `Dictionary`. This protects against reading garbage if a collection is read
+/// while it is being freed.
+const MAX_ELEMENTS: u32 = 0x10000;
+
+/// A `Variant` is how Godot stores values whose type is only known at runtime,
+/// such as the member variables of GDScript scripts and the elements of
+/// `Array`s and `Dictionary`s. The value is decoded when the variant is read.
+/// Strings and collections are only referenced, so they can be read
+/// separately.
+///
+/// Variants of types that aren't supported are returned as
+/// [`Unsupported`](Self::Unsupported) with the type tag that was found, so it's
+/// possible to tell which type was encountered.
+///
+// Docs:
+// - https://docs.godotengine.org/en/3.5/classes/class_@globalscope.html#enum-globalscope-variant-type
+// - https://docs.godotengine.org/en/4.2/classes/class_variant.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Variant {
+    /// The variant is empty. This is `null` in GDScript.
+    Nil,
+    /// A boolean.
+    Bool(bool),
+    /// A 64-bit integer.
+    Int(i64),
+    /// A 64-bit floating point number.
+    Float(f64),
+    /// A `String`.
+    String(GodotString),
+    /// A `StringName`.
+    StringName(GodotStringName),
+    /// A `Vector2` with the components `x` and `y`.
+    Vector2([f32; 2]),
+    /// A `Vector3` with the components `x`, `y` and `z`.
+    Vector3([f32; 3]),
+    /// A pointer to an `Object`, such as a `Node`. Freed objects are null.
+    Object(Address),
+    /// A `Dictionary`.
+    Dictionary(Dictionary),
+    /// An `Array`.
+    Array(Array),
+    /// A variant of a type that isn't supported. This contains its type tag.
+    Unsupported(u32),
+}
+
+/// The kinds of variants that are supported, independently of the type tags
+/// that the versions of Godot use for them.
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Nil,
+    Bool,
+    Int,
+    Float,
+    String,
+    StringName,
+    Vector2,
+    Vector3,
+    Object,
+    Dictionary,
+    Array,
+}
+
+impl Kind {
+    const fn from_tag(version: Version, tag: u32) -> Option<Self> {
+        Some(match version {
+            Version::V3_5 => match tag {
+                0 => Self::Nil,
+                1 => Self::Bool,
+                2 => Self::Int,
+                3 => Self::Float,
+                4 => Self::String,
+                5 => Self::Vector2,
+                7 => Self::Vector3,
+                17 => Self::Object,
+                18 => Self::Dictionary,
+                19 => Self::Array,
+                _ => return None,
+            },
+            Version::V4_2 => match tag {
+                0 => Self::Nil,
+                1 => Self::Bool,
+                2 => Self::Int,
+                3 => Self::Float,
+                4 => Self::String,
+                5 => Self::Vector2,
+                9 => Self::Vector3,
+                21 => Self::StringName,
+                24 => Self::Object,
+                27 => Self::Dictionary,
+                28 => Self::Array,
+                _ => return None,
+            },
+        })
+    }
+}
+
+impl Variant {
+    /// Reads the variant stored at the address given.
+    pub fn read(process: &Process, module: &Module, address: Address) -> Result<Self, Error> {
+        let tag = process.read::<u32>(address)?;
+        let data = address + module.offsets.variant_data;
+        let Some(kind) = Kind::from_tag(module.version, tag) else {
+            return Ok(Self::Unsupported(tag));
+        };
+
+        Ok(match kind {
+            Kind::Nil => Self::Nil,
+            Kind::Bool => Self::Bool(process.read::<u8>(data)? != 0),
+            Kind::Int => Self::Int(process.read(data)?),
+            Kind::Float => Self::Float(process.read(data)?),
+            Kind::String => Self::String(GodotString { address: data }),
+            Kind::StringName => Self::StringName(GodotStringName { address: data }),
+            Kind::Vector2 => Self::Vector2(process.read(data)?),
+            Kind::Vector3 => Self::Vector3(process.read(data)?),
+            Kind::Object => Self::Object(
+                process.read_pointer(data + module.offsets.variant_object, module.pointer_size)?,
+            ),
+            Kind::Dictionary => Self::Dictionary(Dictionary {
+                address: process.read_pointer(data, module.pointer_size)?,
+            }),
+            Kind::Array => Self::Array(Array {
+                address: process.read_pointer(data, module.pointer_size)?,
+            }),
+        })
+    }
+
+    /// Checks whether the variant is a `String` or a `StringName` that
+    /// matches the text given.
+    pub fn matches_str(&self, process: &Process, module: &Module, text: &str) -> bool {
+        match self {
+            Self::String(string) => string
+                .read::<CSTR>(process, module)
+                .is_ok_and(|s| s.as_str() == text),
+            Self::StringName(string) => string
+                .read::<CSTR>(process, module)
+                .is_ok_and(|s| s.as_str() == text),
+            _ => false,
+        }
+    }
+}
+
+/// A reference to a `String` in the memory of the game. Godot 3 stores the
+/// characters as `wchar_t`, which is 16-bit on Windows and 32-bit on Linux,
+/// while Godot 4 always uses 32-bit characters. In both versions the
+/// characters are stored in copy-on-write data that is nul-terminated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GodotString {
+    address: Address,
+}
+
+impl GodotString {
+    /// Returns the address of the `String`.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Reads the string and converts it to UTF-8. Strings that don't fit into
+    /// `N` bytes are truncated.
+    pub fn read<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Result<ArrayString<N>, Error> {
+        module.read_string(process, self.address)
+    }
+}
+
+/// A reference to a `StringName` in the memory of the game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GodotStringName {
+    address: Address,
+}
+
+impl GodotStringName {
+    /// Returns the address of the `StringName`.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Reads the string and converts it to UTF-8. Strings that don't fit into
+    /// `N` bytes are truncated.
+    pub fn read<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Result<ArrayString<N>, Error> {
+        module.read_string_name(process, self.address)
+    }
+}
+
+/// A `Dictionary` maps variants to variants. It keeps its entries in the order
+/// in which they were inserted.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::godot::{Module, Variant}, Address, Process};
+/// # fn example(process: &Process, module: &Module, stats: Address) {
+/// if let Ok(Variant::Dictionary(stats)) = Variant::read(process, module, stats) {
+///     if let Some(Variant::Int(deaths)) = stats.get(process, module, "deaths") {
+///         // TODO: Do something with the deaths.
+///     }
+/// }
+/// # }
+/// ```
+///
+// Source: https://github.com/godotengine/godot/blob/4.2/core/variant/dictionary.cpp
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dictionary {
+    address: Address,
+}
+
+impl Dictionary {
+    /// Returns the address of the data that the `Dictionary` points to.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the address of the structure that links the entries together.
+    fn entries(&self, process: &Process, module: &Module) -> Result<Address, Error> {
+        let entries = self.address + module.offsets.dictionary_entries;
+        if !module.offsets.dictionary_entries_is_pointer {
+            return Ok(entries);
+        }
+        // The list only allocates its data once the first entry is inserted.
+        process.read_pointer(entries, module.pointer_size)
+    }
+
+    /// Returns the amount of entries in the dictionary.
+    pub fn len(&self, process: &Process, module: &Module) -> Result<u32, Error> {
+        let entries = self.entries(process, module)?;
+        if entries.is_null() {
+            return Ok(0);
+        }
+        process.read(entries + module.offsets.dictionary_num_elements)
+    }
+
+    /// Checks whether the dictionary is empty.
+    pub fn is_empty(&self, process: &Process, module: &Module) -> Result<bool, Error> {
+        Ok(self.len(process, module)? == 0)
+    }
+
+    /// Iterates over the keys and values of the dictionary.
+    pub fn iter<'a>(
+        &self,
+        process: &'a Process,
+        module: &'a Module,
+    ) -> impl FusedIterator<Item = (Variant, Variant)> + 'a {
+        let mut element = self
+            .entries(process, module)
+            .ok()
+            .filter(|entries| !entries.is_null())
+            .and_then(|entries| {
+                process
+                    .read_pointer(
+                        entries + module.offsets.dictionary_head_element,
+                        module.pointer_size,
+                    )
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        iter::from_fn(move || {
+            if element.is_null() {
+                return None;
+            }
+            let current = element;
+            element = process
+                .read_pointer(
+                    current + module.offsets.dictionary_element_next,
+                    module.pointer_size,
+                )
+                .unwrap_or_default();
+            Some(current)
+        })
+        .take(MAX_ELEMENTS as usize)
+        .filter_map(move |element| {
+            let key = element + module.offsets.dictionary_element_key;
+            Some((
+                Variant::read(process, module, key).ok()?,
+                Variant::read(process, module, key + module.offsets.variant_size).ok()?,
+            ))
+        })
+        .fuse()
+    }
+
+    /// Returns the value of the entry whose key is a `String` or a
+    /// `StringName` that matches the key given.
+    pub fn get(&self, process: &Process, module: &Module, key: &str) -> Option<Variant> {
+        self.iter(process, module)
+            .find(|(k, _)| k.matches_str(process, module, key))
+            .map(|(_, v)| v)
+    }
+}
+
+/// An `Array` is a list of variants.
+///
+// Source: https://github.com/godotengine/godot/blob/4.2/core/variant/array.cpp
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Array {
+    address: Address,
+}
+
+impl Array {
+    /// Returns the address of the data that the `Array` points to.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the address of the first element. The amount of elements is
+    /// stored right in front of it.
+    fn elements(&self, process: &Process, module: &Module) -> Result<Address, Error> {
+        process.read_pointer(
+            self.address + module.offsets.array_elements,
+            module.pointer_size,
+        )
+    }
+
+    /// Returns the amount of elements in the array.
+    pub fn len(&self, process: &Process, module: &Module) -> Result<u32, Error> {
+        let elements = self.elements(process, module)?;
+        if elements.is_null() {
+            return Ok(0);
+        }
+        process.read(elements.add_signed(-(module.offsets.cow_data_size as i64)))
+    }
+
+    /// Checks whether the array is empty.
+    pub fn is_empty(&self, process: &Process, module: &Module) -> Result<bool, Error> {
+        Ok(self.len(process, module)? == 0)
+    }
+
+    /// Reads the element at the index given.
+    pub fn get(&self, process: &Process, module: &Module, index: u32) -> Result<Variant, Error> {
+        if index >= self.len(process, module)? {
+            return Err(Error {});
+        }
+        let elements = self.elements(process, module)?;
+        Variant::read(
+            process,
+            module,
+            elements + (index as u64).wrapping_mul(module.offsets.variant_size as u64),
+        )
+    }
+
+    /// Iterates over the elements of the array.
+    pub fn iter<'a>(
+        &self,
+        process: &'a Process,
+        module: &'a Module,
+    ) -> impl FusedIterator<Item = Variant> + 'a {
+        let len = self
+            .len(process, module)
+            .ok()
+            .filter(|&len| len <= MAX_ELEMENTS)
+            .unwrap_or_default();
+        let this = *self;
+        (0..len)
+            .map_while(move |index| this.get(process, module, index).ok())
+            .fuse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_3_5() {
+        let kind = |tag| Kind::from_tag(Version::V3_5, tag);
+        assert_eq!(kind(0), Some(Kind::Nil));
+        assert_eq!(kind(1), Some(Kind::Bool));
+        assert_eq!(kind(2), Some(Kind::Int));
+        assert_eq!(kind(3), Some(Kind::Float));
+        assert_eq!(kind(4), Some(Kind::String));
+        assert_eq!(kind(5), Some(Kind::Vector2));
+        assert_eq!(kind(7), Some(Kind::Vector3));
+        assert_eq!(kind(17), Some(Kind::Object));
+        assert_eq!(kind(18), Some(Kind::Dictionary));
+        assert_eq!(kind(19), Some(Kind::Array));
+        // Rect2, NodePath, RID and PoolByteArray aren't supported.
+        for tag in [6, 15, 16, 20, 27, u32::MAX] {
+            assert_eq!(kind(tag), None, "{tag}");
+        }
+    }
+
+    #[test]
+    fn tags_4_2() {
+        let kind = |tag| Kind::from_tag(Version::V4_2, tag);
+        assert_eq!(kind(0), Some(Kind::Nil));
+        assert_eq!(kind(1), Some(Kind::Bool));
+        assert_eq!(kind(2), Some(Kind::Int));
+        assert_eq!(kind(3), Some(Kind::Float));
+        assert_eq!(kind(4), Some(Kind::String));
+        assert_eq!(kind(5), Some(Kind::Vector2));
+        assert_eq!(kind(9), Some(Kind::Vector3));
+        assert_eq!(kind(21), Some(Kind::StringName));
+        assert_eq!(kind(24), Some(Kind::Object));
+        assert_eq!(kind(27), Some(Kind::Dictionary));
+        assert_eq!(kind(28), Some(Kind::Array));
+        // Vector2i, Vector3i, NodePath and Callable aren't supported.
+        for tag in [6, 10, 22, 25, 38, u32::MAX] {
+            assert_eq!(kind(tag), None, "{tag}");
+        }
+    }
+
+    #[test]
+    fn tags_differ_between_versions() {
+        // Godot 4 inserted new types, so most of the tags of Godot 3 moved.
+        for tag in [7, 17, 18, 19] {
+            assert_ne!(
+                Kind::from_tag(Version::V3_5, tag),
+                Kind::from_tag(Version::V4_2, tag),
+                "{tag}",
+            );
+        }
+        assert_eq!(Kind::from_tag(Version::V3_5, 21), None);
+    }
+}
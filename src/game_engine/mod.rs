@@ -1,5 +1,9 @@
 //! Support for attaching to various game engines.
 
+#[cfg(feature = "gamemaker")]
+pub mod gamemaker;
+#[cfg(feature = "godot")]
+pub mod godot;
 #[cfg(feature = "unity")]
 pub mod unity;
 #[cfg(feature = "unreal")]
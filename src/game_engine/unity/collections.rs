@@ -0,0 +1,512 @@
+//! Support for reading strings and collections of the .NET base class library.
+
+use core::{iter, marker::PhantomData, mem};
+
+use bytemuck::CheckedBitPattern;
+
+use crate::{string::ArrayString, Address, Error, PointerSize, Process};
+
+/// The maximum length of a string or collection that is read. Larger lengths
+/// are treated as an error, as they most likely come from reading a garbage
+/// pointer and would otherwise result in huge reads.
+pub const MAX_COLLECTION_LEN: usize = 1 << 20;
+
+/// The number of pointers that every object starts with, the class (or
+/// vtable) and the monitor.
+const OBJECT_HEADER_PTRS: u64 = 2;
+
+const fn validate_len(len: u64) -> Result<usize, Error> {
+    if len <= MAX_COLLECTION_LEN as u64 {
+        Ok(len as usize)
+    } else {
+        Err(Error {})
+    }
+}
+
+/// A .NET `string` in the memory of the game. The string is stored as a
+/// length followed by its UTF-16 characters.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unity::{mono::Module, CsString}, Address, Process};
+/// # fn example(process: &Process, module: &Module, player: Address) {
+/// let name = process.read_pointer(player + 0x20, module.pointer_size()).unwrap();
+/// let name = CsString::new(name, module.pointer_size());
+/// if let Ok(name) = name.read::<32>(process) {
+///     // Do something with the name.
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CsString {
+    address: Address,
+    pointer_size: PointerSize,
+}
+
+impl CsString {
+    /// Creates a reader for the string object at the address given. The
+    /// pointer size is available from the attached module.
+    pub const fn new(address: Address, pointer_size: PointerSize) -> Self {
+        Self {
+            address,
+            pointer_size,
+        }
+    }
+
+    /// Returns the address of the string object.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chars_address(&self) -> Address {
+        self.address + (OBJECT_HEADER_PTRS * self.pointer_size as u64 + 4)
+    }
+
+    /// Reads the number of 16-bit characters of the string.
+    pub fn read_len(&self, process: &Process) -> Result<usize, Error> {
+        let len =
+            process.read::<i32>(self.address + OBJECT_HEADER_PTRS * self.pointer_size as u64)?;
+        validate_len(u64::try_from(len).map_err(|_| Error {})?)
+    }
+
+    /// Reads the string and converts it to UTF-8. Unpaired surrogates are
+    /// replaced by [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER). If
+    /// the string doesn't fit into the `N` bytes of the [`ArrayString`], it is
+    /// truncated.
+    pub fn read<const N: usize>(&self, process: &Process) -> Result<ArrayString<N>, Error> {
+        // Every character takes up at least one byte, so no more than `N`
+        // characters can fit.
+        let mut buf = [0u16; N];
+        let buf = &mut buf[..self.read_len(process)?.min(N)];
+        process.read_into_slice(self.chars_address(), buf)?;
+
+        let mut string = ArrayString::new();
+        for c in char::decode_utf16(buf.iter().copied()) {
+            if string
+                .try_push(c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .is_err()
+            {
+                break;
+            }
+        }
+        Ok(string)
+    }
+
+    /// Checks whether the string is equal to the text given. This doesn't
+    /// need a buffer for the whole string, so it works for strings of any
+    /// length.
+    pub fn matches(&self, process: &Process, text: &str) -> bool {
+        const CHUNK_LEN: usize = 64;
+
+        let Ok(len) = self.read_len(process) else {
+            return false;
+        };
+        if text.encode_utf16().count() != len {
+            return false;
+        }
+
+        let mut expected = text.encode_utf16();
+        let mut address = self.chars_address();
+        let mut buf = [0u16; CHUNK_LEN];
+        let mut remaining = len;
+        while remaining != 0 {
+            let chunk = &mut buf[..remaining.min(CHUNK_LEN)];
+            if process.read_into_slice(address, chunk).is_err()
+                || !chunk
+                    .iter()
+                    .copied()
+                    .eq(expected.by_ref().take(chunk.len()))
+            {
+                return false;
+            }
+            address = address + mem::size_of_val(chunk) as u64;
+            remaining -= chunk.len();
+        }
+        true
+    }
+
+    /// Reads the whole string and converts it to UTF-8. Unpaired surrogates
+    /// are replaced by [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER).
+    #[cfg(feature = "alloc")]
+    pub fn read_alloc(&self, process: &Process) -> Result<alloc::string::String, Error> {
+        let chars = process.read_vec::<u16>(self.chars_address(), self.read_len(process)?)?;
+        Ok(char::decode_utf16(chars)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect())
+    }
+}
+
+/// A .NET array `T[]` in the memory of the game. The elements are read as
+/// values of the type `T`, so for arrays of references, `T` needs to be
+/// [`Address32`](crate::Address32) or [`Address64`](crate::Address64)
+/// depending on the pointer size of the game.
+pub struct CsArray<T> {
+    address: Address,
+    pointer_size: PointerSize,
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for CsArray<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CsArray<T> {}
+
+impl<T: CheckedBitPattern> CsArray<T> {
+    /// Creates a reader for the array object at the address given. The
+    /// pointer size is available from the attached module.
+    pub const fn new(address: Address, pointer_size: PointerSize) -> Self {
+        Self {
+            address,
+            pointer_size,
+            _element: PhantomData,
+        }
+    }
+
+    /// Returns the address of the array object.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the address of the first element of the array.
+    pub fn elements_address(&self) -> Address {
+        // The header is followed by a pointer to the bounds and the length.
+        self.address + (OBJECT_HEADER_PTRS + 2) * self.pointer_size as u64
+    }
+
+    /// Reads the number of elements of the array.
+    pub fn read_len(&self, process: &Process) -> Result<usize, Error> {
+        let len = process.read_pointer(
+            self.address + (OBJECT_HEADER_PTRS + 1) * self.pointer_size as u64,
+            self.pointer_size,
+        )?;
+        validate_len(len.value())
+    }
+
+    /// Reads the element at the index given. This fails if the index is out
+    /// of bounds.
+    pub fn read_elem(&self, process: &Process, index: usize) -> Result<T, Error> {
+        if index >= self.read_len(process)? {
+            return Err(Error {});
+        }
+        self.read_elem_unchecked(process, index)
+    }
+
+    fn read_elem_unchecked(&self, process: &Process, index: usize) -> Result<T, Error> {
+        process.read(self.elements_address() + (index * mem::size_of::<T>()) as u64)
+    }
+
+    /// Iterates over the elements of the array. Each element is read
+    /// individually, so no buffer is needed. The iteration stops early if an
+    /// element can't be read.
+    pub fn iter<'a>(&self, process: &'a Process) -> impl Iterator<Item = T> + 'a
+    where
+        T: 'a,
+    {
+        let this = *self;
+        let len = self.read_len(process).unwrap_or_default();
+        (0..len).map_while(move |i| this.read_elem_unchecked(process, i).ok())
+    }
+
+    /// Reads all the elements of the array into a new `Vec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self, process: &Process) -> Result<alloc::vec::Vec<T>, Error> {
+        process.read_vec(self.elements_address(), self.read_len(process)?)
+    }
+}
+
+/// A .NET `List<T>` in the memory of the game. The elements are stored in an
+/// [array](CsArray) that may be larger than the list itself. The elements are
+/// read as values of the type `T`, so for lists of references, `T` needs to
+/// be [`Address32`](crate::Address32) or [`Address64`](crate::Address64)
+/// depending on the pointer size of the game.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unity::{il2cpp::Module, CsList}, Address, Process};
+/// # fn example(process: &Process, module: &Module, inventory: Address) {
+/// let inventory = CsList::<u32>::new(inventory, module.pointer_size());
+/// for item_id in inventory.iter(process) {
+///     // Do something with the item.
+/// }
+/// # }
+/// ```
+pub struct CsList<T> {
+    address: Address,
+    pointer_size: PointerSize,
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for CsList<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for CsList<T> {}
+
+impl<T: CheckedBitPattern> CsList<T> {
+    /// Creates a reader for the list object at the address given. The pointer
+    /// size is available from the attached module.
+    pub const fn new(address: Address, pointer_size: PointerSize) -> Self {
+        Self {
+            address,
+            pointer_size,
+            _element: PhantomData,
+        }
+    }
+
+    /// Returns the address of the list object.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Reads the number of elements of the list.
+    pub fn read_len(&self, process: &Process) -> Result<usize, Error> {
+        let len = process
+            .read::<i32>(self.address + (OBJECT_HEADER_PTRS + 1) * self.pointer_size as u64)?;
+        validate_len(u64::try_from(len).map_err(|_| Error {})?)
+    }
+
+    /// Reads the array that stores the elements of the list. Only the first
+    /// [`read_len`](Self::read_len) elements of it belong to the list.
+    pub fn read_items(&self, process: &Process) -> Result<CsArray<T>, Error> {
+        let items = process.read_pointer(
+            self.address + OBJECT_HEADER_PTRS * self.pointer_size as u64,
+            self.pointer_size,
+        )?;
+        if items.is_null() {
+            return Err(Error {});
+        }
+        Ok(CsArray::new(items, self.pointer_size))
+    }
+
+    /// Reads the length of the list and the array of its elements, making
+    /// sure that the list fits into the array.
+    fn read_len_and_items(&self, process: &Process) -> Result<(usize, CsArray<T>), Error> {
+        let len = self.read_len(process)?;
+        let items = self.read_items(process)?;
+        if len > items.read_len(process)? {
+            return Err(Error {});
+        }
+        Ok((len, items))
+    }
+
+    /// Reads the element at the index given. This fails if the index is out
+    /// of bounds.
+    pub fn read_elem(&self, process: &Process, index: usize) -> Result<T, Error> {
+        let (len, items) = self.read_len_and_items(process)?;
+        if index >= len {
+            return Err(Error {});
+        }
+        items.read_elem_unchecked(process, index)
+    }
+
+    /// Iterates over the elements of the list. Each element is read
+    /// individually, so no buffer is needed. The iteration stops early if an
+    /// element can't be read.
+    pub fn iter<'a>(&self, process: &'a Process) -> impl Iterator<Item = T> + 'a
+    where
+        T: 'a,
+    {
+        let (len, items) = match self.read_len_and_items(process) {
+            Ok((len, items)) => (len, Some(items)),
+            Err(_) => (0, None),
+        };
+        (0..len).map_while(move |i| items?.read_elem_unchecked(process, i).ok())
+    }
+
+    /// Reads all the elements of the list into a new `Vec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self, process: &Process) -> Result<alloc::vec::Vec<T>, Error> {
+        let (len, items) = self.read_len_and_items(process)?;
+        process.read_vec(items.elements_address(), len)
+    }
+}
+
+/// A .NET `Dictionary<TKey, TValue>` in the memory of the game. The keys and
+/// values are read as values of the types `K` and `V`, so for references,
+/// they need to be [`Address32`](crate::Address32) or
+/// [`Address64`](crate::Address64) depending on the pointer size of the game.
+/// The entries are assumed to be laid out with the natural alignment of the
+/// types.
+///
+/// The dictionary is read by following its buckets, as used by the class
+/// libraries that ship with Unity, so the entries are not visited in the order
+/// they were inserted in.
+///
+/// Only the layout of the modern class libraries is supported, where the
+/// buckets are followed by an array of entries that each store the hash code,
+/// the index of the next entry, the key and the value. Games that use the old
+/// Mono class libraries of the .NET 2.0 profile store the links, the keys and
+/// the values in separate arrays instead. That layout isn't detected, so
+/// reading such a dictionary returns garbage or nothing at all.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unity::{mono::Module, CsDictionary}, Address, Address64, Process};
+/// # fn example(process: &Process, module: &Module, flags: Address) {
+/// let flags = CsDictionary::<Address64, bool>::new(flags, module.pointer_size());
+/// if flags.get_by_str(process, "BossDefeated") == Some(true) {
+///     // Split.
+/// }
+/// # }
+/// ```
+pub struct CsDictionary<K, V> {
+    address: Address,
+    pointer_size: PointerSize,
+    _entry: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> Clone for CsDictionary<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for CsDictionary<K, V> {}
+
+impl<K: CheckedBitPattern, V: CheckedBitPattern> CsDictionary<K, V> {
+    /// The entries start with the hash code and the index of the next entry.
+    const KEY_OFFSET: usize = align_up(8, mem::align_of::<K>());
+    const VALUE_OFFSET: usize =
+        align_up(Self::KEY_OFFSET + mem::size_of::<K>(), mem::align_of::<V>());
+    const ENTRY_SIZE: usize = align_up(
+        Self::VALUE_OFFSET + mem::size_of::<V>(),
+        max(4, max(mem::align_of::<K>(), mem::align_of::<V>())),
+    );
+
+    /// Creates a reader for the dictionary object at the address given. The
+    /// pointer size is available from the attached module.
+    pub const fn new(address: Address, pointer_size: PointerSize) -> Self {
+        Self {
+            address,
+            pointer_size,
+            _entry: PhantomData,
+        }
+    }
+
+    /// Returns the address of the dictionary object.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    fn read_array<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        index: u64,
+    ) -> Result<CsArray<T>, Error> {
+        let array = process.read_pointer(
+            self.address + (OBJECT_HEADER_PTRS + index) * self.pointer_size as u64,
+            self.pointer_size,
+        )?;
+        if array.is_null() {
+            return Err(Error {});
+        }
+        Ok(CsArray::new(array, self.pointer_size))
+    }
+
+    /// Reads the buckets along with their length and the address of the
+    /// entries along with their length.
+    fn read_buckets_and_entries(
+        &self,
+        process: &Process,
+    ) -> Result<(CsArray<i32>, usize, Address, usize), Error> {
+        let buckets = self.read_array::<i32>(process, 0)?;
+        let entries = self.read_array::<u8>(process, 1)?;
+        Ok((
+            buckets,
+            buckets.read_len(process)?,
+            entries.elements_address(),
+            entries.read_len(process)?,
+        ))
+    }
+
+    /// Iterates over the entries of the dictionary. Each entry is read
+    /// individually, so no buffer is needed. The iteration stops early if an
+    /// entry can't be read. A dictionary that never had any entries may not
+    /// have any buckets yet, which is treated as being empty.
+    pub fn iter<'a>(&self, process: &'a Process) -> impl Iterator<Item = (K, V)> + 'a
+    where
+        K: 'a,
+        V: 'a,
+    {
+        let arrays = self.read_buckets_and_entries(process).ok();
+
+        let mut bucket = 0;
+        let mut entry = -1;
+        // Every entry is part of exactly one chain, so the number of entries
+        // limits how many can be visited, even if the chains are corrupted.
+        let mut remaining = arrays.map_or(0, |(_, _, _, entries_len)| entries_len);
+
+        iter::from_fn(move || {
+            let (buckets, buckets_len, entries, entries_len) = arrays?;
+            while entry < 0 {
+                if bucket >= buckets_len {
+                    return None;
+                }
+                entry = buckets.read_elem_unchecked(process, bucket).ok()?;
+                bucket += 1;
+            }
+            if remaining == 0 || entry as usize >= entries_len {
+                return None;
+            }
+            remaining -= 1;
+
+            let address = entries + (entry as usize * Self::ENTRY_SIZE) as u64;
+            entry = process.read::<i32>(address + 4).ok()?;
+            let key = process.read(address + Self::KEY_OFFSET as u64).ok()?;
+            let value = process.read(address + Self::VALUE_OFFSET as u64).ok()?;
+            Some((key, value))
+        })
+    }
+
+    /// Looks up the value of the first entry whose key matches the predicate.
+    pub fn find(&self, process: &Process, mut predicate: impl FnMut(&K) -> bool) -> Option<V> {
+        self.iter(process)
+            .find_map(|(key, value)| predicate(&key).then_some(value))
+    }
+
+    /// Looks up the value for the key given. This is meant for keys that are
+    /// compared by value, such as [`i32`] or enums.
+    pub fn get(&self, process: &Process, key: &K) -> Option<V>
+    where
+        K: PartialEq,
+    {
+        self.find(process, |k| k == key)
+    }
+
+    /// Looks up the value for the string key given. The keys need to be
+    /// references to .NET strings.
+    pub fn get_by_str(&self, process: &Process, key: &str) -> Option<V>
+    where
+        K: Into<Address> + Copy,
+    {
+        self.find(process, |&k| {
+            CsString::new(k.into(), self.pointer_size).matches(process, key)
+        })
+    }
+
+    /// Reads all the entries of the dictionary into a new `Vec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self, process: &Process) -> alloc::vec::Vec<(K, V)> {
+        self.iter(process).collect()
+    }
+}
+
+const fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
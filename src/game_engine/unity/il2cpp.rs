@@ -1,12 +1,22 @@
 //! Support for attaching to Unity games that are using the IL2CPP backend.
 
-use core::{array, cell::RefCell, iter};
+use core::{
+    array,
+    cell::{Cell, RefCell},
+    iter,
+    marker::PhantomData,
+};
 
 use crate::{
-    deep_pointer::DeepPointer, file_format::pe, future::retry, signature::Signature,
-    string::ArrayCString, Address, Address64, Error, PointerSize, Process,
+    deep_pointer::DeepPointer,
+    file_format::pe,
+    future::retry,
+    signature::Signature,
+    string::{from_utf8_lossy, ArrayCString, ArrayString},
+    Address, Address64, Error, PointerSize, Process,
 };
 
+use arrayvec::ArrayVec;
 #[cfg(feature = "derive")]
 pub use asr_derive::Il2cppClass as Class;
 use bytemuck::CheckedBitPattern;
@@ -18,6 +28,7 @@ pub struct Module {
     pointer_size: PointerSize,
     version: Version,
     offsets: &'static Offsets,
+    module_address: Address,
     assemblies: Address,
     type_info_definition_table: Address,
 }
@@ -97,11 +108,44 @@ impl Module {
             pointer_size,
             version,
             offsets,
+            module_address: mono_module.0,
             assemblies,
             type_info_definition_table,
         })
     }
 
+    /// Checks whether the `GameAssembly.dll` module that was attached to is still
+    /// loaded. Some games unload and reload it, for example when switching
+    /// from a launcher to the actual game, after which everything that was
+    /// looked up through the module is stale. Use [`reattach`](Self::reattach)
+    /// to attach to the reloaded module, or use a [`ModuleCache`] that does so
+    /// automatically.
+    pub fn is_valid(&self, process: &Process) -> bool {
+        pe::MachineType::read(process, self.module_address).is_some()
+            && process.read::<u8>(self.assemblies).is_ok()
+    }
+
+    /// Tries attaching to the module again with the same [version](Version).
+    /// This is meant to be used once the module is no longer
+    /// [valid](Self::is_valid). Returns [`true`] if attaching succeeded.
+    /// Otherwise the module is left unchanged.
+    pub fn reattach(&mut self, process: &Process) -> bool {
+        match Self::attach(process, self.version) {
+            Some(module) => {
+                *self = module;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attaches to the module again with the same [version](Version). This is
+    /// the `await`able version of the [`reattach`](Self::reattach) function,
+    /// yielding back to the runtime between each try.
+    pub async fn wait_reattach(&mut self, process: &Process) {
+        *self = Self::wait_attach(process, self.version).await;
+    }
+
     fn assemblies<'a>(
         &'a self,
         process: &'a Process,
@@ -212,12 +256,121 @@ impl Module {
         retry(|| self.get_default_image(process)).await
     }
 
+    /// Returns the size of pointers in the game. This is needed for reading
+    /// the [collections](super::CsList) of the game.
+    #[inline]
+    pub const fn pointer_size(&self) -> PointerSize {
+        self.pointer_size
+    }
+
     #[inline]
     const fn size_of_ptr(&self) -> u64 {
         self.pointer_size as u64
     }
 }
 
+/// Caches the [default image](Module::get_default_image) of a [`Module`] and
+/// up to `CLASSES` [classes](struct@Class) that are looked up by name. The
+/// cache is meant to be [updated](Self::update) at the start of every tick.
+/// If the game reloaded the module in the meantime, the module is attached to
+/// again and the cache is cleared, so the image and the classes are looked up
+/// again the next time they are accessed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::il2cpp::{Module, ModuleCache};
+/// # async fn example(process: &asr::Process) {
+/// let mut cache = ModuleCache::<4>::new(Module::wait_attach_auto_detect(process).await);
+/// loop {
+///     if !cache.update(process) {
+///         // The module is currently not loaded.
+/// #       break;
+///     }
+///     if let Some(timer) = cache.get_class(process, "Timer") {
+///         let time = timer.get_static_value::<f32>(process, cache.module(), "time");
+///     }
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct ModuleCache<const CLASSES: usize> {
+    module: Module,
+    generation: u32,
+    image: Cell<Option<Image>>,
+    classes: RefCell<ArrayVec<(&'static str, Class), CLASSES>>,
+}
+
+impl<const CLASSES: usize> ModuleCache<CLASSES> {
+    /// Creates a new empty cache for the module given.
+    pub const fn new(module: Module) -> Self {
+        Self {
+            module,
+            generation: 0,
+            image: Cell::new(None),
+            classes: RefCell::new(ArrayVec::new_const()),
+        }
+    }
+
+    /// Returns the module that is currently attached to.
+    pub const fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Returns the number of times the module was attached to again. This
+    /// can be used to notice when offsets or addresses that were looked up
+    /// outside of the cache need to be looked up again.
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Checks whether the module is still [valid](Module::is_valid). If it
+    /// isn't, the cache is cleared and the module is attached to again.
+    /// Returns [`true`] if the module is valid afterwards.
+    pub fn update(&mut self, process: &Process) -> bool {
+        if self.module.is_valid(process) {
+            return true;
+        }
+        self.image.set(None);
+        self.classes.get_mut().clear();
+        if !self.module.reattach(process) {
+            return false;
+        }
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Looks up the `Assembly-CSharp` [image](Image) or returns it from the
+    /// cache. Check [`Module::get_default_image`] for more information.
+    pub fn get_default_image(&self, process: &Process) -> Option<Image> {
+        if let Some(image) = self.image.get() {
+            return Some(image);
+        }
+        let image = self.module.get_default_image(process)?;
+        self.image.set(Some(image));
+        Some(image)
+    }
+
+    /// Looks up the [class](struct@Class) with the specified name in the
+    /// `Assembly-CSharp` [image](Image) or returns it from the cache. If the
+    /// cache is full, the class is still looked up, but not cached.
+    pub fn get_class(&self, process: &Process, class_name: &'static str) -> Option<Class> {
+        if let Some(&(_, class)) = self
+            .classes
+            .borrow()
+            .iter()
+            .find(|(name, _)| *name == class_name)
+        {
+            return Some(class);
+        }
+        let class =
+            self.get_default_image(process)?
+                .get_class(process, &self.module, class_name)?;
+        let _ = self.classes.borrow_mut().try_push((class_name, class));
+        Some(class)
+    }
+}
+
 #[derive(Copy, Clone)]
 struct Assembly {
     assembly: Address,
@@ -355,11 +508,41 @@ impl Class {
         )
     }
 
-    fn fields<'a>(
-        &'a self,
+    /// Iterates over all the [fields](Field) of the class, including the ones
+    /// inherited from its parent classes. The fields of the class itself come
+    /// first. The fields of `Object` and of the classes in the `UnityEngine`
+    /// namespace are not included.
+    ///
+    /// # Example
+    ///
+    /// This can be used to print all the fields of a class, which is useful
+    /// when figuring out what changed in a new version of a game.
+    ///
+    /// ```no_run
+    /// # use asr::game_engine::unity::il2cpp::{Class, Module};
+    /// # fn example(process: &asr::Process, module: &Module, class: Class) {
+    /// for field in class.fields(process, module) {
+    ///     let Ok(name) = field.get_name::<128>(process, module) else {
+    ///         continue;
+    ///     };
+    ///     let type_name = field.get_type_name::<64>(process, module).unwrap_or_default();
+    ///     let offset = field.get_offset(process, module).unwrap_or_default();
+    ///     let kind = match field.is_static(process, module) {
+    ///         Some(true) => "static",
+    ///         _ => "instance",
+    ///     };
+    ///     asr::print_limited::<256>(&format_args!(
+    ///         "{kind} {type_name} {} at 0x{offset:X}",
+    ///         name.validate_utf8().unwrap_or("?"),
+    ///     ));
+    /// }
+    /// # }
+    /// ```
+    pub fn fields<'a>(
+        &self,
         process: &'a Process,
         module: &'a Module,
-    ) -> impl Iterator<Item = Field> + '_ {
+    ) -> impl Iterator<Item = Field> + 'a {
         let mut this_class = Class { class: self.class };
         let mut iter_break = this_class.class.is_null();
 
@@ -420,13 +603,25 @@ impl Class {
         module: &Module,
         field_name: &str,
     ) -> Option<u32> {
-        self.fields(process, module)
-            .find(|field| {
-                field
-                    .get_name::<CSTR>(process, module)
-                    .is_ok_and(|name| name.matches(field_name))
-            })?
-            .get_offset(process, module)
+        self.field_by_predicate(process, module, |field| {
+            field
+                .get_name::<CSTR>(process, module)
+                .is_ok_and(|name| name.matches(field_name))
+        })?
+        .get_offset(process, module)
+    }
+
+    /// Tries to find the first [field](Field) of the class that matches the
+    /// predicate. The fields are visited in the same order as in
+    /// [`fields`](Self::fields). This is useful for finding fields whose names
+    /// are not stable, such as obfuscated or compiler generated fields.
+    pub fn field_by_predicate(
+        &self,
+        process: &Process,
+        module: &Module,
+        mut predicate: impl FnMut(&Field) -> bool,
+    ) -> Option<Field> {
+        self.fields(process, module).find(|field| predicate(field))
     }
 
     /// Tries to find the address of a static instance of the class based on its
@@ -456,6 +651,51 @@ impl Class {
         self.class + module.offsets.monoclass_static_fields
     }
 
+    fn get_static_field_address(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> Option<Address> {
+        let field = self.field_by_predicate(process, module, |field| {
+            field
+                .get_name::<CSTR>(process, module)
+                .is_ok_and(|name| name.matches(field_name))
+        })?;
+        if field.is_static(process, module) == Some(false) {
+            return None;
+        }
+        Some(self.get_static_table(process, module)? + field.get_offset(process, module)?)
+    }
+
+    /// Reads the value of the static field with the specified name. This
+    /// combines looking up the [static table](Self::get_static_table) and the
+    /// [offset of the field](Self::get_field_offset), so every call looks up
+    /// the field again. Use a [`StaticField`] to only look it up once.
+    pub fn get_static_value<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> Option<T> {
+        process
+            .read(self.get_static_field_address(process, module, field_name)?)
+            .ok()
+    }
+
+    /// Reads the value of the static field with the specified name. This is
+    /// the `await`able version of the
+    /// [`get_static_value`](Self::get_static_value) function, yielding back to
+    /// the runtime between each try.
+    pub async fn wait_get_static_value<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> T {
+        retry(|| self.get_static_value(process, module, field_name)).await
+    }
+
     /// Returns the address of the static table of the class. This contains the
     /// values of all the static fields.
     pub fn get_static_table(&self, process: &Process, module: &Module) -> Option<Address> {
@@ -505,13 +745,15 @@ impl Class {
     }
 }
 
+/// A field of a [.NET class](struct@Class).
 #[derive(Copy, Clone)]
-struct Field {
+pub struct Field {
     field: Address,
 }
 
 impl Field {
-    fn get_name<const N: usize>(
+    /// Reads the name of the field.
+    pub fn get_name<const N: usize>(
         &self,
         process: &Process,
         module: &Module,
@@ -523,14 +765,219 @@ impl Field {
         )
     }
 
-    fn get_offset(&self, process: &Process, module: &Module) -> Option<u32> {
+    /// Reads the offset of the field. For instance fields, this is the offset
+    /// from the start of an instance of the class. For [static
+    /// fields](Self::is_static), this is the offset from the start of the
+    /// [static table](Class::get_static_table) of the class instead.
+    pub fn get_offset(&self, process: &Process, module: &Module) -> Option<u32> {
         process
             .read(self.field + module.offsets.monoclassfield_offset)
             .ok()
     }
+
+    fn get_type(&self, process: &Process, module: &Module) -> Option<Address> {
+        process
+            .read_pointer(
+                self.field + module.offsets.monoclassfield_type,
+                module.pointer_size,
+            )
+            .ok()
+            .filter(|val| !val.is_null())
+    }
+
+    /// Checks whether the field is static. The offset of a static field is
+    /// relative to the [static table](Class::get_static_table) of the class.
+    pub fn is_static(&self, process: &Process, module: &Module) -> Option<bool> {
+        let attrs = process
+            .read::<u16>(self.get_type(process, module)? + module.size_of_ptr())
+            .ok()?;
+        Some(attrs & super::FIELD_ATTRIBUTE_STATIC != 0)
+    }
+
+    /// Tries to determine the name of the type of the field. Built-in types
+    /// use their C# names, such as `int` or `string`, while all other types
+    /// use the name of their [class](Self::get_class) without its namespace,
+    /// such as ``List`1``. This is a best effort, so types without a class,
+    /// such as arrays, return [`None`].
+    pub fn get_type_name<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Option<ArrayString<N>> {
+        let ty = self.get_type(process, module)?;
+        let type_enum = process.read::<u8>(ty + module.size_of_ptr() + 2).ok()?;
+
+        if let Some(name) = super::builtin_type_name(type_enum) {
+            return Some(from_utf8_lossy(name.as_bytes()));
+        }
+
+        let name = Self::get_type_class(process, module, ty, type_enum)?
+            .get_name::<CSTR>(process, module)
+            .ok()?;
+        Some(name.to_array_string())
+    }
+
+    /// Tries to find the [class](struct@Class) of the type of the field. This
+    /// is useful for generic types, such as `List<T>`, as their instantiations
+    /// can't be found by name in the [image](Image). The class of a generic
+    /// instantiation has the fields laid out for its type arguments, so
+    /// [`get_field_offset`](Class::get_field_offset) works on it. IL2CPP only
+    /// creates it once the game uses the instantiation, so this may fail
+    /// early on.
+    ///
+    /// Classes and value types that aren't generic can only be resolved before
+    /// the [2020 version](Version::V2020).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::game_engine::unity::il2cpp::{Class, Module};
+    /// # fn example(process: &asr::Process, module: &Module, player_class: Class) {
+    /// // The class has a field `List<PlayerData> inventory`.
+    /// let inventory = player_class
+    ///     .field_by_predicate(process, module, |field| {
+    ///         field
+    ///             .get_name::<128>(process, module)
+    ///             .is_ok_and(|name| name.matches("inventory"))
+    ///     })
+    ///     .unwrap();
+    /// let list_class = inventory.get_class(process, module).unwrap();
+    /// let items_offset = list_class.get_field_offset(process, module, "_items");
+    /// let size_offset = list_class.get_field_offset(process, module, "_size");
+    /// # }
+    /// ```
+    pub fn get_class(&self, process: &Process, module: &Module) -> Option<Class> {
+        let ty = self.get_type(process, module)?;
+        let type_enum = process.read::<u8>(ty + module.size_of_ptr() + 2).ok()?;
+        Self::get_type_class(process, module, ty, type_enum)
+    }
+
+    fn get_type_class(
+        process: &Process,
+        module: &Module,
+        ty: Address,
+        type_enum: u8,
+    ) -> Option<Class> {
+        let class = match (type_enum, module.version) {
+            (super::TYPE_GENERICINST, _) => {
+                let generic_class = process
+                    .read_pointer(ty, module.pointer_size)
+                    .ok()
+                    .filter(|val| !val.is_null())?;
+                process
+                    .read_pointer(
+                        generic_class + module.offsets.il2cppgenericclass_cached_class,
+                        module.pointer_size,
+                    )
+                    .ok()?
+            }
+            // Starting with 2020, the type refers to its class through a
+            // handle into the metadata instead.
+            (_, Version::V2020) => return None,
+            (super::TYPE_CLASS | super::TYPE_VALUETYPE, _) => {
+                // The type refers to its class through an index into the type
+                // info definition table.
+                let index = process.read::<u32>(ty).ok()?;
+                process
+                    .read_pointer(
+                        module.type_info_definition_table
+                            + (index as u64).wrapping_mul(module.size_of_ptr()),
+                        module.pointer_size,
+                    )
+                    .ok()?
+            }
+            _ => return None,
+        };
+
+        if class.is_null() {
+            None
+        } else {
+            Some(Class { class })
+        }
+    }
+}
+
+/// A static field of a [.NET class](struct@Class) whose address is looked up
+/// once and then remembered. If reading the value fails, for example because
+/// the game reloaded its domain and the static table moved, the address is
+/// looked up again.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::il2cpp::{Class, Module, StaticField};
+/// # fn example(process: &asr::Process, module: &Module, game_manager: Class) {
+/// let is_loading = StaticField::<bool>::new(game_manager, "isLoading");
+/// loop {
+///     if let Some(is_loading) = is_loading.read(process, module) {
+///         // Do something with the value.
+///     }
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct StaticField<T> {
+    class: Class,
+    field_name: &'static str,
+    address: Cell<Option<Address>>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T: CheckedBitPattern> StaticField<T> {
+    /// Creates a new static field of the class with the specified name. The
+    /// address of the field is only looked up once it is read.
+    pub const fn new(class: Class, field_name: &'static str) -> Self {
+        Self {
+            class,
+            field_name,
+            address: Cell::new(None),
+            _value: PhantomData,
+        }
+    }
+
+    /// Reads the value of the static field. The address of the field is
+    /// looked up if it isn't known yet or if reading from the remembered
+    /// address fails.
+    pub fn read(&self, process: &Process, module: &Module) -> Option<T> {
+        if let Some(address) = self.address.get() {
+            if let Ok(value) = process.read(address) {
+                return Some(value);
+            }
+        }
+
+        let address = self
+            .class
+            .get_static_field_address(process, module, self.field_name);
+        self.address.set(address);
+        process.read(address?).ok()
+    }
+
+    /// Forgets the address of the static field, so it is looked up again the
+    /// next time it is read.
+    pub fn invalidate(&self) {
+        self.address.set(None);
+    }
 }
 
 /// An IL2CPP-specific implementation for automatic pointer path resolution
+///
+/// The pointer path starts at a static field of a class and continues through
+/// the fields of the instances it points to. The class and the offsets of the
+/// fields are looked up by their names the first time the pointer path is
+/// dereferenced and are cached afterwards. The fields can also be specified
+/// as offsets directly, either in decimal or in hexadecimal with a `0x`
+/// prefix.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::il2cpp::{Image, Module, UnityPointer};
+/// # async fn example(process: &asr::Process, module: &Module, image: &Image) {
+/// // GameManager.instance.player.health
+/// let health = UnityPointer::<3>::new("GameManager", 0, &["instance", "player", "health"]);
+/// let health = health.wait_deref::<f32>(process, module, image).await;
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct UnityPointer<const CAP: usize> {
     cache: RefCell<UnityPointerCache<CAP>>,
@@ -584,7 +1031,7 @@ impl<const CAP: usize> UnityPointer<CAP> {
         let mut cache = self.cache.borrow_mut();
 
         // If the pointer path has already been found, there's no need to continue
-        if cache.resolved_offsets == self.depth {
+        if cache.resolved_offsets == self.depth && !cache.base_address.is_null() {
             return Ok(());
         }
 
@@ -681,6 +1128,36 @@ impl<const CAP: usize> UnityPointer<CAP> {
         Ok(())
     }
 
+    /// Looks up the address of the static table again the next time the
+    /// pointer path is dereferenced. This is done whenever dereferencing fails,
+    /// as the static table may have moved, for example when the game reloads
+    /// its domain. The classes and the offsets of the fields stay cached.
+    fn invalidate_base_address(&self) {
+        self.cache.borrow_mut().base_address = Address::NULL;
+    }
+
+    /// Forgets everything that was resolved so far, so that the class and all
+    /// the offsets are looked up again the next time the pointer path is
+    /// dereferenced. This is needed if the game reloaded its module, as the
+    /// cached class is stale then.
+    pub fn reset(&self) {
+        let mut cache = self.cache.borrow_mut();
+        cache.base_address = Address::NULL;
+        cache.resolved_offsets = 0;
+        cache.current_instance_pointer = None;
+        cache.starting_class = None;
+    }
+
+    fn deref_offsets_cached(&self, process: &Process, module: &Module) -> Result<Address, Error> {
+        let cache = self.cache.borrow();
+        let mut address = cache.base_address;
+        let (&last, path) = cache.offsets[..self.depth].split_last().ok_or(Error {})?;
+        for &offset in path {
+            address = process.read_pointer(address + offset, module.pointer_size)?;
+        }
+        Ok(address + last)
+    }
+
     /// Dereferences the pointer path, returning the memory address of the value of interest
     pub fn deref_offsets(
         &self,
@@ -689,13 +1166,11 @@ impl<const CAP: usize> UnityPointer<CAP> {
         image: &Image,
     ) -> Result<Address, Error> {
         self.find_offsets(process, module, image)?;
-        let cache = self.cache.borrow();
-        let mut address = cache.base_address;
-        let (&last, path) = cache.offsets[..self.depth].split_last().ok_or(Error {})?;
-        for &offset in path {
-            address = process.read_pointer(address + offset, module.pointer_size)?;
+        let address = self.deref_offsets_cached(process, module);
+        if address.is_err() {
+            self.invalidate_base_address();
         }
-        Ok(address + last)
+        address
     }
 
     /// Dereferences the pointer path, returning the value stored at the final memory address
@@ -706,12 +1181,31 @@ impl<const CAP: usize> UnityPointer<CAP> {
         image: &Image,
     ) -> Result<T, Error> {
         self.find_offsets(process, module, image)?;
-        let cache = self.cache.borrow();
-        process.read_pointer_path(
-            cache.base_address,
-            module.pointer_size,
-            &cache.offsets[..self.depth],
-        )
+        let value = {
+            let cache = self.cache.borrow();
+            process.read_pointer_path(
+                cache.base_address,
+                module.pointer_size,
+                &cache.offsets[..self.depth],
+            )
+        };
+        if value.is_err() {
+            self.invalidate_base_address();
+        }
+        value
+    }
+
+    /// Dereferences the pointer path, returning the value stored at the final
+    /// memory address. This is the `await`able version of the
+    /// [`deref`](Self::deref) function, yielding back to the runtime between
+    /// each try.
+    pub async fn wait_deref<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        image: &Image,
+    ) -> T {
+        retry(|| self.deref(process, module, image).ok()).await
     }
 
     /// Generates a `DeepPointer` struct based on the offsets
@@ -746,7 +1240,9 @@ struct Offsets {
     monoclass_parent: u8,
     monoclassfield_structsize: u8,
     monoclassfield_name: u8,
+    monoclassfield_type: u8,
     monoclassfield_offset: u8,
+    il2cppgenericclass_cached_class: u8,
 }
 
 impl Offsets {
@@ -768,7 +1264,9 @@ impl Offsets {
                         monoclass_parent: 0x58,
                         monoclassfield_structsize: 0x20,
                         monoclassfield_name: 0x0,
+                        monoclassfield_type: 0x8,
                         monoclassfield_offset: 0x18,
+                        il2cppgenericclass_cached_class: 0x18,
                     },
                     Version::V2019 => &Self {
                         monoassembly_image: 0x0,
@@ -784,7 +1282,9 @@ impl Offsets {
                         monoclass_parent: 0x58,
                         monoclassfield_structsize: 0x20,
                         monoclassfield_name: 0x0,
+                        monoclassfield_type: 0x8,
                         monoclassfield_offset: 0x18,
+                        il2cppgenericclass_cached_class: 0x18,
                     },
                     Version::V2020 => &Self {
                         monoassembly_image: 0x0,
@@ -800,7 +1300,9 @@ impl Offsets {
                         monoclass_parent: 0x58,
                         monoclassfield_structsize: 0x20,
                         monoclassfield_name: 0x0,
+                        monoclassfield_type: 0x8,
                         monoclassfield_offset: 0x18,
+                        il2cppgenericclass_cached_class: 0x18,
                     },
                 })
             }
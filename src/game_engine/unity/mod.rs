@@ -86,8 +86,43 @@
 pub mod il2cpp;
 pub mod mono;
 
+mod collections;
 mod scene;
-pub use self::scene::*;
+pub use self::{collections::*, scene::*};
+
+/// The attribute flag of a field that marks it as static.
+const FIELD_ATTRIBUTE_STATIC: u16 = 0x10;
+
+/// The type enum values of classes, value types and generic instances. Their
+/// name needs to be read from the class itself.
+const TYPE_VALUETYPE: u8 = 0x11;
+const TYPE_CLASS: u8 = 0x12;
+const TYPE_GENERICINST: u8 = 0x15;
+
+/// Returns the C# name of a built-in type based on its type enum value, which
+/// is shared by both Mono and IL2CPP.
+const fn builtin_type_name(type_enum: u8) -> Option<&'static str> {
+    Some(match type_enum {
+        0x01 => "void",
+        0x02 => "bool",
+        0x03 => "char",
+        0x04 => "sbyte",
+        0x05 => "byte",
+        0x06 => "short",
+        0x07 => "ushort",
+        0x08 => "int",
+        0x09 => "uint",
+        0x0A => "long",
+        0x0B => "ulong",
+        0x0C => "float",
+        0x0D => "double",
+        0x0E => "string",
+        0x18 => "IntPtr",
+        0x19 => "UIntPtr",
+        0x1C => "object",
+        _ => return None,
+    })
+}
 
 fn value_from_string(value: &str) -> Option<u32> {
     if let Some(rem) = value.strip_prefix("0x") {
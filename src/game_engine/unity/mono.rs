@@ -1,23 +1,57 @@
 //! Support for attaching to Unity games that are using the standard Mono
 //! backend.
+//!
+//! The following builds of Mono are supported:
+//!
+//! | Module                                      | Platform | Pointer size   | Version    |
+//! |---------------------------------------------|----------|----------------|------------|
+//! | `mono.dll`                                  | Windows  | 32-bit, 64-bit | [`V1`](Version::V1) |
+//! | `mono-2.0-bdwgc.dll`                        | Windows  | 32-bit, 64-bit | [`V2`](Version::V2), [`V3`](Version::V3) |
+//! | `libmono.so`                                | Linux    | 64-bit         | [`V1`](Version::V1) |
+//! | `libmonobdwgc-2.0.so`                       | Linux    | 64-bit         | [`V2`](Version::V2), [`V3`](Version::V3) |
+//!
+//! The layouts of the structs are the same on Windows and Linux. 32-bit
+//! builds of Mono for Linux are not supported, as they access the list of
+//! assemblies through the global offset table. The macOS builds, such as
+//! `libmonobdwgc-2.0.dylib`, are not supported either, as Mach-O modules can't
+//! be parsed, so their symbols can't be looked up.
 
 use crate::{
-    deep_pointer::DeepPointer, file_format::pe, future::retry, signature::Signature,
-    string::ArrayCString, Address, Address32, Address64, Error, PointerSize, Process,
+    deep_pointer::DeepPointer,
+    file_format::{elf, pe},
+    future::retry,
+    signature::Signature,
+    string::{from_utf8_lossy, ArrayCString, ArrayString},
+    Address, Address32, Address64, Error, PointerSize, Process,
+};
+use core::{
+    array,
+    cell::{Cell, RefCell},
+    iter,
+    marker::PhantomData,
 };
-use core::{array, cell::RefCell, iter};
 
+use arrayvec::ArrayVec;
 #[cfg(feature = "derive")]
 pub use asr_derive::MonoClass as Class;
 use bytemuck::CheckedBitPattern;
 
 const CSTR: usize = 128;
 
+/// The names of the Mono modules, the ones used by [`Version::V1`] first.
+const MODULE_NAMES: [&str; 4] = [
+    "mono.dll",
+    "libmono.so",
+    "mono-2.0-bdwgc.dll",
+    "libmonobdwgc-2.0.so",
+];
+
 /// Represents access to a Unity game that is using the standard Mono backend.
 pub struct Module {
     pointer_size: PointerSize,
     version: Version,
     offsets: &'static Offsets,
+    module_address: Address,
     assemblies: Address,
 }
 
@@ -36,34 +70,56 @@ impl Module {
     /// correct for this function to work. If you don't know the version in
     /// advance, use [`attach_auto_detect`](Self::attach_auto_detect) instead.
     pub fn attach(process: &Process, version: Version) -> Option<Self> {
-        let module = ["mono.dll", "mono-2.0-bdwgc.dll"]
+        let module = MODULE_NAMES
             .iter()
             .find_map(|&name| process.get_module_address(name).ok())?;
 
-        let pointer_size = match pe::MachineType::read(process, module)? {
-            pe::MachineType::X86_64 => PointerSize::Bit64,
-            _ => PointerSize::Bit32,
+        let machine_type = pe::MachineType::read(process, module);
+        let is_pe = machine_type.is_some();
+
+        let (pointer_size, root_domain_function_address) = if let Some(machine_type) = machine_type
+        {
+            let pointer_size = match machine_type {
+                pe::MachineType::X86_64 => PointerSize::Bit64,
+                _ => PointerSize::Bit32,
+            };
+
+            let address = pe::symbols(process, module)
+                .find(|symbol| {
+                    symbol
+                        .get_name::<25>(process)
+                        .is_ok_and(|name| name.matches("mono_assembly_foreach"))
+                })?
+                .address;
+
+            (pointer_size, address)
+        } else {
+            let pointer_size = match elf::is_64_bit(process, module)? {
+                true => PointerSize::Bit64,
+                false => PointerSize::Bit32,
+            };
+
+            let address = elf::find_address(process, module, "mono_assembly_foreach")?;
+
+            (pointer_size, address)
         };
 
         let offsets = Offsets::new(version, pointer_size)?;
 
-        let root_domain_function_address = pe::symbols(process, module)
-            .find(|symbol| {
-                symbol
-                    .get_name::<25>(process)
-                    .is_ok_and(|name| name.matches("mono_assembly_foreach"))
-            })?
-            .address;
-
         let assemblies_pointer: Address = match pointer_size {
             PointerSize::Bit64 => {
+                // The list is passed as the first argument, which is `rcx` on
+                // Windows and `rdi` on Linux.
                 const SIG_MONO_64: Signature<3> = Signature::new("48 8B 0D");
-                let scan_address: Address = SIG_MONO_64
-                    .scan_process_range(process, (root_domain_function_address, 0x100))?
-                    + 3;
+                const SIG_MONO_64_SYSV: Signature<3> = Signature::new("48 8B 3D");
+                let sig = if is_pe { SIG_MONO_64 } else { SIG_MONO_64_SYSV };
+                let scan_address: Address =
+                    sig.scan_process_range(process, (root_domain_function_address, 0x100))? + 3;
                 scan_address + 0x4 + process.read::<i32>(scan_address).ok()?
             }
-            PointerSize::Bit32 => {
+            // 32-bit Linux builds access the list through the global offset
+            // table, which isn't supported.
+            PointerSize::Bit32 if is_pe => {
                 const SIG_32_1: Signature<2> = Signature::new("FF 35");
                 const SIG_32_2: Signature<2> = Signature::new("8B 0D");
 
@@ -85,10 +141,44 @@ impl Module {
             pointer_size,
             version,
             offsets,
+            module_address: module,
             assemblies,
         })
     }
 
+    /// Checks whether the Mono module that was attached to is still
+    /// loaded. Some games unload and reload it, for example when switching
+    /// from a launcher to the actual game, after which everything that was
+    /// looked up through the module is stale. Use [`reattach`](Self::reattach)
+    /// to attach to the reloaded module, or use a [`ModuleCache`] that does so
+    /// automatically.
+    pub fn is_valid(&self, process: &Process) -> bool {
+        (pe::MachineType::read(process, self.module_address).is_some()
+            || elf::is_64_bit(process, self.module_address).is_some())
+            && process.read::<u8>(self.assemblies).is_ok()
+    }
+
+    /// Tries attaching to the module again with the same [version](Version).
+    /// This is meant to be used once the module is no longer
+    /// [valid](Self::is_valid). Returns [`true`] if attaching succeeded.
+    /// Otherwise the module is left unchanged.
+    pub fn reattach(&mut self, process: &Process) -> bool {
+        match Self::attach(process, self.version) {
+            Some(module) => {
+                *self = module;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attaches to the module again with the same [version](Version). This is
+    /// the `await`able version of the [`reattach`](Self::reattach) function,
+    /// yielding back to the runtime between each try.
+    pub async fn wait_reattach(&mut self, process: &Process) {
+        *self = Self::wait_attach(process, self.version).await;
+    }
+
     fn assemblies<'a>(&'a self, process: &'a Process) -> impl Iterator<Item = Assembly> + 'a {
         let mut assembly = self.assemblies;
         let mut iter_break = assembly.is_null();
@@ -195,12 +285,121 @@ impl Module {
         retry(|| self.get_default_image(process)).await
     }
 
+    /// Returns the size of pointers in the game. This is needed for reading
+    /// the [collections](super::CsList) of the game.
+    #[inline]
+    pub const fn pointer_size(&self) -> PointerSize {
+        self.pointer_size
+    }
+
     #[inline]
     const fn size_of_ptr(&self) -> u64 {
         self.pointer_size as u64
     }
 }
 
+/// Caches the [default image](Module::get_default_image) of a [`Module`] and
+/// up to `CLASSES` [classes](struct@Class) that are looked up by name. The
+/// cache is meant to be [updated](Self::update) at the start of every tick.
+/// If the game reloaded the module in the meantime, the module is attached to
+/// again and the cache is cleared, so the image and the classes are looked up
+/// again the next time they are accessed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::mono::{Module, ModuleCache};
+/// # async fn example(process: &asr::Process) {
+/// let mut cache = ModuleCache::<4>::new(Module::wait_attach_auto_detect(process).await);
+/// loop {
+///     if !cache.update(process) {
+///         // The module is currently not loaded.
+/// #       break;
+///     }
+///     if let Some(timer) = cache.get_class(process, "Timer") {
+///         let time = timer.get_static_value::<f32>(process, cache.module(), "time");
+///     }
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct ModuleCache<const CLASSES: usize> {
+    module: Module,
+    generation: u32,
+    image: Cell<Option<Image>>,
+    classes: RefCell<ArrayVec<(&'static str, Class), CLASSES>>,
+}
+
+impl<const CLASSES: usize> ModuleCache<CLASSES> {
+    /// Creates a new empty cache for the module given.
+    pub const fn new(module: Module) -> Self {
+        Self {
+            module,
+            generation: 0,
+            image: Cell::new(None),
+            classes: RefCell::new(ArrayVec::new_const()),
+        }
+    }
+
+    /// Returns the module that is currently attached to.
+    pub const fn module(&self) -> &Module {
+        &self.module
+    }
+
+    /// Returns the number of times the module was attached to again. This
+    /// can be used to notice when offsets or addresses that were looked up
+    /// outside of the cache need to be looked up again.
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Checks whether the module is still [valid](Module::is_valid). If it
+    /// isn't, the cache is cleared and the module is attached to again.
+    /// Returns [`true`] if the module is valid afterwards.
+    pub fn update(&mut self, process: &Process) -> bool {
+        if self.module.is_valid(process) {
+            return true;
+        }
+        self.image.set(None);
+        self.classes.get_mut().clear();
+        if !self.module.reattach(process) {
+            return false;
+        }
+        self.generation = self.generation.wrapping_add(1);
+        true
+    }
+
+    /// Looks up the `Assembly-CSharp` [image](Image) or returns it from the
+    /// cache. Check [`Module::get_default_image`] for more information.
+    pub fn get_default_image(&self, process: &Process) -> Option<Image> {
+        if let Some(image) = self.image.get() {
+            return Some(image);
+        }
+        let image = self.module.get_default_image(process)?;
+        self.image.set(Some(image));
+        Some(image)
+    }
+
+    /// Looks up the [class](struct@Class) with the specified name in the
+    /// `Assembly-CSharp` [image](Image) or returns it from the cache. If the
+    /// cache is full, the class is still looked up, but not cached.
+    pub fn get_class(&self, process: &Process, class_name: &'static str) -> Option<Class> {
+        if let Some(&(_, class)) = self
+            .classes
+            .borrow()
+            .iter()
+            .find(|(name, _)| *name == class_name)
+        {
+            return Some(class);
+        }
+        let class =
+            self.get_default_image(process)?
+                .get_class(process, &self.module, class_name)?;
+        let _ = self.classes.borrow_mut().try_push((class_name, class));
+        Some(class)
+    }
+}
+
 #[derive(Copy, Clone)]
 struct Assembly {
     assembly: Address,
@@ -352,8 +551,38 @@ impl Class {
         )
     }
 
-    fn fields<'a>(
-        &'a self,
+    /// Iterates over all the [fields](Field) of the class, including the ones
+    /// inherited from its parent classes. The fields of the class itself come
+    /// first. The fields of `Object` and of the classes in the `UnityEngine`
+    /// namespace are not included.
+    ///
+    /// # Example
+    ///
+    /// This can be used to print all the fields of a class, which is useful
+    /// when figuring out what changed in a new version of a game.
+    ///
+    /// ```no_run
+    /// # use asr::game_engine::unity::mono::{Class, Module};
+    /// # fn example(process: &asr::Process, module: &Module, class: Class) {
+    /// for field in class.fields(process, module) {
+    ///     let Ok(name) = field.get_name::<128>(process, module) else {
+    ///         continue;
+    ///     };
+    ///     let type_name = field.get_type_name::<64>(process, module).unwrap_or_default();
+    ///     let offset = field.get_offset(process, module).unwrap_or_default();
+    ///     let kind = match field.is_static(process, module) {
+    ///         Some(true) => "static",
+    ///         _ => "instance",
+    ///     };
+    ///     asr::print_limited::<256>(&format_args!(
+    ///         "{kind} {type_name} {} at 0x{offset:X}",
+    ///         name.validate_utf8().unwrap_or("?"),
+    ///     ));
+    /// }
+    /// # }
+    /// ```
+    pub fn fields<'a>(
+        &self,
         process: &'a Process,
         module: &'a Module,
     ) -> impl Iterator<Item = Field> + 'a {
@@ -420,13 +649,25 @@ impl Class {
         module: &Module,
         field_name: &str,
     ) -> Option<u32> {
-        self.fields(process, module)
-            .find(|field| {
-                field
-                    .get_name::<CSTR>(process, module)
-                    .is_ok_and(|name| name.matches(field_name))
-            })?
-            .get_offset(process, module)
+        self.field_by_predicate(process, module, |field| {
+            field
+                .get_name::<CSTR>(process, module)
+                .is_ok_and(|name| name.matches(field_name))
+        })?
+        .get_offset(process, module)
+    }
+
+    /// Tries to find the first [field](Field) of the class that matches the
+    /// predicate. The fields are visited in the same order as in
+    /// [`fields`](Self::fields). This is useful for finding fields whose names
+    /// are not stable, such as obfuscated or compiler generated fields.
+    pub fn field_by_predicate(
+        &self,
+        process: &Process,
+        module: &Module,
+        mut predicate: impl FnMut(&Field) -> bool,
+    ) -> Option<Field> {
+        self.fields(process, module).find(|field| predicate(field))
     }
 
     /// Tries to find the address of a static instance of the class based on its
@@ -493,6 +734,51 @@ impl Class {
         }
     }
 
+    fn get_static_field_address(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> Option<Address> {
+        let field = self.field_by_predicate(process, module, |field| {
+            field
+                .get_name::<CSTR>(process, module)
+                .is_ok_and(|name| name.matches(field_name))
+        })?;
+        if field.is_static(process, module) == Some(false) {
+            return None;
+        }
+        Some(self.get_static_table(process, module)? + field.get_offset(process, module)?)
+    }
+
+    /// Reads the value of the static field with the specified name. This
+    /// combines looking up the [static table](Self::get_static_table) and the
+    /// [offset of the field](Self::get_field_offset), so every call looks up
+    /// the field again. Use a [`StaticField`] to only look it up once.
+    pub fn get_static_value<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> Option<T> {
+        process
+            .read(self.get_static_field_address(process, module, field_name)?)
+            .ok()
+    }
+
+    /// Reads the value of the static field with the specified name. This is
+    /// the `await`able version of the
+    /// [`get_static_value`](Self::get_static_value) function, yielding back to
+    /// the runtime between each try.
+    pub async fn wait_get_static_value<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> T {
+        retry(|| self.get_static_value(process, module, field_name)).await
+    }
+
     /// Returns the address of the static table of the class. This contains the
     /// values of all the static fields.
     pub fn get_static_table(&self, process: &Process, module: &Module) -> Option<Address> {
@@ -551,13 +837,15 @@ impl Class {
     }
 }
 
+/// A field of a [.NET class](struct@Class).
 #[derive(Copy, Clone)]
-struct Field {
+pub struct Field {
     field: Address,
 }
 
 impl Field {
-    fn get_name<const N: usize>(
+    /// Reads the name of the field.
+    pub fn get_name<const N: usize>(
         &self,
         process: &Process,
         module: &Module,
@@ -569,14 +857,147 @@ impl Field {
         )
     }
 
-    fn get_offset(&self, process: &Process, module: &Module) -> Option<u32> {
+    /// Reads the offset of the field. For instance fields, this is the offset
+    /// from the start of an instance of the class. For [static
+    /// fields](Self::is_static), this is the offset from the start of the
+    /// [static table](Class::get_static_table) of the class instead.
+    pub fn get_offset(&self, process: &Process, module: &Module) -> Option<u32> {
         process
             .read(self.field + module.offsets.monoclassfield_offset)
             .ok()
     }
+
+    fn get_type(&self, process: &Process, module: &Module) -> Option<Address> {
+        process
+            .read_pointer(
+                self.field + module.offsets.monoclassfield_type,
+                module.pointer_size,
+            )
+            .ok()
+            .filter(|val| !val.is_null())
+    }
+
+    /// Checks whether the field is static. The offset of a static field is
+    /// relative to the [static table](Class::get_static_table) of the class.
+    pub fn is_static(&self, process: &Process, module: &Module) -> Option<bool> {
+        let attrs = process
+            .read::<u16>(self.get_type(process, module)? + module.size_of_ptr())
+            .ok()?;
+        Some(attrs & super::FIELD_ATTRIBUTE_STATIC != 0)
+    }
+
+    /// Tries to determine the name of the type of the field. Built-in types
+    /// use their C# names, such as `int` or `string`, while classes and value
+    /// types use the name of the class without its namespace. This is a best
+    /// effort, so more complex types, such as arrays and generic types, are
+    /// not supported and return [`None`].
+    pub fn get_type_name<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Option<ArrayString<N>> {
+        let ty = self.get_type(process, module)?;
+        let type_enum = process.read::<u8>(ty + module.size_of_ptr() + 2).ok()?;
+
+        if let Some(name) = super::builtin_type_name(type_enum) {
+            return Some(from_utf8_lossy(name.as_bytes()));
+        }
+
+        match type_enum {
+            super::TYPE_CLASS | super::TYPE_VALUETYPE => {
+                let class = process
+                    .read_pointer(ty, module.pointer_size)
+                    .ok()
+                    .filter(|val| !val.is_null())?;
+                let name = Class { class }.get_name::<CSTR>(process, module).ok()?;
+                Some(name.to_array_string())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A static field of a [.NET class](struct@Class) whose address is looked up
+/// once and then remembered. If reading the value fails, for example because
+/// the game reloaded its domain and the static table moved, the address is
+/// looked up again.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::mono::{Class, Module, StaticField};
+/// # fn example(process: &asr::Process, module: &Module, game_manager: Class) {
+/// let is_loading = StaticField::<bool>::new(game_manager, "isLoading");
+/// loop {
+///     if let Some(is_loading) = is_loading.read(process, module) {
+///         // Do something with the value.
+///     }
+/// #   break;
+/// }
+/// # }
+/// ```
+pub struct StaticField<T> {
+    class: Class,
+    field_name: &'static str,
+    address: Cell<Option<Address>>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T: CheckedBitPattern> StaticField<T> {
+    /// Creates a new static field of the class with the specified name. The
+    /// address of the field is only looked up once it is read.
+    pub const fn new(class: Class, field_name: &'static str) -> Self {
+        Self {
+            class,
+            field_name,
+            address: Cell::new(None),
+            _value: PhantomData,
+        }
+    }
+
+    /// Reads the value of the static field. The address of the field is
+    /// looked up if it isn't known yet or if reading from the remembered
+    /// address fails.
+    pub fn read(&self, process: &Process, module: &Module) -> Option<T> {
+        if let Some(address) = self.address.get() {
+            if let Ok(value) = process.read(address) {
+                return Some(value);
+            }
+        }
+
+        let address = self
+            .class
+            .get_static_field_address(process, module, self.field_name);
+        self.address.set(address);
+        process.read(address?).ok()
+    }
+
+    /// Forgets the address of the static field, so it is looked up again the
+    /// next time it is read.
+    pub fn invalidate(&self) {
+        self.address.set(None);
+    }
 }
 
 /// A Mono-specific implementation for automatic pointer path resolution
+///
+/// The pointer path starts at a static field of a class and continues through
+/// the fields of the instances it points to. The class and the offsets of the
+/// fields are looked up by their names the first time the pointer path is
+/// dereferenced and are cached afterwards. The fields can also be specified
+/// as offsets directly, either in decimal or in hexadecimal with a `0x`
+/// prefix.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::game_engine::unity::mono::{Image, Module, UnityPointer};
+/// # async fn example(process: &asr::Process, module: &Module, image: &Image) {
+/// // GameManager.instance.player.health
+/// let health = UnityPointer::<3>::new("GameManager", 0, &["instance", "player", "health"]);
+/// let health = health.wait_deref::<f32>(process, module, image).await;
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct UnityPointer<const CAP: usize> {
     cache: RefCell<UnityPointerCache<CAP>>,
@@ -630,7 +1051,7 @@ impl<const CAP: usize> UnityPointer<CAP> {
         let mut cache = self.cache.borrow_mut();
 
         // If the pointer path has already been found, there's no need to continue
-        if cache.resolved_offsets == self.depth {
+        if cache.resolved_offsets == self.depth && !cache.base_address.is_null() {
             return Ok(());
         }
 
@@ -737,6 +1158,36 @@ impl<const CAP: usize> UnityPointer<CAP> {
         Ok(())
     }
 
+    /// Looks up the address of the static table again the next time the
+    /// pointer path is dereferenced. This is done whenever dereferencing fails,
+    /// as the static table may have moved, for example when the game reloads
+    /// its domain. The classes and the offsets of the fields stay cached.
+    fn invalidate_base_address(&self) {
+        self.cache.borrow_mut().base_address = Address::NULL;
+    }
+
+    /// Forgets everything that was resolved so far, so that the class and all
+    /// the offsets are looked up again the next time the pointer path is
+    /// dereferenced. This is needed if the game reloaded its module, as the
+    /// cached class is stale then.
+    pub fn reset(&self) {
+        let mut cache = self.cache.borrow_mut();
+        cache.base_address = Address::NULL;
+        cache.resolved_offsets = 0;
+        cache.current_instance_pointer = None;
+        cache.starting_class = None;
+    }
+
+    fn deref_offsets_cached(&self, process: &Process, module: &Module) -> Result<Address, Error> {
+        let cache = self.cache.borrow();
+        let mut address = cache.base_address;
+        let (&last, path) = cache.offsets[..self.depth].split_last().ok_or(Error {})?;
+        for &offset in path {
+            address = process.read_pointer(address + offset, module.pointer_size)?;
+        }
+        Ok(address + last)
+    }
+
     /// Dereferences the pointer path, returning the memory address of the value of interest
     pub fn deref_offsets(
         &self,
@@ -745,13 +1196,11 @@ impl<const CAP: usize> UnityPointer<CAP> {
         image: &Image,
     ) -> Result<Address, Error> {
         self.find_offsets(process, module, image)?;
-        let cache = self.cache.borrow();
-        let mut address = cache.base_address;
-        let (&last, path) = cache.offsets[..self.depth].split_last().ok_or(Error {})?;
-        for &offset in path {
-            address = process.read_pointer(address + offset, module.pointer_size)?;
+        let address = self.deref_offsets_cached(process, module);
+        if address.is_err() {
+            self.invalidate_base_address();
         }
-        Ok(address + last)
+        address
     }
 
     /// Dereferences the pointer path, returning the value stored at the final memory address
@@ -762,12 +1211,31 @@ impl<const CAP: usize> UnityPointer<CAP> {
         image: &Image,
     ) -> Result<T, Error> {
         self.find_offsets(process, module, image)?;
-        let cache = self.cache.borrow();
-        process.read_pointer_path(
-            cache.base_address,
-            module.pointer_size,
-            &cache.offsets[..self.depth],
-        )
+        let value = {
+            let cache = self.cache.borrow();
+            process.read_pointer_path(
+                cache.base_address,
+                module.pointer_size,
+                &cache.offsets[..self.depth],
+            )
+        };
+        if value.is_err() {
+            self.invalidate_base_address();
+        }
+        value
+    }
+
+    /// Dereferences the pointer path, returning the value stored at the final
+    /// memory address. This is the `await`able version of the
+    /// [`deref`](Self::deref) function, yielding back to the runtime between
+    /// each try.
+    pub async fn wait_deref<T: CheckedBitPattern>(
+        &self,
+        process: &Process,
+        module: &Module,
+        image: &Image,
+    ) -> T {
+        retry(|| self.deref(process, module, image).ok()).await
     }
 
     /// Generates a `DeepPointer` struct based on the offsets
@@ -803,6 +1271,7 @@ struct Offsets {
     monoclass_runtime_info: u8,
     monoclass_vtable_size: u8,
     monoclass_parent: u8,
+    monoclassfield_type: u8,
     monoclassfield_name: u8,
     monoclassfield_offset: u8,
     monoclassruntimeinfo_domain_vtables: u8,
@@ -829,6 +1298,7 @@ impl Offsets {
                     monoclass_runtime_info: 0xF8,
                     monoclass_vtable_size: 0x18, // MonoVtable.data
                     monoclass_parent: 0x30,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x8,
                     monoclassfield_offset: 0x18,
                     monoclassruntimeinfo_domain_vtables: 0x8,
@@ -850,6 +1320,7 @@ impl Offsets {
                     monoclass_runtime_info: 0xD0,
                     monoclass_vtable_size: 0x5C,
                     monoclass_parent: 0x30,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x8,
                     monoclassfield_offset: 0x18,
                     monoclassruntimeinfo_domain_vtables: 0x8,
@@ -871,6 +1342,7 @@ impl Offsets {
                     monoclass_runtime_info: 0xD0,
                     monoclass_vtable_size: 0x5C,
                     monoclass_parent: 0x30,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x8,
                     monoclassfield_offset: 0x18,
                     monoclassruntimeinfo_domain_vtables: 0x8,
@@ -894,6 +1366,7 @@ impl Offsets {
                     monoclass_runtime_info: 0xA4,
                     monoclass_vtable_size: 0xC, // MonoVtable.data
                     monoclass_parent: 0x24,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x4,
                     monoclassfield_offset: 0xC,
                     monoclassruntimeinfo_domain_vtables: 0x4,
@@ -915,6 +1388,7 @@ impl Offsets {
                     monoclass_runtime_info: 0x84,
                     monoclass_vtable_size: 0x38,
                     monoclass_parent: 0x20,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x4,
                     monoclassfield_offset: 0xC,
                     monoclassruntimeinfo_domain_vtables: 0x4,
@@ -936,6 +1410,7 @@ impl Offsets {
                     monoclass_runtime_info: 0x7C,
                     monoclass_vtable_size: 0x38,
                     monoclass_parent: 0x20,
+                    monoclassfield_type: 0x0,
                     monoclassfield_name: 0x4,
                     monoclassfield_offset: 0xC,
                     monoclassruntimeinfo_domain_vtables: 0x4,
@@ -961,14 +1436,30 @@ pub enum Version {
 }
 
 fn detect_version(process: &Process) -> Option<Version> {
-    if process.get_module_address("mono.dll").is_ok() {
+    if MODULE_NAMES[..2]
+        .iter()
+        .any(|&name| process.get_module_address(name).is_ok())
+    {
         return Some(Version::V1);
     }
 
-    let unity_module = {
-        let address = process.get_module_address("UnityPlayer.dll").ok()?;
+    // Older versions of Unity are linked into the main executable instead.
+    // Looking it up requires the path of the executable, so the version can't
+    // be detected for them without the `alloc` feature.
+    let unity_module = if let Ok(address) = process.get_module_address("UnityPlayer.dll") {
         let range = pe::read_size_of_image(process, address)? as u64;
         (address, range)
+    } else if let Ok(range) = process.get_module_range("UnityPlayer.so") {
+        range
+    } else {
+        #[cfg(feature = "alloc")]
+        {
+            process.get_main_module_range().ok()?
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            return None;
+        }
     };
 
     const SIG_202X: Signature<6> = Signature::new("00 32 30 32 ?? 2E");
@@ -1006,3 +1497,206 @@ fn detect_version(process: &Process) -> Option<Version> {
         Version::V2
     })
 }
+
+#[cfg(test)]
+mod tests {
+    //! The offsets are checked against the definitions of the structs in
+    //! Mono's headers, mirrored with the pointers replaced by integers of the
+    //! pointer size.
+
+    use core::mem::{offset_of, size_of};
+
+    use super::*;
+
+    /// `MonoAssemblyName` of Mono 2.x, as used by [`Version::V1`].
+    #[repr(C)]
+    struct MonoAssemblyNameV1<P> {
+        name: P,
+        culture: P,
+        hash_value: P,
+        public_key: P,
+        public_key_token: [u8; 17],
+        hash_alg: u32,
+        hash_len: u32,
+        flags: u32,
+        version: [u16; 4],
+    }
+
+    /// `MonoAssemblyName` of Mono 5.x, as used by [`Version::V2`], which adds
+    /// the architecture.
+    #[repr(C)]
+    struct MonoAssemblyNameV2<P> {
+        name: P,
+        culture: P,
+        hash_value: P,
+        public_key: P,
+        public_key_token: [u8; 17],
+        hash_alg: u32,
+        hash_len: u32,
+        flags: u32,
+        version: [u16; 4],
+        arch: u16,
+    }
+
+    /// `MonoAssemblyName` of Mono 6.x, as used by [`Version::V3`], which adds
+    /// the `without_version`, `without_culture` and
+    /// `without_public_key_token` flags.
+    #[repr(C)]
+    struct MonoAssemblyNameV3<P> {
+        name: P,
+        culture: P,
+        hash_value: P,
+        public_key: P,
+        public_key_token: [u8; 17],
+        hash_alg: u32,
+        hash_len: u32,
+        flags: u32,
+        version: [u16; 4],
+        arch: u16,
+        without_version: u8,
+        without_culture: u8,
+        without_public_key_token: u8,
+    }
+
+    #[repr(C)]
+    struct MonoAssembly<P, Name> {
+        ref_count: i32,
+        basedir: P,
+        aname: Name,
+        image: P,
+    }
+
+    #[repr(C)]
+    struct MonoInternalHashTable<P> {
+        hash_func: P,
+        key_extract: P,
+        next_value: P,
+        size: i32,
+        num_entries: i32,
+        table: P,
+    }
+
+    #[repr(C)]
+    struct MonoClassField<P> {
+        ty: P,
+        name: P,
+        parent: P,
+        offset: i32,
+    }
+
+    #[repr(C)]
+    struct MonoClassRuntimeInfo<P> {
+        max_domain: u16,
+        domain_vtables: [P; 0],
+    }
+
+    const VERSIONS: [Version; 3] = [Version::V1, Version::V2, Version::V3];
+
+    fn offsets(version: Version, pointer_size: PointerSize) -> &'static Offsets {
+        Offsets::new(version, pointer_size).unwrap()
+    }
+
+    fn check_assembly<P, Name>(offsets: &Offsets) {
+        assert_eq!(
+            offsets.monoassembly_aname as usize,
+            offset_of!(MonoAssembly<P, Name>, aname)
+        );
+        assert_eq!(
+            offsets.monoassembly_image as usize,
+            offset_of!(MonoAssembly<P, Name>, image)
+        );
+    }
+
+    #[test]
+    fn assembly_layout() {
+        check_assembly::<u32, MonoAssemblyNameV1<u32>>(offsets(Version::V1, PointerSize::Bit32));
+        check_assembly::<u64, MonoAssemblyNameV1<u64>>(offsets(Version::V1, PointerSize::Bit64));
+        check_assembly::<u32, MonoAssemblyNameV2<u32>>(offsets(Version::V2, PointerSize::Bit32));
+        check_assembly::<u64, MonoAssemblyNameV2<u64>>(offsets(Version::V2, PointerSize::Bit64));
+        check_assembly::<u32, MonoAssemblyNameV3<u32>>(offsets(Version::V3, PointerSize::Bit32));
+        check_assembly::<u64, MonoAssemblyNameV3<u64>>(offsets(Version::V3, PointerSize::Bit64));
+    }
+
+    fn check_shared_layouts<P>(offsets: &Offsets) {
+        assert_eq!(
+            offsets.monointernalhashtable_size as usize,
+            offset_of!(MonoInternalHashTable<P>, size)
+        );
+        assert_eq!(
+            offsets.monointernalhashtable_table as usize,
+            offset_of!(MonoInternalHashTable<P>, table)
+        );
+        assert_eq!(
+            offsets.monoclassfield_type as usize,
+            offset_of!(MonoClassField<P>, ty)
+        );
+        assert_eq!(
+            offsets.monoclassfield_name as usize,
+            offset_of!(MonoClassField<P>, name)
+        );
+        assert_eq!(
+            offsets.monoclassfield_offset as usize,
+            offset_of!(MonoClassField<P>, offset)
+        );
+        assert_eq!(
+            offsets.monoclassfieldalignment as usize,
+            size_of::<MonoClassField<P>>()
+        );
+        assert_eq!(
+            offsets.monoclassruntimeinfo_domain_vtables as usize,
+            offset_of!(MonoClassRuntimeInfo<P>, domain_vtables)
+        );
+        // The `MonoClassDef` starts with the `MonoClass` it extends.
+        assert_eq!(offsets.monoclassdef_klass, 0);
+        // `MonoClass` stores the pointers `parent`, `nested_in`, `image`,
+        // `name` and `name_space` right after each other.
+        let pointer_size = size_of::<P>() as u8;
+        assert_eq!(
+            offsets.monoclass_name,
+            offsets.monoclass_parent + 3 * pointer_size
+        );
+        assert_eq!(
+            offsets.monoclass_name_space,
+            offsets.monoclass_name + pointer_size
+        );
+    }
+
+    #[test]
+    fn shared_layouts() {
+        for version in VERSIONS {
+            check_shared_layouts::<u32>(offsets(version, PointerSize::Bit32));
+            check_shared_layouts::<u64>(offsets(version, PointerSize::Bit64));
+        }
+    }
+
+    #[test]
+    fn pointer_fields_are_aligned() {
+        for (pointer_size, align) in [(PointerSize::Bit32, 4), (PointerSize::Bit64, 8)] {
+            for version in VERSIONS {
+                let offsets = offsets(version, pointer_size);
+                for offset in [
+                    offsets.monoassembly_image as u16,
+                    offsets.monoimage_class_cache,
+                    offsets.monoclassdef_next_class_cache,
+                    offsets.monoclass_parent as u16,
+                    offsets.monoclass_fields as u16,
+                    offsets.monoclass_runtime_info as u16,
+                    offsets.monovtable_vtable as u16,
+                ] {
+                    assert_eq!(
+                        offset % align,
+                        0,
+                        "{version:?} {pointer_size:?} {offset:#X}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unsupported_pointer_size() {
+        for version in VERSIONS {
+            assert!(Offsets::new(version, PointerSize::Bit16).is_none());
+        }
+    }
+}
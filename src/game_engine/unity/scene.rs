@@ -133,6 +133,9 @@ impl SceneManager {
     }
 
     /// Iterates over all the currently loaded scenes in the attached game.
+    /// Scenes that don't have a path yet, which happens while they are being
+    /// loaded, are skipped. Use [`Scene::is_loaded`] to check whether a scene
+    /// finished loading.
     pub fn scenes<'a>(
         &'a self,
         process: &'a Process,
@@ -153,7 +156,7 @@ impl SceneManager {
         };
 
         (0..num_scenes).filter_map(move |index| {
-            Some(Scene {
+            let scene = Scene {
                 address: process
                     .read_pointer(
                         addr + (index as u64).wrapping_mul(self.size_of_ptr()),
@@ -161,10 +164,32 @@ impl SceneManager {
                     )
                     .ok()
                     .filter(|val| !val.is_null())?,
-            })
+            };
+
+            process
+                .read_pointer(scene.address + self.offsets.asset_path, self.pointer_size)
+                .ok()
+                .filter(|val| !val.is_null())?;
+
+            Some(scene)
         })
     }
 
+    /// Tries to find the loaded scene with the specified name. The name is
+    /// compared with the name of the scene's path, as returned by
+    /// [`get_scene_name`]. This is useful for games that load scenes
+    /// additively, where the scene of interest isn't necessarily the active
+    /// one.
+    pub fn get_scene(&self, process: &Process, name: &str) -> Result<Scene, Error> {
+        self.scenes(process)
+            .find(|scene| {
+                scene
+                    .path::<CSTR>(process, self)
+                    .is_ok_and(|path| get_scene_name(&path) == name.as_bytes())
+            })
+            .ok_or(Error {})
+    }
+
     /// Iterates over all root [`Transform`]s declared for the
     /// specified scene.
     ///
@@ -248,7 +273,178 @@ pub struct Transform {
     address: Address,
 }
 
+/// A `GameObject` is an entity in a Unity scene. Its position in the scene is
+/// described by its [`Transform`], which also links it to its parent and its
+/// children.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unity::SceneManager, Process};
+/// # fn example(process: &Process, scene_manager: &SceneManager) {
+/// let Ok(scene) = scene_manager.get_scene(process, "Level1") else {
+///     return;
+/// };
+/// let position = scene
+///     .get_root_game_object(process, scene_manager, "Players")
+///     .and_then(|players| players.find_child(process, scene_manager, "Player"))
+///     .and_then(|player| player.get_transform(process, scene_manager))
+///     .and_then(|transform| transform.get_position(process, scene_manager));
+/// if let Ok([x, y, z]) = position {
+///     // Do something with the position.
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct GameObject {
+    address: Address,
+}
+
+impl GameObject {
+    /// Returns the address of the `GameObject` in the attached game.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Tries to return the name of the `GameObject`.
+    pub fn get_name<const N: usize>(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<ArrayCString<N>, Error> {
+        process.read_pointer_path(
+            self.address,
+            scene_manager.pointer_size,
+            &[scene_manager.offsets.game_object_name as u64, 0x0],
+        )
+    }
+
+    /// Returns the [`Transform`] of the `GameObject`. This is always the
+    /// first of its components. This is only supported for 64-bit games.
+    pub fn get_transform(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<Transform, Error> {
+        if scene_manager.pointer_size != PointerSize::Bit64 {
+            return Err(Error {});
+        }
+        // The components are stored as pairs of their type and the component.
+        let components = process.read_pointer(
+            self.address + scene_manager.offsets.game_object_components,
+            scene_manager.pointer_size,
+        )?;
+        let transform = process
+            .read_pointer(
+                components + scene_manager.size_of_ptr(),
+                scene_manager.pointer_size,
+            )
+            .ok()
+            .filter(|val| !val.is_null())
+            .ok_or(Error {})?;
+        Ok(Transform { address: transform })
+    }
+
+    /// Tries to find the direct child `GameObject` with the specified name.
+    pub fn find_child(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+        name: &str,
+    ) -> Result<GameObject, Error> {
+        self.get_transform(process, scene_manager)?
+            .get_child(process, scene_manager, name)?
+            .get_game_object(process, scene_manager)
+    }
+}
+
 impl Transform {
+    /// Returns the address of the `Transform` in the attached game.
+    pub const fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Returns the [`GameObject`] that the `Transform` belongs to.
+    pub fn get_game_object(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<GameObject, Error> {
+        let game_object = process
+            .read_pointer(
+                self.address + scene_manager.offsets.game_object,
+                scene_manager.pointer_size,
+            )
+            .ok()
+            .filter(|val| !val.is_null())
+            .ok_or(Error {})?;
+        Ok(GameObject {
+            address: game_object,
+        })
+    }
+
+    /// Reads the hierarchy that the `Transform` is part of, along with the
+    /// index of the `Transform` within it. Only the layout used since Unity
+    /// 2019 is supported, which is only known for 64-bit games.
+    fn get_hierarchy(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<(TransformHierarchy, i32), Error> {
+        if scene_manager.pointer_size != PointerSize::Bit64 {
+            return Err(Error {});
+        }
+        let [hierarchy, index] =
+            process.read::<[u64; 2]>(self.address + scene_manager.offsets.transform_access)?;
+        let [local_transforms, parent_indices] = process.read::<[Address64; 2]>(
+            Address::new(hierarchy) + scene_manager.offsets.hierarchy_local_transforms,
+        )?;
+        if local_transforms.is_null() || parent_indices.is_null() {
+            return Err(Error {});
+        }
+        Ok((
+            TransformHierarchy {
+                local_transforms: local_transforms.into(),
+                parent_indices: parent_indices.into(),
+            },
+            index as i32,
+        ))
+    }
+
+    /// Reads the position of the `Transform` relative to its parent. Only
+    /// 64-bit games using Unity 2019 or newer are supported.
+    pub fn get_local_position(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<[f32; 3], Error> {
+        let (hierarchy, index) = self.get_hierarchy(process, scene_manager)?;
+        Ok(hierarchy.read_local(process, index)?.position)
+    }
+
+    /// Reads the position of the `Transform` in the world. This applies the
+    /// position, rotation and scale of all of its parents. Only 64-bit games
+    /// using Unity 2019 or newer are supported.
+    pub fn get_position(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<[f32; 3], Error> {
+        // Deeper hierarchies are most likely caused by reading garbage.
+        const MAX_DEPTH: usize = 256;
+
+        let (hierarchy, mut index) = self.get_hierarchy(process, scene_manager)?;
+        let mut position = hierarchy.read_local(process, index)?.position;
+        for _ in 0..MAX_DEPTH {
+            index = process.read(hierarchy.parent_indices + index as u64 * 4)?;
+            if index < 0 {
+                return Ok(position);
+            }
+            position = hierarchy.read_local(process, index)?.apply(position);
+        }
+        Err(Error {})
+    }
+
     /// Tries to return the name of the current `Transform`.
     pub fn get_name<const N: usize>(
         &self,
@@ -280,13 +476,15 @@ impl Transform {
         let (number_of_components, main_object): (usize, Address) = match scene_manager.pointer_size
         {
             PointerSize::Bit64 => {
-                let array = process
-                    .read::<[Address64; 3]>(game_object + scene_manager.offsets.game_object)?;
+                let array = process.read::<[Address64; 3]>(
+                    game_object + scene_manager.offsets.game_object_components,
+                )?;
                 (array[2].value() as usize, array[0].into())
             }
             _ => {
-                let array = process
-                    .read::<[Address32; 3]>(game_object + scene_manager.offsets.game_object)?;
+                let array = process.read::<[Address32; 3]>(
+                    game_object + scene_manager.offsets.game_object_components,
+                )?;
                 (array[2].value() as usize, array[0].into())
             }
         };
@@ -429,18 +627,78 @@ impl Transform {
     }
 }
 
+struct TransformHierarchy {
+    local_transforms: Address,
+    parent_indices: Address,
+}
+
+impl TransformHierarchy {
+    fn read_local(&self, process: &Process, index: i32) -> Result<LocalTransform, Error> {
+        if index < 0 {
+            return Err(Error {});
+        }
+        // Each component is padded to 16 bytes.
+        let [position, rotation, scale] =
+            process.read::<[[f32; 4]; 3]>(self.local_transforms + index as u64 * 0x30)?;
+        Ok(LocalTransform {
+            position: [position[0], position[1], position[2]],
+            rotation,
+            scale: [scale[0], scale[1], scale[2]],
+        })
+    }
+}
+
+struct LocalTransform {
+    position: [f32; 3],
+    rotation: [f32; 4],
+    scale: [f32; 3],
+}
+
+impl LocalTransform {
+    /// Transforms a position from the space of the child into the space of
+    /// the parent, which this transform belongs to.
+    fn apply(&self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        let v = [x * self.scale[0], y * self.scale[1], z * self.scale[2]];
+        let [qx, qy, qz, qw] = self.rotation;
+        let q = [qx, qy, qz];
+
+        // v' = v + 2w(q x v) + 2(q x (q x v))
+        let cross = |a: [f32; 3], b: [f32; 3]| {
+            [
+                a[1] * b[2] - a[2] * b[1],
+                a[2] * b[0] - a[0] * b[2],
+                a[0] * b[1] - a[1] * b[0],
+            ]
+        };
+        let t = cross(q, v);
+        let u = cross(q, t);
+
+        [
+            v[0] + 2.0 * (qw * t[0] + u[0]) + self.position[0],
+            v[1] + 2.0 * (qw * t[1] + u[1]) + self.position[1],
+            v[2] + 2.0 * (qw * t[2] + u[2]) + self.position[2],
+        ]
+    }
+}
+
 struct Offsets {
     scene_count: u8,
     active_scene: u8,
     dont_destroy_on_load_scene: u8,
     asset_path: u8,
+    loading_state: u8,
     build_index: u8,
     root_storage_container: u8,
+    /// The `GameObject` that a `Transform` belongs to.
     game_object: u8,
+    /// The array of components of a `GameObject`.
+    game_object_components: u8,
     game_object_name: u8,
     klass: u8,
     klass_name: u8,
     children_pointer: u8,
+    transform_access: u8,
+    hierarchy_local_transforms: u8,
 }
 
 impl Offsets {
@@ -451,26 +709,35 @@ impl Offsets {
                 active_scene: 0x48,
                 dont_destroy_on_load_scene: 0x70,
                 asset_path: 0x10,
+                loading_state: 0x94,
                 build_index: 0x98,
                 root_storage_container: 0xB0,
                 game_object: 0x30,
+                game_object_components: 0x30,
                 game_object_name: 0x60,
                 klass: 0x28,
                 klass_name: 0x48,
                 children_pointer: 0x70,
+                transform_access: 0x38,
+                hierarchy_local_transforms: 0x18,
             },
             _ => &Self {
                 scene_count: 0x10,
                 active_scene: 0x28,
                 dont_destroy_on_load_scene: 0x40,
                 asset_path: 0xC,
+                loading_state: 0x6C,
                 build_index: 0x70,
                 root_storage_container: 0x88,
                 game_object: 0x1C,
+                game_object_components: 0x1C,
                 game_object_name: 0x3C,
                 klass: 0x18,
                 klass_name: 0x2C,
                 children_pointer: 0x50,
+                // The layout isn't known for 32-bit games.
+                transform_access: 0x0,
+                hierarchy_local_transforms: 0x0,
             },
         }
     }
@@ -499,6 +766,47 @@ impl Scene {
         process.read(self.address + scene_manager.offsets.build_index)
     }
 
+    /// Iterates over all the root [`GameObject`]s of the scene.
+    pub fn root_game_objects<'a>(
+        &self,
+        process: &'a Process,
+        scene_manager: &'a SceneManager,
+    ) -> impl Iterator<Item = GameObject> + 'a {
+        scene_manager
+            .root_game_objects(process, self)
+            .filter_map(|transform| transform.get_game_object(process, scene_manager).ok())
+    }
+
+    /// Tries to find the root [`GameObject`] of the scene with the specified
+    /// name.
+    pub fn get_root_game_object(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+        name: &str,
+    ) -> Result<GameObject, Error> {
+        self.root_game_objects(process, scene_manager)
+            .find(|obj| {
+                obj.get_name::<CSTR>(process, scene_manager)
+                    .is_ok_and(|obj_name| obj_name.matches(name))
+            })
+            .ok_or(Error {})
+    }
+
+    /// Returns [`true`] if the scene has finished loading and isn't being
+    /// unloaded yet.
+    pub fn is_loaded(
+        &self,
+        process: &Process,
+        scene_manager: &SceneManager,
+    ) -> Result<bool, Error> {
+        const LOADED: u32 = 2;
+
+        process
+            .read::<u32>(self.address + scene_manager.offsets.loading_state)
+            .map(|state| state == LOADED)
+    }
+
     /// Returns the full path to the scene.
     pub fn path<const N: usize>(
         &self,
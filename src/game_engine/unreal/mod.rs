@@ -3,15 +3,21 @@
 use core::{
     array,
     cell::RefCell,
+    fmt::Write,
     iter::{self, FusedIterator},
     mem::size_of,
 };
 
-use bytemuck::CheckedBitPattern;
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+use bytemuck::{CheckedBitPattern, Pod, Zeroable};
 
 use crate::{
-    file_format::pe, future::retry, signature::Signature, string::ArrayCString, Address, Error,
-    PointerSize, Process,
+    file_format::pe,
+    future::retry,
+    signature::Signature,
+    string::{ArrayCString, ArrayString},
+    Address, Error, PointerSize, Process,
 };
 
 const CSTR: usize = 128;
@@ -31,6 +37,15 @@ pub struct Module {
 }
 
 impl Module {
+    /// Tries attaching to a UE game. The UE version is detected automatically
+    /// from the file version of the game's executable, which the engine sets to
+    /// its own version by default. If the game overrides it, use
+    /// [`attach`](Self::attach) with the correct version instead.
+    pub fn attach_auto_detect(process: &Process, main_module_address: Address) -> Option<Self> {
+        let version = detect_version(process, main_module_address)?;
+        Self::attach(process, version, main_module_address)
+    }
+
     /// Tries attaching to a UE game. The UE version needs to be correct for this
     /// function to work.
     pub fn attach(
@@ -100,6 +115,13 @@ impl Module {
         retry(|| Self::attach(process, version, main_module_address)).await
     }
 
+    /// Tries attaching to a UE game, detecting the UE version automatically.
+    /// Check [`attach_auto_detect`](Self::attach_auto_detect) for more
+    /// information.
+    pub async fn wait_attach_auto_detect(process: &Process, main_module_address: Address) -> Self {
+        retry(|| Self::attach_auto_detect(process, main_module_address)).await
+    }
+
     /// Returns the memory pointer to GWorld
     pub const fn g_world(&self) -> Address {
         self.g_world
@@ -118,6 +140,25 @@ impl Module {
         }
     }
 
+    /// Returns the `UWorld` that is currently loaded.
+    pub fn get_g_world(&self, process: &Process) -> Option<UWorld> {
+        Some(UWorld {
+            object: self.get_g_world_uobject(process)?,
+        })
+    }
+
+    /// Returns the `GameInstance` of GEngine, which persists across level
+    /// changes and is where games usually keep their global state. Its offset
+    /// is looked up through reflection.
+    pub fn get_game_instance(&self, process: &Process) -> Option<UObject> {
+        let engine = self.get_g_engine_uobject(process)?;
+        let offset = engine.get_field_offset(process, self, "GameInstance")?;
+        match process.read_pointer(engine.object + offset, self.pointer_size) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(UObject { object: val }),
+        }
+    }
+
     /// Returns the current instance of GEngine
     pub fn get_g_engine_uobject(&self, process: &Process) -> Option<UObject> {
         match process.read_pointer(self.g_engine, self.pointer_size) {
@@ -130,6 +171,152 @@ impl Module {
     const fn size_of_ptr(&self) -> u64 {
         self.pointer_size as u64
     }
+
+    /// Reads the name stored in the `FNamePool` entry with the index given.
+    /// The pool is split into blocks, with the upper 16 bits of the index
+    /// selecting the block and the lower 16 bits selecting the entry in units
+    /// of 2 bytes. Each entry starts with a 16-bit header that stores whether
+    /// the name is wide in its lowest bit and the length in its upper 10 bits.
+    fn read_fname_entry<const N: usize>(
+        &self,
+        process: &Process,
+        comparison_index: u32,
+    ) -> Result<ArrayCString<N>, Error> {
+        let block = (comparison_index >> 16) as u64;
+        let offset = (comparison_index & 0xFFFF) as u64;
+
+        let addr = process.read_pointer(
+            self.fname_base + self.size_of_ptr().wrapping_mul(block + 2),
+            self.pointer_size,
+        )? + offset.wrapping_mul(size_of::<u16>() as u64);
+
+        let header = process.read::<u16>(addr)?;
+        let len = (header >> 6) as usize;
+        let addr = addr + size_of::<u16>() as u64;
+
+        let mut name = NameBuf::<N>::new();
+        if header & 1 != 0 {
+            let mut buf = [0u16; N];
+            let buf = &mut buf[..len.min(N)];
+            process.read_into_slice(addr, buf)?;
+            char::decode_utf16(buf.iter().copied())
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .try_for_each(|c| name.push(c))
+                .ok();
+        } else {
+            let mut buf = [0u8; N];
+            let buf = &mut buf[..len.min(N)];
+            process.read_into_slice(addr, buf)?;
+            buf.iter().try_for_each(|&b| name.push(b as char)).ok();
+        }
+        Ok(name.into_cstring())
+    }
+}
+
+/// An `FName` is how the Unreal Engine refers to the names of objects, such as
+/// the `NamePrivate` field of every `UObject`. Instead of storing the name
+/// itself, it stores an index into the global `FNamePool` and a number that
+/// gets appended to the name.
+///
+/// Only the `FNamePool` of Unreal Engine 4.23 and newer is supported. Older
+/// versions store their names in a `TNameEntryArray`, which has a different
+/// layout and isn't covered by any [`Version`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unreal::{FName, Module}, Address, Process};
+/// # fn example(process: &Process, module: &Module, world: Address) {
+/// // Read the `NamePrivate` of the current world, which is the name of the map.
+/// if let Ok(name) = process.read::<FName>(world + 0x18) {
+///     if let Ok(map) = name.resolve::<64>(process, module) {
+///         if map.matches("MainMenu") {
+///             // TODO: The game is in the main menu.
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+// Docs:
+// - https://docs.unrealengine.com/4.27/en-US/API/Runtime/Core/UObject/FName/
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Pod, Zeroable)]
+#[repr(C)]
+pub struct FName {
+    /// The index of the entry in the `FNamePool` that stores the name.
+    pub comparison_index: u32,
+    /// The number that is appended to the name. A number of `0` means that
+    /// there is no suffix, otherwise the suffix is `_{number - 1}`.
+    pub number: u32,
+}
+
+impl FName {
+    /// Creates a new `FName` from its index into the `FNamePool` and its
+    /// number.
+    pub const fn new(comparison_index: u32, number: u32) -> Self {
+        Self {
+            comparison_index,
+            number,
+        }
+    }
+
+    /// Resolves the name by looking it up in the `FNamePool` of the game. If
+    /// the name has a number, it is appended as a suffix, such as `Actor_2`.
+    /// Names that don't fit into `N` bytes are truncated.
+    pub fn resolve<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Result<ArrayCString<N>, Error> {
+        let base = module.read_fname_entry::<N>(process, self.comparison_index)?;
+        if self.number == 0 {
+            return Ok(base);
+        }
+
+        let mut name = NameBuf::<N>::new();
+        let mut suffix = ArrayString::<11>::new();
+        let _ = write!(suffix, "_{}", self.number - 1);
+        name.push_bytes(&base);
+        name.push_bytes(suffix.as_bytes());
+        Ok(name.into_cstring())
+    }
+}
+
+/// A buffer that names are built up in before turning them into an
+/// [`ArrayCString`].
+struct NameBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> NameBuf<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Appends the UTF-8 encoding of the character. Fails without appending
+    /// anything if the character doesn't fit anymore.
+    fn push(&mut self, c: char) -> Result<(), Error> {
+        let end = self.len + c.len_utf8();
+        let dst = self.buf.get_mut(self.len..end).ok_or(Error {})?;
+        c.encode_utf8(dst);
+        self.len = end;
+        Ok(())
+    }
+
+    /// Appends as many of the bytes as still fit.
+    fn push_bytes(&mut self, bytes: &[u8]) {
+        let len = bytes.len().min(N - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&bytes[..len]);
+        self.len += len;
+    }
+
+    fn into_cstring(self) -> ArrayCString<N> {
+        bytemuck::cast(self.buf)
+    }
 }
 
 /// An `UObject` is the base class of every Unreal Engine object,
@@ -148,29 +335,27 @@ pub struct UObject {
 }
 
 impl UObject {
-    /// Reads the `FName` of the current `UObject`
+    /// Returns the address of the current `UObject`.
+    pub const fn address(&self) -> Address {
+        self.object
+    }
+
+    /// Reads the `NamePrivate` field of the current `UObject`, which can be
+    /// [resolved](FName::resolve) to the name of the object.
+    pub fn get_name_private(&self, process: &Process, module: &Module) -> Result<FName, Error> {
+        process.read(self.object + module.offsets.uobject_fname)
+    }
+
+    /// Reads the `FName` of the current `UObject`. This is the plain name
+    /// without the number suffix. Use [`FName::resolve`] on the `FName` read
+    /// from the object to include the suffix.
     pub fn get_fname<const N: usize>(
         &self,
         process: &Process,
         module: &Module,
     ) -> Result<ArrayCString<N>, Error> {
-        let [name_offset, chunk_offset] =
-            process.read::<[u16; 2]>(self.object + module.offsets.uobject_fname)?;
-
-        let addr = process.read_pointer(
-            module.fname_base + module.size_of_ptr().wrapping_mul(chunk_offset as u64 + 2),
-            module.pointer_size,
-        )? + (name_offset as u64).wrapping_mul(size_of::<u16>() as u64);
-
-        let string_size = process
-            .read::<u16>(addr)?
-            .checked_shr(6)
-            .unwrap_or_default() as usize;
-
-        let mut string = process.read::<ArrayCString<N>>(addr + size_of::<u16>() as u64)?;
-        string.set_len(string_size);
-
-        Ok(string)
+        let fname = process.read::<FName>(self.object + module.offsets.uobject_fname)?;
+        module.read_fname_entry(process, fname.comparison_index)
     }
 
     /// Returns the underlying class definition for the current `UObject`
@@ -184,6 +369,12 @@ impl UObject {
         }
     }
 
+    /// Returns the class of the current `UObject`, which allows looking up
+    /// the offsets of its fields.
+    pub fn get_class(&self, process: &Process, module: &Module) -> Option<UClass> {
+        self.get_uclass(process, module).ok()
+    }
+
     /// Tries to find a field with the specified name in the current UObject and returns
     /// the offset of the field from the start of an instance of the class.
     pub fn get_field_offset(
@@ -198,6 +389,152 @@ impl UObject {
     }
 }
 
+/// The `UWorld` that is currently loaded. It holds all the levels of the map,
+/// most importantly the persistent level, which stays loaded for as long as
+/// the map is.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unreal::Module, Process};
+/// # fn example(process: &Process, module: &Module) {
+/// let Some(world) = module.get_g_world(process) else { return };
+/// let Some(level) = world.get_persistent_level(process, module) else { return };
+/// for actor in level.actors(process, module) {
+///     let Ok(name) = actor.uobject().get_fname::<64>(process, module) else { continue };
+///     if name.matches("BP_Player_C") {
+///         if let Ok([x, y, z]) = actor.get_location(process, module) {
+///             // TODO: Do something with the position of the player.
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
+// Docs:
+// - https://docs.unrealengine.com/4.27/en-US/API/Runtime/Engine/Engine/UWorld/
+#[derive(Copy, Clone)]
+pub struct UWorld {
+    object: UObject,
+}
+
+impl UWorld {
+    /// Returns the `UObject` of the world.
+    pub const fn uobject(&self) -> UObject {
+        self.object
+    }
+
+    /// Returns the persistent level of the world.
+    pub fn get_persistent_level(&self, process: &Process, module: &Module) -> Option<ULevel> {
+        match process.read_pointer(
+            self.object.object + module.offsets.uworld_persistent_level,
+            module.pointer_size,
+        ) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(ULevel {
+                object: UObject { object: val },
+            }),
+        }
+    }
+}
+
+/// A `ULevel` is a collection of actors that are loaded together.
+///
+// Docs:
+// - https://docs.unrealengine.com/4.27/en-US/API/Runtime/Engine/Engine/ULevel/
+#[derive(Copy, Clone)]
+pub struct ULevel {
+    object: UObject,
+}
+
+impl ULevel {
+    /// The maximum amount of actors that are iterated. This protects against
+    /// reading garbage if the level is read while it is being destroyed.
+    const MAX_ACTORS: u32 = 0x10000;
+
+    /// Returns the `UObject` of the level.
+    pub const fn uobject(&self) -> UObject {
+        self.object
+    }
+
+    /// Iterates over the actors of the level. Destroyed actors leave a null
+    /// pointer behind, which are skipped.
+    pub fn actors<'a>(
+        &self,
+        process: &'a Process,
+        module: &'a Module,
+    ) -> impl FusedIterator<Item = AActor> + 'a {
+        // The actors are stored in a `TArray<AActor*>`, which consists of a
+        // pointer to the data, followed by the amount of elements and the
+        // capacity as 32-bit integers.
+        let actors = self.object.object + module.offsets.ulevel_actors;
+        let (data, len) = match (
+            process.read_pointer(actors, module.pointer_size),
+            process.read::<u32>(actors + module.size_of_ptr()),
+        ) {
+            (Ok(data), Ok(len)) if len <= Self::MAX_ACTORS => (data, len),
+            _ => (Address::NULL, 0),
+        };
+
+        (0..len as u64)
+            .filter_map(move |index| {
+                match process.read_pointer(
+                    data + index.wrapping_mul(module.size_of_ptr()),
+                    module.pointer_size,
+                ) {
+                    Ok(Address::NULL) | Err(_) => None,
+                    Ok(val) => Some(AActor {
+                        object: UObject { object: val },
+                    }),
+                }
+            })
+            .fuse()
+    }
+}
+
+/// An `AActor` is an object that can be placed in a level.
+///
+// Docs:
+// - https://docs.unrealengine.com/4.27/en-US/API/Runtime/Engine/GameFramework/AActor/
+#[derive(Copy, Clone)]
+pub struct AActor {
+    object: UObject,
+}
+
+impl AActor {
+    /// Returns the `UObject` of the actor.
+    pub const fn uobject(&self) -> UObject {
+        self.object
+    }
+
+    /// Returns the `RootComponent` of the actor, which determines its
+    /// position in the world.
+    pub fn get_root_component(&self, process: &Process, module: &Module) -> Option<UObject> {
+        match process.read_pointer(
+            self.object.object + module.offsets.aactor_root_component,
+            module.pointer_size,
+        ) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(UObject { object: val }),
+        }
+    }
+
+    /// Reads the location of the actor, which is the `RelativeLocation` of its
+    /// root component. This is only the location in the world if the actor
+    /// isn't attached to another actor. Unreal Engine 5 stores the location as
+    /// doubles, while older versions use floats, which are converted to doubles.
+    pub fn get_location(&self, process: &Process, module: &Module) -> Result<[f64; 3], Error> {
+        let root_component = self.get_root_component(process, module).ok_or(Error {})?;
+        let location = root_component.object + module.offsets.uscenecomponent_relative_location;
+        if module.offsets.large_world_coordinates {
+            process.read(location)
+        } else {
+            let [x, y, z] = process.read::<[f32; 3]>(location)?;
+            Ok([x as f64, y as f64, z as f64])
+        }
+    }
+}
+
 /// An UClass / UStruct is the object class relative to a specific UObject.
 /// It essentially represents the class definition for any given UObject,
 /// containing information about its properties, parent and children classes,
@@ -206,13 +543,61 @@ impl UObject {
 /// It's always referred by an UObject and it's used for recover data about
 /// its properties and offsets.
 ///
+/// The properties are looked up by reflection, so the offsets keep working
+/// when a patch of the game moves the fields around. Before Unreal Engine 4.25
+/// the properties are `UProperty` objects, which are linked through their
+/// `PropertyLinkNext` field. From 4.25 on they are `FProperty` structs, which
+/// aren't objects anymore and are linked through their `PropertyLinkNext`
+/// field as well, but store their name at a different offset. Both layouts are
+/// handled based on the [`Version`] of the [`Module`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unreal::{Module, UObject}, Process};
+/// # fn example(process: &Process, module: &Module, player: UObject) {
+/// let Some(class) = player.get_class(process, module) else { return };
+/// if let Some(offset) = class.get_field_offset(process, module, "Health") {
+///     if let Ok(health) = process.read::<f32>(player.address() + offset) {
+///         // TODO: Do something with the health.
+///     }
+/// }
+/// # }
+/// ```
+///
 // Source: https://github.com/bl-sdk/unrealsdk/blob/master/src/unrealsdk/unreal/classes/ustruct.h
-#[derive(Copy, Clone)]
-struct UClass {
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UClass {
     class: Address,
 }
 
 impl UClass {
+    /// Returns the address of the class.
+    pub const fn address(&self) -> Address {
+        self.class
+    }
+
+    /// Reads the name of the class. Classes are `UObject`s themselves, so
+    /// this is the same as [`UObject::get_fname`].
+    pub fn get_fname<const N: usize>(
+        &self,
+        process: &Process,
+        module: &Module,
+    ) -> Result<ArrayCString<N>, Error> {
+        UObject { object: self.class }.get_fname(process, module)
+    }
+
+    /// Returns the class that the class inherits from, if there is one.
+    pub fn get_super_class(&self, process: &Process, module: &Module) -> Option<UClass> {
+        match process.read_pointer(
+            self.class + module.offsets.uclass_super_field,
+            module.pointer_size,
+        ) {
+            Ok(Address::NULL) | Err(_) => None,
+            Ok(val) => Some(UClass { class: val }),
+        }
+    }
+
     fn properties<'a>(
         &'a self,
         process: &'a Process,
@@ -274,9 +659,10 @@ impl UClass {
         .fuse()
     }
 
-    /// Returns the offset for the specified named property.
-    /// Returns `None` on case of failure.
-    fn get_field_offset(
+    /// Returns the offset for the specified named property, including the
+    /// properties inherited from the super classes. Returns `None` on case of
+    /// failure.
+    pub fn get_field_offset(
         &self,
         process: &Process,
         module: &Module,
@@ -290,6 +676,82 @@ impl UClass {
             })?
             .get_offset(process, module)
     }
+
+    /// Returns the offset for the specified named property. This is the
+    /// `await`able version of [`get_field_offset`](Self::get_field_offset),
+    /// yielding back to the runtime between each try.
+    pub async fn wait_get_field_offset(
+        &self,
+        process: &Process,
+        module: &Module,
+        field_name: &str,
+    ) -> u32 {
+        retry(|| self.get_field_offset(process, module, field_name)).await
+    }
+}
+
+/// A cache for the offsets of fields that were looked up by reflection. Looking
+/// up an offset walks the whole property chain of a class and reads the name of
+/// every property, so looking them up every tick is expensive. The offsets are
+/// cached per class, so each field is only looked up once per class.
+///
+/// The cache needs to be [cleared](Self::clear) when the game restarts, as the
+/// classes may then be at different addresses.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unreal::{FieldOffsetCache, Module, UObject}, Process};
+/// # fn example(process: &Process, module: &Module, player: UObject) {
+/// let mut cache = FieldOffsetCache::new();
+/// loop {
+///     if let Some(offset) = cache.get_field_offset(process, module, player, "Health") {
+///         if let Ok(health) = process.read::<f32>(player.address() + offset) {
+///             // TODO: Do something with the health.
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct FieldOffsetCache {
+    classes: BTreeMap<Address, BTreeMap<&'static str, u32>>,
+}
+
+#[cfg(feature = "alloc")]
+impl FieldOffsetCache {
+    /// Creates a new empty cache.
+    pub const fn new() -> Self {
+        Self {
+            classes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the offset of the field with the name specified in the class of
+    /// the object given. The offset is looked up by reflection the first time
+    /// and then served from the cache. Failed lookups are not cached.
+    pub fn get_field_offset(
+        &mut self,
+        process: &Process,
+        module: &Module,
+        object: UObject,
+        field_name: &'static str,
+    ) -> Option<u32> {
+        let class = object.get_class(process, module)?;
+        let fields = self.classes.entry(class.class).or_default();
+        if let Some(&offset) = fields.get(field_name) {
+            return Some(offset);
+        }
+        let offset = class.get_field_offset(process, module, field_name)?;
+        fields.insert(field_name, offset);
+        Some(offset)
+    }
+
+    /// Removes all cached offsets.
+    pub fn clear(&mut self) {
+        self.classes.clear();
+    }
 }
 
 /// Definition for a property used in a certain UClass.
@@ -307,23 +769,8 @@ impl UProperty {
         process: &Process,
         module: &Module,
     ) -> Result<ArrayCString<N>, Error> {
-        let [name_offset, chunk_offset] =
-            process.read::<[u16; 2]>(self.property + module.offsets.uproperty_fname)?;
-
-        let addr = process.read_pointer(
-            module.fname_base + module.size_of_ptr().wrapping_mul(chunk_offset as u64 + 2),
-            module.pointer_size,
-        )? + (name_offset as u64).wrapping_mul(size_of::<u16>() as u64);
-
-        let string_size = process
-            .read::<u16>(addr)?
-            .checked_shr(6)
-            .unwrap_or_default() as usize;
-
-        let mut string = process.read::<ArrayCString<N>>(addr + size_of::<u16>() as u64)?;
-        string.set_len(string_size);
-
-        Ok(string)
+        let fname = process.read::<FName>(self.property + module.offsets.uproperty_fname)?;
+        module.read_fname_entry(process, fname.comparison_index)
     }
 
     fn get_offset(&self, process: &Process, module: &Module) -> Option<u32> {
@@ -333,7 +780,40 @@ impl UProperty {
     }
 }
 
-/// An implementation for automatic pointer path resolution
+/// An implementation for automatic pointer path resolution. The path starts at
+/// a global, such as [`GEngine`](Module::g_engine) or
+/// [`GWorld`](Module::g_world), and follows fields that are looked up by name
+/// through reflection. Fields can also be given as offsets, such as `"0x10"`.
+///
+/// The offsets are only looked up once and then cached. Every dereference
+/// checks that the objects along the path still have the same class as when
+/// their fields were looked up. If the class changed, for example because the
+/// game replaced the object with one of a different class, the offsets are
+/// looked up again from that point on. Reads that fail, such as during level
+/// transitions, don't affect the cache.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{game_engine::unreal::{Module, UnrealPointer}, Process};
+/// # async fn example(process: &Process, module: &Module) {
+/// let bosses_killed = UnrealPointer::<3>::new(
+///     module.g_engine(),
+///     &["GameInstance", "MyGameState", "bossesKilled"],
+/// );
+///
+/// let mut old = bosses_killed.wait_deref::<u32>(process, module).await;
+/// loop {
+///     if let Ok(current) = bosses_killed.deref::<u32>(process, module) {
+///         if current > old {
+///             asr::timer::split();
+///         }
+///         old = current;
+///     }
+///     asr::future::next_tick().await;
+/// }
+/// # }
+/// ```
 #[derive(Clone)]
 pub struct UnrealPointer<const CAP: usize> {
     cache: RefCell<UnrealPointerCache<CAP>>,
@@ -345,6 +825,9 @@ pub struct UnrealPointer<const CAP: usize> {
 #[derive(Clone, Copy)]
 struct UnrealPointerCache<const CAP: usize> {
     offsets: [u64; CAP],
+    /// The class of the object that each offset was looked up in. This is
+    /// null for offsets that were specified directly.
+    classes: [Address; CAP],
     resolved_offsets: usize,
 }
 
@@ -363,6 +846,7 @@ impl<const CAP: usize> UnrealPointer<CAP> {
 
         let cache = RefCell::new(UnrealPointerCache {
             offsets: [u64::default(); CAP],
+            classes: [Address::NULL; CAP],
             resolved_offsets: usize::default(),
         });
 
@@ -374,65 +858,65 @@ impl<const CAP: usize> UnrealPointer<CAP> {
         }
     }
 
-    /// Tries to resolve the pointer path
-    fn find_offsets(&self, process: &Process, module: &Module) -> Result<(), Error> {
-        let mut cache = self.cache.borrow_mut();
-
-        // If the pointer path has already been found, there's no need to continue
-        if cache.resolved_offsets == self.depth {
-            return Ok(());
-        }
+    /// Clears all the cached offsets, so they are looked up again on the next
+    /// dereference.
+    pub fn reset(&self) {
+        self.cache.borrow_mut().resolved_offsets = 0;
+    }
 
-        // If we already resolved some offsets, we need to traverse them again starting from the base address
-        // (usually GWorld of GEngine) in order to recalculate the address of the farthest UObject we can reach.
-        // If no offsets have been resolved yet, we just need to read the base address instead.
+    /// Walks the pointer path, looking up the offsets that haven't been
+    /// resolved yet and checking the classes of the ones that have. Returns
+    /// the address at the end of the path.
+    fn resolve(&self, process: &Process, module: &Module) -> Result<Address, Error> {
+        let mut cache = self.cache.borrow_mut();
         let mut current_uobject = UObject {
-            object: match cache.resolved_offsets {
-                0 => process.read_pointer(self.base_address, module.pointer_size)?,
-                x => {
-                    let mut addr = process.read_pointer(self.base_address, module.pointer_size)?;
-                    for &i in &cache.offsets[..x] {
-                        addr = process.read_pointer(addr + i, module.pointer_size)?;
-                    }
-                    addr
-                }
-            },
+            object: process.read_pointer(self.base_address, module.pointer_size)?,
         };
 
-        for i in cache.resolved_offsets..self.depth {
-            let offset_from_string = match self.fields[i].strip_prefix("0x") {
-                Some(rem) => u32::from_str_radix(rem, 16).ok(),
-                _ => self.fields[i].parse().ok(),
-            };
+        for i in 0..self.depth {
+            if i < cache.resolved_offsets && cache.classes[i] != Address::NULL {
+                let class = current_uobject.get_uclass(process, module)?;
+                if class.class != cache.classes[i] {
+                    cache.resolved_offsets = i;
+                }
+            }
 
-            let current_offset = match offset_from_string {
-                Some(offset) => offset as u64,
-                _ => current_uobject
-                    .get_field_offset(process, module, self.fields[i])
-                    .ok_or(Error {})? as u64,
-            };
+            if i >= cache.resolved_offsets {
+                let offset_from_string = match self.fields[i].strip_prefix("0x") {
+                    Some(rem) => u32::from_str_radix(rem, 16).ok(),
+                    _ => self.fields[i].parse().ok(),
+                };
+
+                let (offset, class) = match offset_from_string {
+                    Some(offset) => (offset as u64, Address::NULL),
+                    _ => {
+                        let class = current_uobject.get_uclass(process, module)?;
+                        let offset = class
+                            .get_field_offset(process, module, self.fields[i])
+                            .ok_or(Error {})?;
+                        (offset as u64, class.class)
+                    }
+                };
 
-            cache.offsets[i] = current_offset;
-            cache.resolved_offsets += 1;
+                cache.offsets[i] = offset;
+                cache.classes[i] = class;
+                cache.resolved_offsets = i + 1;
+            }
 
+            let address = current_uobject.object + cache.offsets[i];
+            if i + 1 == self.depth {
+                return Ok(address);
+            }
             current_uobject = UObject {
-                object: process
-                    .read_pointer(current_uobject.object + current_offset, module.pointer_size)?,
+                object: process.read_pointer(address, module.pointer_size)?,
             };
         }
-        Ok(())
+        Err(Error {})
     }
 
     /// Dereferences the pointer path, returning the memory address at the end of the path
     pub fn deref_offsets(&self, process: &Process, module: &Module) -> Result<Address, Error> {
-        self.find_offsets(process, module)?;
-        let cache = self.cache.borrow();
-        let (&last, path) = cache.offsets[..self.depth].split_last().ok_or(Error {})?;
-        let mut address = process.read_pointer(self.base_address, module.pointer_size)?;
-        for &offset in path {
-            address = process.read_pointer(address + offset, module.pointer_size)?;
-        }
-        Ok(address + last)
+        self.resolve(process, module)
     }
 
     /// Dereferences the pointer path, returning the value stored at the final memory address
@@ -441,13 +925,14 @@ impl<const CAP: usize> UnrealPointer<CAP> {
         process: &Process,
         module: &Module,
     ) -> Result<T, Error> {
-        self.find_offsets(process, module)?;
-        let cache = self.cache.borrow();
-        process.read_pointer_path(
-            process.read_pointer(self.base_address, module.pointer_size)?,
-            module.pointer_size,
-            &cache.offsets[..self.depth],
-        )
+        process.read(self.resolve(process, module)?)
+    }
+
+    /// Dereferences the pointer path, returning the value stored at the final
+    /// memory address. This is the `await`able version of
+    /// [`deref`](Self::deref), yielding back to the runtime between each try.
+    pub async fn wait_deref<T: CheckedBitPattern>(&self, process: &Process, module: &Module) -> T {
+        retry(|| self.deref(process, module)).await
     }
 }
 
@@ -459,53 +944,111 @@ struct Offsets {
     uproperty_fname: u8,
     uproperty_offset_internal: u8,
     uproperty_property_link_next: u8,
+    uworld_persistent_level: u8,
+    ulevel_actors: u8,
+    aactor_root_component: u16,
+    uscenecomponent_relative_location: u16,
+    large_world_coordinates: bool,
 }
 
 impl Offsets {
+    /// Unreal Engine 4.23 and 4.24, where properties are `UProperty` objects.
+    // Tested on: Sonic Omens
+    const V4_23: Self = Self {
+        uobject_fname: 0x18,
+        uobject_class: 0x10,
+        uclass_super_field: 0x40,
+        uclass_property_link: 0x48,
+        uproperty_fname: 0x18,
+        uproperty_offset_internal: 0x44,
+        uproperty_property_link_next: 0x50,
+        uworld_persistent_level: 0x30,
+        ulevel_actors: 0x98,
+        aactor_root_component: 0x130,
+        uscenecomponent_relative_location: 0x11C,
+        large_world_coordinates: false,
+    };
+
+    /// Unreal Engine 4.25 to 4.27, where properties are `FProperty` fields.
+    // Tested on: Tetris Effect / Kao the Kangaroo
+    const V4_25: Self = Self {
+        uobject_fname: 0x18,
+        uobject_class: 0x10,
+        uclass_super_field: 0x40,
+        uclass_property_link: 0x50,
+        uproperty_fname: 0x28,
+        uproperty_offset_internal: 0x4C,
+        uproperty_property_link_next: 0x58,
+        uworld_persistent_level: 0x30,
+        ulevel_actors: 0x98,
+        aactor_root_component: 0x130,
+        uscenecomponent_relative_location: 0x11C,
+        large_world_coordinates: false,
+    };
+
+    /// Unreal Engine 5.0 to 5.2, which use large world coordinates.
+    const V5_0: Self = Self {
+        uobject_fname: 0x18,
+        uobject_class: 0x10,
+        uclass_super_field: 0x40,
+        uclass_property_link: 0x50,
+        uproperty_fname: 0x28,
+        uproperty_offset_internal: 0x4C,
+        uproperty_property_link_next: 0x58,
+        uworld_persistent_level: 0x30,
+        ulevel_actors: 0x98,
+        aactor_root_component: 0x198,
+        uscenecomponent_relative_location: 0x128,
+        large_world_coordinates: true,
+    };
+
+    /// Unreal Engine 5.3, where the owner of an `FField` is a single tagged
+    /// pointer and `PropertyLinkNext` directly follows `Offset_Internal`.
+    // Tested on Unreal Physics
+    const V5_3: Self = Self {
+        uobject_fname: 0x18,
+        uobject_class: 0x10,
+        uclass_super_field: 0x40,
+        uclass_property_link: 0x50,
+        uproperty_fname: 0x20,
+        uproperty_offset_internal: 0x44,
+        uproperty_property_link_next: 0x48,
+        uworld_persistent_level: 0x30,
+        ulevel_actors: 0x98,
+        aactor_root_component: 0x198,
+        uscenecomponent_relative_location: 0x128,
+        large_world_coordinates: true,
+    };
+
     const fn new(version: Version, pointer_size: PointerSize) -> Option<&'static Self> {
         match pointer_size {
             PointerSize::Bit64 => Some(match version {
-                // Tested on: Sonic Omens
-                Version::V4_23 | Version::V4_24 => &Self {
-                    uobject_fname: 0x18,
-                    uobject_class: 0x10,
-                    uclass_super_field: 0x40,
-                    uclass_property_link: 0x48,
-                    uproperty_fname: 0x18,
-                    uproperty_offset_internal: 0x44,
-                    uproperty_property_link_next: 0x50,
-                },
-                // Tested on: Tetris Effect / Kao the Kangaroo
-                Version::V4_25
-                | Version::V4_26
-                | Version::V4_27
-                | Version::V5_0
-                | Version::V5_1
-                | Version::V5_2 => &Self {
-                    uobject_fname: 0x18,
-                    uobject_class: 0x10,
-                    uclass_super_field: 0x40,
-                    uclass_property_link: 0x50,
-                    uproperty_fname: 0x28,
-                    uproperty_offset_internal: 0x4C,
-                    uproperty_property_link_next: 0x58,
-                },
-                // Tested on Unreal Physics
-                Version::V5_3 => &Self {
-                    uobject_fname: 0x18,
-                    uobject_class: 0x10,
-                    uclass_super_field: 0x40,
-                    uclass_property_link: 0x50,
-                    uproperty_fname: 0x20,
-                    uproperty_offset_internal: 0x44,
-                    uproperty_property_link_next: 0x48,
-                },
+                Version::V4_23 | Version::V4_24 => &Self::V4_23,
+                Version::V4_25 | Version::V4_26 | Version::V4_27 => &Self::V4_25,
+                Version::V5_0 | Version::V5_1 | Version::V5_2 => &Self::V5_0,
+                Version::V5_3 => &Self::V5_3,
             }),
             _ => None,
         }
     }
 }
 
+fn detect_version(process: &Process, main_module_address: Address) -> Option<Version> {
+    let (major, minor, _, _) = pe::file_version(process, main_module_address)?;
+    Some(match (major, minor) {
+        (4, 23) => Version::V4_23,
+        (4, 24) => Version::V4_24,
+        (4, 25) => Version::V4_25,
+        (4, 26) => Version::V4_26,
+        (4, 27) => Version::V4_27,
+        (5, 0) => Version::V5_0,
+        (5, 1) => Version::V5_1,
+        (5, 2) => Version::V5_2,
+        (5, 3) => Version::V5_3,
+        _ => return None,
+    })
+}
+
 #[non_exhaustive]
 #[derive(Copy, Clone, PartialEq, Hash, Debug, PartialOrd)]
 #[allow(missing_docs)]
@@ -521,3 +1064,255 @@ pub enum Version {
     V5_2,
     V5_3,
 }
+
+#[cfg(test)]
+mod tests {
+    //! The reflection offsets are checked against the definitions of the
+    //! classes in the engine's headers, mirrored for 64-bit games.
+
+    use core::mem::offset_of;
+
+    use super::*;
+
+    #[repr(C)]
+    struct FName {
+        comparison_index: u32,
+        number: u32,
+    }
+
+    #[repr(C)]
+    struct UObjectBase {
+        vtable: u64,
+        object_flags: i32,
+        internal_index: i32,
+        class_private: u64,
+        name_private: FName,
+        outer_private: u64,
+    }
+
+    #[repr(C)]
+    struct UField {
+        object: UObjectBase,
+        next: u64,
+    }
+
+    #[repr(C)]
+    struct UStruct {
+        field: UField,
+        struct_base_chain_array: u64,
+        num_struct_bases_in_chain_minus_one: i32,
+        super_struct: u64,
+        children: u64,
+        /// Only exists since 4.25.
+        child_properties: u64,
+    }
+
+    /// `UProperty` before 4.25.
+    #[repr(C)]
+    struct UProperty {
+        field: UField,
+        array_dim: i32,
+        element_size: i32,
+        property_flags: u64,
+        rep_index: u16,
+        blueprint_replication_condition: u8,
+        offset_internal: i32,
+        rep_notify_func: FName,
+        property_link_next: u64,
+    }
+
+    /// `FField` from 4.25 to 5.2, where the owner is a pointer along with a
+    /// flag that tells whether it's a `UObject`.
+    #[repr(C)]
+    struct FField {
+        vtable: u64,
+        class_private: u64,
+        owner: [u64; 2],
+        next: u64,
+        name_private: FName,
+        flags_private: u32,
+    }
+
+    #[repr(C)]
+    struct FProperty {
+        field: FField,
+        array_dim: i32,
+        element_size: i32,
+        property_flags: u64,
+        rep_index: u16,
+        blueprint_replication_condition: u8,
+        offset_internal: i32,
+        rep_notify_func: FName,
+        property_link_next: u64,
+    }
+
+    /// `FField` since 5.3, where the owner is a single tagged pointer.
+    #[repr(C)]
+    struct FField5_3 {
+        vtable: u64,
+        class_private: u64,
+        owner: u64,
+        next: u64,
+        name_private: FName,
+        flags_private: u32,
+    }
+
+    /// `FProperty` since 5.3, where `PropertyLinkNext` directly follows
+    /// `Offset_Internal`.
+    #[repr(C)]
+    struct FProperty5_3 {
+        field: FField5_3,
+        array_dim: i32,
+        element_size: i32,
+        property_flags: u64,
+        rep_index: u16,
+        blueprint_replication_condition: u8,
+        offset_internal: i32,
+        property_link_next: u64,
+    }
+
+    fn check_uobject(offsets: &Offsets) {
+        assert_eq!(
+            offsets.uobject_class as usize,
+            offset_of!(UObjectBase, class_private)
+        );
+        assert_eq!(
+            offsets.uobject_fname as usize,
+            offset_of!(UObjectBase, name_private)
+        );
+        assert_eq!(
+            offsets.uclass_super_field as usize,
+            offset_of!(UStruct, super_struct)
+        );
+    }
+
+    #[test]
+    fn uproperty_layout() {
+        let offsets = &Offsets::V4_23;
+        check_uobject(offsets);
+        // The properties are found through the children of the class.
+        assert_eq!(
+            offsets.uclass_property_link as usize,
+            offset_of!(UStruct, children)
+        );
+        assert_eq!(
+            offsets.uproperty_fname as usize,
+            offset_of!(UObjectBase, name_private)
+        );
+        assert_eq!(
+            offsets.uproperty_offset_internal as usize,
+            offset_of!(UProperty, offset_internal)
+        );
+        assert_eq!(
+            offsets.uproperty_property_link_next as usize,
+            offset_of!(UProperty, property_link_next)
+        );
+    }
+
+    #[test]
+    fn fproperty_layout() {
+        for offsets in [&Offsets::V4_25, &Offsets::V5_0] {
+            check_uobject(offsets);
+            assert_eq!(
+                offsets.uclass_property_link as usize,
+                offset_of!(UStruct, child_properties)
+            );
+            assert_eq!(
+                offsets.uproperty_fname as usize,
+                offset_of!(FField, name_private)
+            );
+            assert_eq!(
+                offsets.uproperty_offset_internal as usize,
+                offset_of!(FProperty, offset_internal)
+            );
+            assert_eq!(
+                offsets.uproperty_property_link_next as usize,
+                offset_of!(FProperty, property_link_next)
+            );
+        }
+    }
+
+    #[test]
+    fn fproperty_layout_5_3() {
+        let offsets = &Offsets::V5_3;
+        check_uobject(offsets);
+        assert_eq!(
+            offsets.uclass_property_link as usize,
+            offset_of!(UStruct, child_properties)
+        );
+        assert_eq!(
+            offsets.uproperty_fname as usize,
+            offset_of!(FField5_3, name_private)
+        );
+        assert_eq!(
+            offsets.uproperty_offset_internal as usize,
+            offset_of!(FProperty5_3, offset_internal)
+        );
+        assert_eq!(
+            offsets.uproperty_property_link_next as usize,
+            offset_of!(FProperty5_3, property_link_next)
+        );
+    }
+
+    fn same_layout(a: &Offsets, b: &Offsets) -> bool {
+        (
+            a.uclass_property_link,
+            a.uproperty_fname,
+            a.uproperty_offset_internal,
+            a.uproperty_property_link_next,
+            a.aactor_root_component,
+            a.large_world_coordinates,
+        ) == (
+            b.uclass_property_link,
+            b.uproperty_fname,
+            b.uproperty_offset_internal,
+            b.uproperty_property_link_next,
+            b.aactor_root_component,
+            b.large_world_coordinates,
+        )
+    }
+
+    #[test]
+    fn versions() {
+        for (versions, expected) in [
+            (&[Version::V4_23, Version::V4_24][..], &Offsets::V4_23),
+            (
+                &[Version::V4_25, Version::V4_26, Version::V4_27],
+                &Offsets::V4_25,
+            ),
+            (
+                &[Version::V5_0, Version::V5_1, Version::V5_2],
+                &Offsets::V5_0,
+            ),
+            (&[Version::V5_3], &Offsets::V5_3),
+        ] {
+            for &version in versions {
+                let offsets = Offsets::new(version, PointerSize::Bit64).unwrap();
+                assert!(same_layout(offsets, expected), "{version:?}");
+            }
+        }
+        assert!(Offsets::new(Version::V5_3, PointerSize::Bit32).is_none());
+    }
+
+    #[test]
+    fn large_world_coordinates() {
+        for version in [
+            Version::V4_23,
+            Version::V4_24,
+            Version::V4_25,
+            Version::V4_26,
+            Version::V4_27,
+            Version::V5_0,
+            Version::V5_1,
+            Version::V5_2,
+            Version::V5_3,
+        ] {
+            let offsets = Offsets::new(version, PointerSize::Bit64).unwrap();
+            assert_eq!(
+                offsets.large_world_coordinates,
+                version >= Version::V5_0,
+                "{version:?}"
+            );
+        }
+    }
+}
@@ -130,12 +130,14 @@ extern crate alloc;
 mod primitives;
 mod runtime;
 
+pub mod bitfield;
 pub mod deep_pointer;
 pub mod emulator;
 #[macro_use]
 pub mod future;
 pub mod file_format;
 pub mod game_engine;
+pub mod memory_reader;
 #[cfg(feature = "signature")]
 pub mod signature;
 pub mod string;
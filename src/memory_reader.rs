@@ -0,0 +1,248 @@
+//! Support for reading memory either directly from a process or through a
+//! cache.
+
+use core::{
+    cell::{Cell, RefCell},
+    mem::{self, MaybeUninit},
+    slice,
+};
+
+use arrayvec::ArrayVec;
+use bytemuck::CheckedBitPattern;
+
+use crate::{
+    deep_pointer::{PathStep, PointerPathError},
+    string::{ArrayString, ArrayWString},
+    Address, Address16, Address32, Address64, Error, PointerSize, Process,
+};
+
+/// The size of the pages that are cached by a [`MemoryCache`].
+const PAGE_SIZE: usize = 0x1000;
+
+/// A source that memory can be read from. This is implemented by [`Process`]
+/// and [`MemoryCache`], so code that reads memory, such as
+/// [`DeepPointer`](crate::deep_pointer::DeepPointer), can use either of them.
+pub trait MemoryReader {
+    /// Returns the process that the memory is read from.
+    fn process(&self) -> &Process;
+
+    /// Reads a range of bytes at the address given into the buffer provided.
+    fn read_into_buf(&self, address: Address, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Reads a value of the type specified at the address given.
+    fn read<T: CheckedBitPattern>(&self, address: impl Into<Address>) -> Result<T, Error> {
+        // SAFETY: We provide a valid pointer and length to the zeroed value,
+        // so all of its bytes are initialized. We also do proper error
+        // handling after reading into it. We then check if the value is a
+        // valid bit pattern for the type. We can then assume that the value is
+        // valid and return it.
+        unsafe {
+            let mut value = MaybeUninit::<T>::zeroed();
+            self.read_into_buf(
+                address.into(),
+                slice::from_raw_parts_mut(value.as_mut_ptr().cast(), mem::size_of::<T>()),
+            )?;
+            if !T::is_valid_bit_pattern(&*value.as_ptr().cast::<T::Bits>()) {
+                return Err(Error {});
+            }
+            Ok(value.assume_init())
+        }
+    }
+
+    /// Reads a pointer address at the address given.
+    fn read_pointer(
+        &self,
+        address: impl Into<Address>,
+        pointer_size: PointerSize,
+    ) -> Result<Address, Error> {
+        Ok(match pointer_size {
+            PointerSize::Bit16 => self.read::<Address16>(address)?.into(),
+            PointerSize::Bit32 => self.read::<Address32>(address)?.into(),
+            PointerSize::Bit64 => self.read::<Address64>(address)?.into(),
+        })
+    }
+
+    /// Follows a path of pointers from the address given and reads a value of
+    /// the type specified at the end of the pointer path.
+    fn read_pointer_path<T: CheckedBitPattern>(
+        &self,
+        address: impl Into<Address>,
+        pointer_size: PointerSize,
+        path: &[u64],
+    ) -> Result<T, Error> {
+        let mut address = address.into();
+        let (&last, path) = path.split_last().ok_or(Error {})?;
+        for &offset in path {
+            address = self.read_pointer(address + offset, pointer_size)?;
+        }
+        self.read(address + last)
+    }
+
+    /// Follows a path of steps from the address given, returning the address
+    /// that the path ends at. Each step can use its own pointer size. If a
+    /// pointer can't be read, the error describes the step that failed.
+    fn resolve_path(
+        &self,
+        address: impl Into<Address>,
+        steps: &[PathStep],
+    ) -> Result<Address, PointerPathError> {
+        let mut address = address.into();
+        for (step, path_step) in steps.iter().enumerate() {
+            address = address + path_step.offset;
+            if let Some(pointer_size) = path_step.deref {
+                address = self
+                    .read_pointer(address, pointer_size)
+                    .map_err(|_| PointerPathError { step, address })?;
+            }
+        }
+        Ok(address)
+    }
+
+    /// Follows a path of steps from the address given and reads a value of the
+    /// type specified at the address that the path ends at. Check
+    /// [`resolve_path`](Self::resolve_path) for more information.
+    fn read_path<T: CheckedBitPattern>(
+        &self,
+        address: impl Into<Address>,
+        steps: &[PathStep],
+    ) -> Result<T, PointerPathError> {
+        let address = self.resolve_path(address, steps)?;
+        self.read(address).map_err(|_| PointerPathError {
+            step: steps.len(),
+            address,
+        })
+    }
+
+    /// Reads a nul-terminated UTF-16 string of up to `N` 16-bit characters at
+    /// the address given and converts it to UTF-8. Check
+    /// [`Process::read_utf16_string`] for more information.
+    fn read_utf16_string<const N: usize>(
+        &self,
+        address: impl Into<Address>,
+    ) -> Result<ArrayString<N>, Error> {
+        Ok(self.read::<ArrayWString<N>>(address)?.to_array_string())
+    }
+}
+
+impl MemoryReader for Process {
+    #[inline]
+    fn process(&self) -> &Process {
+        self
+    }
+
+    #[inline]
+    fn read_into_buf(&self, address: Address, buf: &mut [u8]) -> Result<(), Error> {
+        Process::read_into_buf(self, address, buf)
+    }
+
+    #[inline]
+    fn read<T: CheckedBitPattern>(&self, address: impl Into<Address>) -> Result<T, Error> {
+        Process::read(self, address)
+    }
+}
+
+struct Page {
+    address: Address,
+    data: [u8; PAGE_SIZE],
+}
+
+/// Caches the memory of a process in pages of 4 KiB. Reading many values that
+/// are close together, such as the fields of a single struct, then only reads
+/// the page from the process once. Up to `PAGES` pages are cached at a time.
+/// Once the cache is full, the pages are evicted in the order that they were
+/// read.
+///
+/// The cache doesn't know when the memory of the process changes, so it needs
+/// to be [cleared](Self::clear) at the start of every tick. Values that cross
+/// a page boundary are always read directly from the process. Failed reads are
+/// not cached.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{memory_reader::{MemoryCache, MemoryReader}, Address, Process};
+/// # fn example(process: &Process, game_state: Address) {
+/// let mut cache = MemoryCache::<8>::new(process);
+/// loop {
+///     cache.clear();
+///     let level = cache.read::<u32>(game_state + 0x10);
+///     let igt = cache.read::<f64>(game_state + 0x18);
+///     // TODO: Do something with the values.
+/// }
+/// # }
+/// ```
+pub struct MemoryCache<'a, const PAGES: usize> {
+    process: &'a Process,
+    pages: RefCell<ArrayVec<Page, PAGES>>,
+    next_eviction: Cell<usize>,
+}
+
+impl<'a, const PAGES: usize> MemoryCache<'a, PAGES> {
+    /// Creates a new empty cache for the process given.
+    pub const fn new(process: &'a Process) -> Self {
+        Self {
+            process,
+            pages: RefCell::new(ArrayVec::new_const()),
+            next_eviction: Cell::new(0),
+        }
+    }
+
+    /// Returns the process that the memory is read from.
+    pub const fn process(&self) -> &'a Process {
+        self.process
+    }
+
+    /// Removes all pages from the cache, so that subsequent reads observe the
+    /// current memory of the process.
+    pub fn clear(&mut self) {
+        self.pages.get_mut().clear();
+        self.next_eviction.set(0);
+    }
+}
+
+impl<const PAGES: usize> MemoryReader for MemoryCache<'_, PAGES> {
+    #[inline]
+    fn process(&self) -> &Process {
+        self.process
+    }
+
+    fn read_into_buf(&self, address: Address, buf: &mut [u8]) -> Result<(), Error> {
+        let offset = (address.value() % PAGE_SIZE as u64) as usize;
+        if PAGES == 0 || buf.is_empty() || offset + buf.len() > PAGE_SIZE {
+            return self.process.read_into_buf(address, buf);
+        }
+        let page_address = address.align_down(PAGE_SIZE as u64);
+
+        let mut pages = self.pages.borrow_mut();
+        let index = match pages.iter().position(|p| p.address == page_address) {
+            Some(index) => index,
+            None => {
+                let mut page = Page {
+                    address: page_address,
+                    data: [0; PAGE_SIZE],
+                };
+                if self
+                    .process
+                    .read_into_buf(page_address, &mut page.data)
+                    .is_err()
+                {
+                    // Parts of the page may still be readable, so the value
+                    // is read directly instead.
+                    return self.process.read_into_buf(address, buf);
+                }
+                if pages.is_full() {
+                    let index = self.next_eviction.get();
+                    self.next_eviction.set((index + 1) % PAGES);
+                    pages[index] = page;
+                    index
+                } else {
+                    pages.push(page);
+                    pages.len() - 1
+                }
+            }
+        };
+
+        buf.copy_from_slice(&pages[index].data[offset..offset + buf.len()]);
+        Ok(())
+    }
+}
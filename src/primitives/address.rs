@@ -43,6 +43,94 @@ macro_rules! define_addr {
             pub const fn add_signed(self, bytes: $inner_i) -> Self {
                 Self(self.0.wrapping_add_signed(bytes))
             }
+
+            /// Offsets the address by the given number of bytes. Returns
+            /// [`None`] if the address would overflow instead of wrapping
+            /// around.
+            #[inline]
+            pub const fn checked_add(self, bytes: $inner_u) -> Option<Self> {
+                match self.0.checked_add(bytes) {
+                    Some(value) => Some(Self(value)),
+                    None => None,
+                }
+            }
+
+            /// Offsets the address backwards by the given number of bytes.
+            /// Returns [`None`] if the address would underflow instead of
+            /// wrapping around.
+            #[inline]
+            pub const fn checked_sub(self, bytes: $inner_u) -> Option<Self> {
+                match self.0.checked_sub(bytes) {
+                    Some(value) => Some(Self(value)),
+                    None => None,
+                }
+            }
+
+            /// Offsets the address by the given signed number of bytes.
+            /// Returns [`None`] if the address would overflow or underflow
+            /// instead of wrapping around.
+            #[inline]
+            pub const fn checked_add_signed(self, bytes: $inner_i) -> Option<Self> {
+                match self.0.checked_add_signed(bytes) {
+                    Some(value) => Some(Self(value)),
+                    None => None,
+                }
+            }
+
+            /// Returns the signed distance in bytes from the other address to
+            /// this address. If the distance doesn't fit into the signed
+            /// integer, it wraps around.
+            #[inline]
+            pub const fn diff(self, other: Self) -> $inner_i {
+                self.0.wrapping_sub(other.0) as $inner_i
+            }
+
+            /// Rounds the address down to a multiple of the alignment given.
+            /// This never overflows.
+            ///
+            /// # Panics
+            ///
+            /// This panics if the alignment is not a power of two.
+            #[inline]
+            pub const fn align_down(self, alignment: $inner_u) -> Self {
+                assert!(
+                    alignment.is_power_of_two(),
+                    "The alignment needs to be a power of two."
+                );
+                Self(self.0 & !(alignment - 1))
+            }
+
+            /// Rounds the address up to a multiple of the alignment given.
+            /// Returns [`None`] if the rounded address would overflow.
+            ///
+            /// # Panics
+            ///
+            /// This panics if the alignment is not a power of two.
+            #[inline]
+            pub const fn align_up(self, alignment: $inner_u) -> Option<Self> {
+                assert!(
+                    alignment.is_power_of_two(),
+                    "The alignment needs to be a power of two."
+                );
+                match self.0.checked_add(alignment - 1) {
+                    Some(value) => Some(Self(value & !(alignment - 1))),
+                    None => None,
+                }
+            }
+
+            /// Checks whether the address is a multiple of the alignment given.
+            ///
+            /// # Panics
+            ///
+            /// This panics if the alignment is not a power of two.
+            #[inline]
+            pub const fn is_aligned(self, alignment: $inner_u) -> bool {
+                assert!(
+                    alignment.is_power_of_two(),
+                    "The alignment needs to be a power of two."
+                );
+                self.0 & (alignment - 1) == 0
+            }
         }
 
         impl fmt::Debug for $name {
@@ -176,3 +264,108 @@ impl Add<u32> for Address64 {
         Self(self.0.wrapping_add(bytes as _))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_add() {
+        assert_eq!(
+            Address::new(0x1000).checked_add(0x10),
+            Some(Address::new(0x1010))
+        );
+        assert_eq!(
+            Address::new(u64::MAX - 1).checked_add(1),
+            Some(Address::new(u64::MAX))
+        );
+        assert_eq!(Address::new(u64::MAX).checked_add(1), None);
+        assert_eq!(Address::new(1).checked_add(u64::MAX), None);
+        assert_eq!(Address32::new(u32::MAX).checked_add(1), None);
+    }
+
+    #[test]
+    fn checked_sub() {
+        assert_eq!(
+            Address::new(0x1010).checked_sub(0x10),
+            Some(Address::new(0x1000))
+        );
+        assert_eq!(Address::new(1).checked_sub(1), Some(Address::NULL));
+        assert_eq!(Address::NULL.checked_sub(1), None);
+        assert_eq!(Address16::new(0x10).checked_sub(0x11), None);
+    }
+
+    #[test]
+    fn checked_add_signed() {
+        assert_eq!(
+            Address::new(0x1000).checked_add_signed(-0x10),
+            Some(Address::new(0xFF0)),
+        );
+        assert_eq!(
+            Address::new(0x1000).checked_add_signed(0x10),
+            Some(Address::new(0x1010)),
+        );
+        assert_eq!(Address::new(0x10).checked_add_signed(-0x11), None);
+        assert_eq!(Address::new(u64::MAX).checked_add_signed(1), None);
+        assert_eq!(
+            Address::new(u64::MAX).checked_add_signed(i64::MIN),
+            Some(Address::new(i64::MAX as u64)),
+        );
+    }
+
+    #[test]
+    fn diff() {
+        assert_eq!(Address::new(0x1010).diff(Address::new(0x1000)), 0x10);
+        assert_eq!(Address::new(0x1000).diff(Address::new(0x1010)), -0x10);
+        assert_eq!(Address::NULL.diff(Address::new(u64::MAX)), 1);
+    }
+
+    #[test]
+    fn align() {
+        assert_eq!(
+            Address::new(0x1234).align_down(0x1000),
+            Address::new(0x1000)
+        );
+        assert_eq!(
+            Address::new(0x1234).align_up(0x1000),
+            Some(Address::new(0x2000))
+        );
+        assert_eq!(
+            Address::new(0x2000).align_up(0x1000),
+            Some(Address::new(0x2000))
+        );
+        assert_eq!(Address::new(0x1234).align_up(1), Some(Address::new(0x1234)));
+        assert!(Address::new(0x2000).is_aligned(0x1000));
+        assert!(!Address::new(0x2001).is_aligned(0x1000));
+    }
+
+    #[test]
+    fn align_up_near_max() {
+        let max = Address::new(u64::MAX);
+        assert_eq!(max.align_up(1), Some(max));
+        assert_eq!(max.align_up(2), None);
+        assert_eq!(
+            Address::new(u64::MAX - 0xFFF).align_up(0x1000),
+            Some(Address::new(u64::MAX - 0xFFF)),
+        );
+        assert_eq!(Address::new(u64::MAX - 0xFFE).align_up(0x1000), None);
+        assert_eq!(
+            Address::new(u64::MAX - 0xF).align_up(0x10),
+            Some(Address::new(u64::MAX - 0xF))
+        );
+        assert_eq!(Address::new(u64::MAX - 0xE).align_up(0x10), None);
+        assert_eq!(
+            Address::new(1).align_up(1 << 63),
+            Some(Address::new(1 << 63))
+        );
+        assert_eq!(Address::new((1 << 63) + 1).align_up(1 << 63), None);
+        assert_eq!(Address32::new(u32::MAX - 2).align_up(4), None);
+        assert_eq!(max.align_down(0x1000), Address::new(u64::MAX - 0xFFF));
+    }
+
+    #[test]
+    #[should_panic]
+    fn align_up_requires_power_of_two() {
+        let _ = Address::new(0x1000).align_up(3);
+    }
+}
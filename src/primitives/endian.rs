@@ -92,3 +92,27 @@ impl<T: FromEndian, const N: usize> FromEndian for [T; N] {
         array::from_fn(|_| iter.next().unwrap().from_le())
     }
 }
+
+macro_rules! define_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: FromEndian),+> FromEndian for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn from_be(&self) -> Self {
+                let ($($name,)+) = self;
+                ($($name.from_be(),)+)
+            }
+            #[allow(non_snake_case)]
+            fn from_le(&self) -> Self {
+                let ($($name,)+) = self;
+                ($($name.from_le(),)+)
+            }
+        }
+    };
+}
+
+define_tuple!(A);
+define_tuple!(A, B);
+define_tuple!(A, B, C);
+define_tuple!(A, B, C, D);
+define_tuple!(A, B, C, D, E);
+define_tuple!(A, B, C, D, E, F);
@@ -13,7 +13,9 @@ bitflags::bitflags! {
         const WRITE = 1 << 2;
         /// The memory range is executable.
         const EXECUTE = 1 << 3;
-        /// The memory range has a file path.
+        /// The memory range has a file path. This is the case for memory
+        /// that is mapped from a file, such as the code of a module. Ranges
+        /// without a path are private to the process, such as its heap.
         const PATH = 1 << 4;
     }
 }
@@ -62,6 +64,17 @@ impl MemoryRange<'_> {
         Ok((self.address()?, self.size()?))
     }
 
+    /// Checks whether the memory range contains the address given.
+    #[inline]
+    pub fn contains(&self, address: impl Into<Address>) -> Result<bool, Error> {
+        let (start, size) = self.range()?;
+        Ok(address
+            .into()
+            .value()
+            .checked_sub(start.value())
+            .is_some_and(|offset| offset < size))
+    }
+
     /// Queries the flags of the memory range.
     #[cfg(feature = "flags")]
     #[inline]
@@ -1,3 +1,5 @@
+use core::sync::atomic::{self, AtomicU64};
+
 pub use memory_range::*;
 pub use process::*;
 
@@ -13,15 +15,69 @@ pub mod timer;
 #[non_exhaustive]
 pub struct Error {}
 
+/// The tick rate that was last set, stored as the bits of an [`f64`].
+static TICK_RATE: AtomicU64 = AtomicU64::new(DEFAULT_TICK_RATE.to_bits());
+
+/// The tick rate of the runtime if it was never changed.
+const DEFAULT_TICK_RATE: f64 = 120.0;
+
 /// Sets the tick rate of the runtime. This influences how many times per second
 /// the `update` function is called. The default tick rate is 120 ticks per
 /// second.
 #[inline]
 pub fn set_tick_rate(ticks_per_second: f64) {
+    TICK_RATE.store(ticks_per_second.to_bits(), atomic::Ordering::Relaxed);
     // SAFETY: It is always safe to call this function.
     unsafe { sys::runtime_set_tick_rate(ticks_per_second) }
 }
 
+/// Returns the tick rate that was last set with [`set_tick_rate`]. The runtime
+/// can't be queried for the tick rate, so this returns the default of 120 ticks
+/// per second if it was never set.
+#[inline]
+pub fn tick_rate() -> f64 {
+    f64::from_bits(TICK_RATE.load(atomic::Ordering::Relaxed))
+}
+
+/// Lowers the tick rate of the runtime while it is alive and restores the
+/// previous tick rate when it is dropped. This reduces the CPU usage of the
+/// auto splitter while there isn't much to do, such as while waiting for the
+/// game to start a run. Guards can be nested, as long as they are dropped in
+/// the reverse order that they were created in.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{IdleGuard, Process};
+/// # async fn example() {
+/// let process = {
+///     let _idle = IdleGuard::new(1.0);
+///     Process::wait_attach("Game.exe").await
+/// };
+/// // The previous tick rate is restored here.
+/// # }
+/// ```
+#[must_use = "The previous tick rate is restored immediately if the guard is not stored."]
+pub struct IdleGuard {
+    previous: f64,
+}
+
+impl IdleGuard {
+    /// Sets the tick rate to the one given and remembers the previous tick
+    /// rate, so it can be restored when the guard is dropped.
+    pub fn new(ticks_per_second: f64) -> Self {
+        let previous = tick_rate();
+        set_tick_rate(ticks_per_second);
+        Self { previous }
+    }
+}
+
+impl Drop for IdleGuard {
+    fn drop(&mut self) {
+        set_tick_rate(self.previous);
+    }
+}
+
 /// Prints a log message for debugging purposes.
 #[inline]
 pub fn print_message(text: &str) {
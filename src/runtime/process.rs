@@ -4,12 +4,42 @@ use core::{
     slice,
 };
 
-use crate::{Address, Address16, Address32, Address64, PointerSize};
+use crate::{
+    string::{ArrayString, ArrayWString},
+    Address, Address16, Address32, Address64, Endian, FromEndian, PointerSize,
+};
 
 use super::{sys, Error, MemoryRange};
 
 pub use super::sys::ProcessId;
 
+/// Remembers the processes that didn't match when attaching to a process by
+/// its executable path, so they don't need to be attached to again on every
+/// try. The path of a process never changes, so a process is skipped until it
+/// exits. The runtime doesn't signal when the list of processes changes, so
+/// the processes with the name are still listed on every try, which is cheap
+/// compared to attaching to them.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default, Clone)]
+pub struct ProcessMatchCache {
+    rejected: alloc::vec::Vec<ProcessId>,
+}
+
+#[cfg(feature = "alloc")]
+impl ProcessMatchCache {
+    /// Creates a new empty cache.
+    pub const fn new() -> Self {
+        Self {
+            rejected: alloc::vec::Vec::new(),
+        }
+    }
+
+    /// Forgets all the processes that didn't match.
+    pub fn clear(&mut self) {
+        self.rejected.clear();
+    }
+}
+
 /// A process that the auto splitter is attached to.
 #[repr(transparent)]
 pub struct Process(pub(super) sys::Process);
@@ -130,6 +160,128 @@ impl Process {
         }
     }
 
+    /// Attaches to all processes with the given name. Processes that exit
+    /// before they can be attached to are skipped. This is useful if multiple
+    /// processes share the same name, such as a game and its crash handler, and
+    /// the right one needs to be picked, for example by comparing the size of
+    /// their main modules.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::Process;
+    /// let process = Process::attach_all_by_name("Game.exe")
+    ///     .max_by_key(|p| p.get_module_size("Game.exe").unwrap_or_default());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn attach_all_by_name(name: &str) -> impl Iterator<Item = Self> {
+        Self::list_by_name(name)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(Self::attach_by_pid)
+    }
+
+    /// Attaches to the process with the given name whose executable path ends
+    /// with the given suffix. This is useful for games that run within a
+    /// generic host process, such as `java` or `dotnet`. The path is the one
+    /// returned by [`get_path`](Self::get_path), so it uses forward slashes
+    /// as separators. When the runtime is running on Windows, the comparison
+    /// ignores ASCII case.
+    ///
+    /// Every process with the name is briefly attached to in order to query
+    /// its path. Use [`attach_by_path_cached`](Self::attach_by_path_cached)
+    /// when calling this repeatedly, so the processes that didn't match are
+    /// skipped on the next tries.
+    #[cfg(feature = "alloc")]
+    pub fn attach_by_path(name: &str, path_suffix: &str) -> Option<Self> {
+        Self::attach_by_path_cached(name, path_suffix, &mut ProcessMatchCache::new())
+    }
+
+    /// Attaches to the process with the given name whose executable path ends
+    /// with the given suffix, skipping the processes that the cache remembers
+    /// as not matching. See [`attach_by_path`](Self::attach_by_path) for
+    /// details.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example() {
+    /// use asr::{future::retry, Process, ProcessMatchCache};
+    ///
+    /// let mut cache = ProcessMatchCache::new();
+    /// let process = retry(|| {
+    ///     Process::attach_by_path_cached("java", "/Game/game.jar", &mut cache)
+    /// })
+    /// .await;
+    /// # }
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn attach_by_path_cached(
+        name: &str,
+        path_suffix: &str,
+        cache: &mut ProcessMatchCache,
+    ) -> Option<Self> {
+        let ignore_case = super::get_os().is_ok_and(|os| &*os == "windows");
+        Self::attach_matching_cached(name, cache, |path| {
+            let Some(start) = path.len().checked_sub(path_suffix.len()) else {
+                return false;
+            };
+            path.as_bytes()[start..]
+                .iter()
+                .zip(path_suffix.as_bytes())
+                .all(|(a, b)| a == b || (ignore_case && a.eq_ignore_ascii_case(b)))
+        })
+    }
+
+    /// Attaches to the first process with the given name whose executable path
+    /// matches the predicate. Every process with the name is briefly attached
+    /// to in order to query its path, so this should not be called on every
+    /// tick. Use [`attach_matching_cached`](Self::attach_matching_cached)
+    /// instead when calling this repeatedly.
+    #[cfg(feature = "alloc")]
+    pub fn attach_matching(name: &str, predicate: impl FnMut(&str) -> bool) -> Option<Self> {
+        Self::attach_matching_cached(name, &mut ProcessMatchCache::new(), predicate)
+    }
+
+    /// Attaches to the first process with the given name whose executable path
+    /// matches the predicate, skipping the processes that the cache remembers
+    /// as not matching. The processes that are found not to match are added to
+    /// the cache. The same cache must not be used with different predicates.
+    #[cfg(feature = "alloc")]
+    pub fn attach_matching_cached(
+        name: &str,
+        cache: &mut ProcessMatchCache,
+        mut predicate: impl FnMut(&str) -> bool,
+    ) -> Option<Self> {
+        let pids = Self::list_by_name(name)?;
+        // Process ids may be reused once a process exits, so only the ids of
+        // the processes that still exist are remembered.
+        cache.rejected.retain(|pid| pids.contains(pid));
+
+        pids.into_iter().find_map(|pid| {
+            if cache.rejected.contains(&pid) {
+                return None;
+            }
+            let process = Self::attach_by_pid(pid)?;
+            // The path may not be available yet while the process is
+            // starting up, so failing to query it isn't remembered.
+            let path = process.get_path().ok()?;
+            if predicate(&path) {
+                Some(process)
+            } else {
+                cache.rejected.push(pid);
+                None
+            }
+        })
+    }
+
+    /// Returns the handle of the attachment to the process. Attaching to the
+    /// same process again results in a different handle.
+    #[inline]
+    pub(crate) const fn handle(&self) -> u64 {
+        self.0 .0.get()
+    }
+
     /// Checks whether the process is still open. If it is not open anymore, you
     /// should drop the process.
     #[inline]
@@ -229,13 +381,42 @@ impl Process {
         }
     }
 
-    /// Gets the address and size of a module in the process.
+    /// Gets the address and size of a module in the process. The size is the
+    /// one that the operating system reports. For packed Windows modules it
+    /// may be smaller than the memory that the module actually occupies. In
+    /// that case [`pe::committed_size`](crate::file_format::pe::committed_size)
+    /// should be preferred.
     #[inline]
     pub fn get_module_range(&self, name: &str) -> Result<(Address, u64), Error> {
         Ok((self.get_module_address(name)?, self.get_module_size(name)?))
     }
 
-    /// Iterates over all committed (not reserved, not free) memory ranges of the process.
+    /// Gets the file name of the executable of the process. This is the name of
+    /// the main module, so it can be passed to functions such as
+    /// [`get_module_range`](Self::get_module_range).
+    #[cfg(feature = "alloc")]
+    pub fn get_main_module_name(&self) -> Result<alloc::string::String, Error> {
+        let mut path = self.get_path()?;
+        if let Some(index) = path.rfind(['/', '\\']) {
+            path.drain(..=index);
+        }
+        if path.is_empty() {
+            return Err(Error {});
+        }
+        Ok(path)
+    }
+
+    /// Gets the address and size of the main module of the process, which is
+    /// the module of its executable.
+    #[cfg(feature = "alloc")]
+    pub fn get_main_module_range(&self) -> Result<(Address, u64), Error> {
+        self.get_module_range(&self.get_main_module_name()?)
+    }
+
+    /// Iterates over all committed (not reserved, not free) memory ranges of the
+    /// process. The ranges are not guaranteed to be sorted by their address.
+    /// The information about each range is queried lazily, so no allocations
+    /// are necessary.
     #[inline]
     pub fn memory_ranges(&self) -> impl DoubleEndedIterator<Item = MemoryRange<'_>> {
         // SAFETY: The process handle is guaranteed to be valid. We handle the
@@ -247,6 +428,15 @@ impl Process {
         })
     }
 
+    /// Finds the committed memory range that contains the address given.
+    /// Returns [`None`] if the address is not part of any committed memory
+    /// range.
+    pub fn range_containing(&self, address: impl Into<Address>) -> Option<MemoryRange<'_>> {
+        let address = address.into();
+        self.memory_ranges()
+            .find(|range| range.contains(address).unwrap_or_default())
+    }
+
     /// Reads a value of the type specified from the process at the address
     /// given.
     #[inline]
@@ -270,10 +460,68 @@ impl Process {
         }
     }
 
+    /// Reads a value of the type specified from the process at the address
+    /// given and converts it from big endian. This is useful for emulated
+    /// consoles, which often store their memory in big endian. Types that
+    /// derive [`FromEndian`] have all of their fields converted.
+    #[inline]
+    pub fn read_be<T: CheckedBitPattern + FromEndian>(
+        &self,
+        address: impl Into<Address>,
+    ) -> Result<T, Error> {
+        Ok(self.read::<T>(address)?.from_be())
+    }
+
+    /// Reads a value of the type specified from the process at the address
+    /// given and converts it from little endian. Check
+    /// [`read_be`](Self::read_be) for more information.
+    #[inline]
+    pub fn read_le<T: CheckedBitPattern + FromEndian>(
+        &self,
+        address: impl Into<Address>,
+    ) -> Result<T, Error> {
+        Ok(self.read::<T>(address)?.from_le())
+    }
+
+    /// Reads a value of the type specified from the process at the address
+    /// given and converts it from the endianness given. Check
+    /// [`read_be`](Self::read_be) for more information.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{Address, Endian, FromEndian, Process};
+    /// #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable, FromEndian)]
+    /// #[repr(C)]
+    /// struct GameState {
+    ///     level: u32,
+    ///     timer: f32,
+    ///     collectibles: [u16; 4],
+    /// }
+    ///
+    /// # fn example(process: &Process, address: Address) {
+    /// let state = process.read_endian::<GameState>(address, Endian::Big);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn read_endian<T: CheckedBitPattern + FromEndian>(
+        &self,
+        address: impl Into<Address>,
+        endian: Endian,
+    ) -> Result<T, Error> {
+        Ok(self.read::<T>(address)?.from_endian(endian))
+    }
+
     /// Reads a range of bytes from the process at the address given into the
-    /// buffer provided.
+    /// buffer provided. The bytes are read all at once, so the read fails if
+    /// any part of the range is not readable. In that case the contents of the
+    /// buffer are unspecified, as it may have been partially filled. Reading
+    /// into an empty buffer always succeeds.
     #[inline]
     pub fn read_into_buf(&self, address: impl Into<Address>, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.is_empty() {
+            return Ok(());
+        }
         // SAFETY: The process handle is guaranteed to be valid. We provide a
         // valid pointer and length to the buffer. We also do proper error
         // handling afterwards.
@@ -289,13 +537,18 @@ impl Process {
 
     /// Reads a range of bytes from the process at the address given into the
     /// buffer provided. The buffer does not need to be initialized. After the
-    /// buffer successfully got filled, the initialized buffer is returned.
+    /// buffer successfully got filled, the initialized buffer is returned. The
+    /// bytes are read all at once, so the read fails if any part of the range
+    /// is not readable. Reading into an empty buffer always succeeds.
     #[inline]
     pub fn read_into_uninit_buf<'buf>(
         &self,
         address: impl Into<Address>,
         buf: &'buf mut [MaybeUninit<u8>],
     ) -> Result<&'buf mut [u8], Error> {
+        if buf.is_empty() {
+            return Ok(&mut []);
+        }
         // SAFETY: The process handle is guaranteed to be valid. We provide a
         // valid pointer and length to the buffer. We also do proper error
         // handling afterwards. The buffer is guaranteed to be initialized
@@ -414,6 +667,56 @@ impl Process {
         Ok(buf)
     }
 
+    /// Reads a nul-terminated UTF-16 string of up to `N` 16-bit characters
+    /// from the process at the address given and converts it to UTF-8.
+    /// Unpaired surrogates are replaced by
+    /// [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER). If there is no
+    /// nul-terminator within the `N` characters, the string is truncated
+    /// instead of failing. The same happens if the converted string doesn't
+    /// fit into the `N` bytes of the [`ArrayString`]. Use
+    /// [`read`](Self::read) with an [`ArrayWString`] if you need the raw
+    /// 16-bit characters.
+    pub fn read_utf16_string<const N: usize>(
+        &self,
+        address: impl Into<Address>,
+    ) -> Result<ArrayString<N>, Error> {
+        Ok(self.read::<ArrayWString<N>>(address)?.to_array_string())
+    }
+
+    /// Reads a nul-terminated UTF-16 string of up to `max_len` 16-bit
+    /// characters from the process at the address given and converts it to
+    /// UTF-8. The string is read in chunks, so the memory after the
+    /// nul-terminator doesn't need to be readable, unless it is within the
+    /// same chunk. Check [`read_utf16_string`](Self::read_utf16_string) for
+    /// more information.
+    #[cfg(feature = "alloc")]
+    pub fn read_utf16_string_alloc(
+        &self,
+        address: impl Into<Address>,
+        max_len: usize,
+    ) -> Result<alloc::string::String, Error> {
+        const CHUNK_LEN: usize = 128;
+
+        let mut address = address.into();
+        let mut chars = alloc::vec::Vec::new();
+        let mut buf = [0u16; CHUNK_LEN];
+        while chars.len() < max_len {
+            let chunk = &mut buf[..CHUNK_LEN.min(max_len - chars.len())];
+            self.read_into_slice(address, chunk)?;
+            match chunk.iter().position(|&c| c == 0) {
+                Some(len) => {
+                    chars.extend_from_slice(&chunk[..len]);
+                    break;
+                }
+                None => chars.extend_from_slice(chunk),
+            }
+            address = address + mem::size_of_val(chunk) as u64;
+        }
+        Ok(char::decode_utf16(chars)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect())
+    }
+
     /// Reads a pointer address from the process at the address given.
     pub fn read_pointer(
         &self,
@@ -427,6 +730,28 @@ impl Process {
         })
     }
 
+    /// Reads a 32-bit displacement that is relative to the end of an
+    /// instruction, such as the one of `mov rax, [rip+disp32]` on x86-64, and
+    /// returns the address it points to. The displacement is expected to be
+    /// the last 4 bytes of the instruction. It is sign-extended, so it may
+    /// point backwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{Address, Process};
+    /// # fn example(process: &Process, instruction: Address) -> Result<(), asr::Error> {
+    /// // 48 8B 05 ?? ?? ?? ?? is 7 bytes long.
+    /// let target = process.read_rel32(instruction + 7)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_rel32(&self, instruction_end: impl Into<Address>) -> Result<Address, Error> {
+        let instruction_end = instruction_end.into();
+        let displacement = self.read::<i32>(instruction_end.add_signed(-4))?;
+        Ok(instruction_end.add_signed(displacement.into()))
+    }
+
     /// Follows a path of pointers from the address given and reads a value of
     /// the type specified from the process at the end of the pointer path.
     pub fn read_pointer_path<T: CheckedBitPattern>(
@@ -1,12 +1,21 @@
 //! This module allows you to add settings widgets to the settings GUI that the
 //! user can modify.
+//!
+//! The widgets can either be described statically through the [`Gui`] trait or
+//! be added one by one with functions such as [`add_bool`] and [`add_title`].
+//! The latter can be called at any point, so widgets that depend on
+//! information read from the game, such as the names of its levels, can be
+//! added once that information is available. Both approaches store the values
+//! of the settings in the same global settings [`Map`](super::Map) under the
+//! keys that are specified. The runtime currently provides no way to remove a
+//! widget again once it has been added.
 
 use core::mem;
 
 #[cfg(feature = "derive")]
 pub use asr_derive::Gui;
 
-use crate::{runtime::sys, watcher::Pair};
+use crate::{runtime::sys, string::ArrayString, watcher::Pair};
 
 use super::map::Map;
 
@@ -195,6 +204,75 @@ pub trait Gui {
     /// Updates the settings with the values that the user has set from the
     /// settings map provided.
     fn update_from(&mut self, settings_map: &Map);
+
+    /// Updates the settings with the values that the user has set from the
+    /// currently set global settings map. Returns [`true`] if any of the
+    /// settings changed. This allows skipping work that depends on the
+    /// settings on ticks where nothing changed. Values are compared, so
+    /// storing an unchanged settings map does not count as a change.
+    fn update_returns_changed(&mut self) -> bool {
+        self.update_from_returns_changed(&Map::load())
+    }
+
+    /// Updates the settings with the values that the user has set from the
+    /// settings map provided. Returns [`true`] if any of the settings changed.
+    /// If this is not overridden, it always returns [`true`].
+    fn update_from_returns_changed(&mut self, settings_map: &Map) -> bool {
+        self.update_from(settings_map);
+        true
+    }
+}
+
+/// A settings [`Gui`] that can be nested inside of another settings [`Gui`].
+/// This is automatically implemented by the derive macro [`Gui`](macro@Gui)
+/// for structs. This is an internal trait that you don't need to worry about.
+#[doc(hidden)]
+pub trait NestedGui: Sized {
+    /// Registers the settings with all their keys prefixed by the given
+    /// prefix.
+    fn register_nested(prefix: &str, keys: &mut KeyRegistry) -> Self;
+
+    /// Updates the settings with all their keys prefixed by the given prefix.
+    /// Returns [`true`] if any of the settings changed.
+    fn update_nested(&mut self, settings_map: &Map, prefix: &str) -> bool;
+}
+
+const KEY_CAP: usize = 256;
+
+/// Prefixes a key of a setting. This is an internal function that you don't
+/// need to worry about.
+#[doc(hidden)]
+#[track_caller]
+pub fn prefixed_key(prefix: &str, key: &str) -> ArrayString<KEY_CAP> {
+    let mut prefixed = ArrayString::new();
+    if prefixed.try_push_str(prefix).is_err() || prefixed.try_push_str(key).is_err() {
+        panic!("The settings key `{prefix}{key}` is longer than {KEY_CAP} bytes.");
+    }
+    prefixed
+}
+
+/// Keeps track of the keys that are registered by a settings [`Gui`] so that
+/// duplicate keys caused by nesting settings can be detected. The keys are only
+/// tracked if the `alloc` feature is enabled. This is an internal type that you
+/// don't need to worry about.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct KeyRegistry {
+    #[cfg(feature = "alloc")]
+    keys: alloc::collections::BTreeSet<alloc::string::String>,
+}
+
+impl KeyRegistry {
+    /// Registers a key. Panics if the key has already been registered.
+    #[track_caller]
+    pub fn insert(&mut self, key: &str) {
+        #[cfg(feature = "alloc")]
+        if !self.keys.insert(key.into()) {
+            panic!("The settings key `{key}` is used by more than one setting.");
+        }
+        #[cfg(not(feature = "alloc"))]
+        let _ = key;
+    }
 }
 
 /// A settings widget that can be used as a field when defining a settings [`Gui`].
@@ -208,6 +286,18 @@ pub trait Widget {
     /// Updates the value of the setting based on the value that the user has
     /// set in the provided settings map.
     fn update_from(&mut self, settings_map: &Map, key: &str, args: Self::Args);
+    /// Updates the value of the setting like [`update_from`](Self::update_from)
+    /// and returns [`true`] if the value changed. If this is not overridden, it
+    /// always returns [`true`].
+    fn update_from_returns_changed(
+        &mut self,
+        settings_map: &Map,
+        key: &str,
+        args: Self::Args,
+    ) -> bool {
+        self.update_from(settings_map, key, args);
+        true
+    }
 }
 
 /// The arguments that are needed to register a boolean setting. This is an
@@ -235,6 +325,18 @@ impl Widget for bool {
             .and_then(|value| value.get_bool())
             .unwrap_or(args.default);
     }
+
+    #[inline]
+    fn update_from_returns_changed(
+        &mut self,
+        settings_map: &Map,
+        key: &str,
+        args: Self::Args,
+    ) -> bool {
+        let old = *self;
+        self.update_from(settings_map, key, args);
+        *self != old
+    }
 }
 
 /// A title that can be used to group settings together.
@@ -262,6 +364,16 @@ impl Widget for Title {
 
     #[inline]
     fn update_from(&mut self, _settings_map: &Map, _key: &str, _args: Self::Args) {}
+
+    #[inline]
+    fn update_from_returns_changed(
+        &mut self,
+        _settings_map: &Map,
+        _key: &str,
+        _args: Self::Args,
+    ) -> bool {
+        false
+    }
 }
 
 impl<T: Clone + Widget> Widget for Pair<T> {
@@ -279,6 +391,19 @@ impl<T: Clone + Widget> Widget for Pair<T> {
         mem::swap(&mut self.old, &mut self.current);
         self.current.update_from(settings_map, key, args);
     }
+
+    fn update_from_returns_changed(
+        &mut self,
+        settings_map: &Map,
+        key: &str,
+        args: Self::Args,
+    ) -> bool {
+        // The current value needs to start out as the old value, so the
+        // comparison is against the value of the previous update.
+        self.old.clone_from(&self.current);
+        self.current
+            .update_from_returns_changed(settings_map, key, args)
+    }
 }
 
 /// A file select widget.
@@ -294,6 +419,8 @@ impl<T: Clone + Widget> Widget for Pair<T> {
 ///     ("Rust files", "*.rs Cargo.*"),
 ///     // The name is optional
 ///     (_, "*.md"),
+///     // A pattern without a name can also be written on its own
+///     "*.json",
 ///     // MIME types
 ///     "text/plain",
 ///     // MIME types with wildcards
@@ -302,6 +429,11 @@ impl<T: Clone + Widget> Widget for Pair<T> {
 /// text_file: FileSelect,
 /// # }
 /// ```
+///
+/// The path is stored in the settings [`Map`] as a string. It's provided as is,
+/// even if the file no longer exists at that path, so make sure to handle
+/// errors when opening the file. If the user hasn't selected a file yet, the
+/// path is empty.
 #[derive(Clone, PartialEq, Eq)]
 #[cfg(feature = "alloc")]
 pub struct FileSelect {
@@ -356,4 +488,15 @@ impl Widget for FileSelect {
             self.path.clear();
         }
     }
+
+    fn update_from_returns_changed(
+        &mut self,
+        settings_map: &Map,
+        key: &str,
+        args: Self::Args,
+    ) -> bool {
+        let old = mem::take(&mut self.path);
+        self.update_from(settings_map, key, args);
+        self.path != old
+    }
 }
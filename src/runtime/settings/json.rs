@@ -0,0 +1,416 @@
+use core::fmt::{self, Write};
+
+use alloc::string::String;
+
+use super::{List, Map, Value, ValueType};
+
+/// The maximum nesting depth of maps and lists that is supported when parsing
+/// JSON. This prevents deeply nested input from overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+/// An error that occurred while parsing JSON into a settings [`Map`] or
+/// [`Value`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    /// The byte offset into the JSON string at which the error occurred.
+    pub offset: usize,
+    /// A description of the error.
+    pub message: &'static str,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte offset {}", self.message, self.offset)
+    }
+}
+
+impl Map {
+    /// Serializes the settings map as a JSON object. This is useful for
+    /// debugging and for sharing settings. Maps and lists are serialized as
+    /// JSON objects and arrays. Integers are serialized verbatim, so they don't
+    /// lose any precision. Floating point numbers that are not finite, as well
+    /// as values of unknown types, are serialized as `null`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write_map(&mut json, self);
+        json
+    }
+
+    /// Parses a settings map from a JSON object. Values that are `null` are
+    /// skipped. Numbers without a fractional part or exponent are parsed as
+    /// integers if they fit into an [`i64`].
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser::new(json);
+        parser.skip_whitespace();
+        if parser.peek() != Some(b'{') {
+            return Err(parser.error("Expected an object"));
+        }
+        let map = parser.parse_map(0)?;
+        parser.finish()?;
+        Ok(map)
+    }
+}
+
+impl Value {
+    /// Serializes the value as JSON. Check [`Map::to_json`] for more
+    /// information.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        write_value(&mut json, self);
+        json
+    }
+
+    /// Parses a value from JSON. Returns an error if the JSON is `null`, as
+    /// there is no value to represent it. Check [`Map::from_json`] for more
+    /// information.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser::new(json);
+        let value = parser.parse_value(0)?.ok_or(JsonError {
+            offset: 0,
+            message: "Expected a value other than null",
+        })?;
+        parser.finish()?;
+        Ok(value)
+    }
+}
+
+fn write_map(json: &mut String, map: &Map) {
+    json.push('{');
+    for (index, (key, value)) in map.iter().enumerate() {
+        if index != 0 {
+            json.push(',');
+        }
+        write_string(json, &key);
+        json.push(':');
+        write_value(json, &value);
+    }
+    json.push('}');
+}
+
+fn write_list(json: &mut String, list: &List) {
+    json.push('[');
+    for (index, value) in list.iter().enumerate() {
+        if index != 0 {
+            json.push(',');
+        }
+        write_value(json, &value);
+    }
+    json.push(']');
+}
+
+fn write_value(json: &mut String, value: &Value) {
+    match value.get_type() {
+        ValueType::Map => {
+            if let Some(map) = value.get_map() {
+                return write_map(json, &map);
+            }
+        }
+        ValueType::List => {
+            if let Some(list) = value.get_list() {
+                return write_list(json, &list);
+            }
+        }
+        ValueType::Bool => {
+            if let Some(value) = value.get_bool() {
+                json.push_str(if value { "true" } else { "false" });
+                return;
+            }
+        }
+        ValueType::I64 => {
+            if let Some(value) = value.get_i64() {
+                let _ = write!(json, "{value}");
+                return;
+            }
+        }
+        ValueType::F64 => {
+            if let Some(value) = value.get_f64().filter(|v| v.is_finite()) {
+                // The debug representation always contains a fractional part
+                // or an exponent, so the number is parsed as a float again.
+                let _ = write!(json, "{value:?}");
+                return;
+            }
+        }
+        ValueType::String => {
+            if let Some(value) = value.get_string() {
+                return write_string(json, &value);
+            }
+        }
+        ValueType::Unknown => {}
+    }
+    json.push_str("null");
+}
+
+fn write_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            c if c < ' ' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+struct Parser<'a> {
+    json: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    const fn new(json: &'a str) -> Self {
+        Self { json, pos: 0 }
+    }
+
+    const fn error(&self, message: &'static str) -> JsonError {
+        JsonError {
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.json.as_bytes().get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8, message: &'static str) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.peek() != Some(byte) {
+            return Err(self.error(message));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn expect_literal(&mut self, literal: &str) -> Result<(), JsonError> {
+        if !self.json[self.pos..].starts_with(literal) {
+            return Err(self.error("Invalid literal"));
+        }
+        self.pos += literal.len();
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), JsonError> {
+        self.skip_whitespace();
+        if self.pos != self.json.len() {
+            return Err(self.error("Trailing characters"));
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Option<Value>, JsonError> {
+        self.skip_whitespace();
+        Ok(Some(match self.peek() {
+            Some(b'{') => Value::from(self.parse_map(depth)?),
+            Some(b'[') => Value::from(self.parse_list(depth)?),
+            Some(b'"') => Value::from(self.parse_string()?.as_str()),
+            Some(b't') => {
+                self.expect_literal("true")?;
+                Value::from(true)
+            }
+            Some(b'f') => {
+                self.expect_literal("false")?;
+                Value::from(false)
+            }
+            Some(b'n') => {
+                self.expect_literal("null")?;
+                return Ok(None);
+            }
+            Some(b'-' | b'0'..=b'9') => self.parse_number()?,
+            Some(_) => return Err(self.error("Expected a value")),
+            None => return Err(self.error("Unexpected end of input")),
+        }))
+    }
+
+    fn parse_map(&mut self, depth: usize) -> Result<Map, JsonError> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error("Nesting is too deep"));
+        }
+        self.expect(b'{', "Expected an object")?;
+        let map = Map::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(map);
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expected a key"));
+            }
+            let key = self.parse_string()?;
+            self.expect(b':', "Expected a colon")?;
+            if let Some(value) = self.parse_value(depth + 1)? {
+                map.insert(&key, &value);
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(map);
+                }
+                _ => return Err(self.error("Expected a comma or the end of the object")),
+            }
+        }
+    }
+
+    fn parse_list(&mut self, depth: usize) -> Result<List, JsonError> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error("Nesting is too deep"));
+        }
+        self.expect(b'[', "Expected an array")?;
+        let list = List::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(list);
+        }
+        loop {
+            if let Some(value) = self.parse_value(depth + 1)? {
+                list.push(&value);
+            }
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(list);
+                }
+                _ => return Err(self.error("Expected a comma or the end of the array")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JsonError> {
+        self.expect(b'"', "Expected a string")?;
+        let mut string = String::new();
+        loop {
+            let start = self.pos;
+            while let Some(byte) = self.peek() {
+                if byte == b'"' || byte == b'\\' || byte < b' ' {
+                    break;
+                }
+                self.pos += 1;
+            }
+            // We only stop at ASCII characters, so this is always on a
+            // character boundary.
+            string.push_str(&self.json[start..self.pos]);
+
+            match self.next() {
+                Some(b'"') => return Ok(string),
+                Some(b'\\') => {
+                    let c = match self.next() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.parse_unicode_escape()?,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("Invalid escape sequence"));
+                        }
+                    };
+                    string.push(c);
+                }
+                Some(_) => {
+                    self.pos -= 1;
+                    return Err(self.error("Control characters need to be escaped"));
+                }
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u16, JsonError> {
+        let digits = self
+            .json
+            .get(self.pos..self.pos + 4)
+            .ok_or(self.error("Invalid unicode escape sequence"))?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(self.error("Invalid unicode escape sequence"));
+        }
+        let value =
+            u16::from_str_radix(digits, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(value)
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.parse_hex4()?;
+        let code_point = if (0xD800..0xDC00).contains(&high) {
+            // A high surrogate needs to be followed by an escaped low
+            // surrogate.
+            self.expect_literal("\\u")?;
+            let low = self.parse_hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("Invalid surrogate pair"));
+            }
+            0x10000 + (((high as u32) - 0xD800) << 10) + ((low as u32) - 0xDC00)
+        } else {
+            high as u32
+        };
+        char::from_u32(code_point).ok_or(self.error("Invalid unicode escape sequence"))
+    }
+
+    fn parse_number(&mut self) -> Result<Value, JsonError> {
+        let start = self.pos;
+        let mut is_integer = true;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'0'..=b'9' | b'-' => {}
+                b'.' | b'e' | b'E' | b'+' => is_integer = false,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let number = &self.json[start..self.pos];
+        let error = JsonError {
+            offset: start,
+            message: "Invalid number",
+        };
+
+        // JSON doesn't allow leading zeros or a leading plus sign, which Rust
+        // accepts, so these need to be rejected manually.
+        let digits = number.strip_prefix('-').unwrap_or(number);
+        if !digits.starts_with(|c: char| c.is_ascii_digit())
+            || (digits.starts_with('0') && digits[1..].starts_with(|c: char| c.is_ascii_digit()))
+            || digits.contains(".e")
+            || digits.contains(".E")
+            || digits.ends_with('.')
+        {
+            return Err(error);
+        }
+
+        if is_integer {
+            if let Ok(value) = number.parse::<i64>() {
+                return Ok(Value::from(value));
+            }
+        }
+        number.parse::<f64>().map(Value::from).map_err(|_| error)
+    }
+}
@@ -0,0 +1,292 @@
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+use super::{List, Map, Value};
+
+/// The maximum nesting depth of elements that is supported. This prevents
+/// deeply nested input from overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Parses the XML based settings of the classic LiveSplit auto splitting
+/// components into a settings [`Map`]. The children of the root element, which
+/// usually is the `<Settings>` element, become the entries of the map. Their
+/// `id` attributes are used as the keys, such as for the
+/// `<Setting id="...">` elements of ASL scripts. Elements without an `id`
+/// attribute use their element names as the keys instead. The values are
+/// converted as follows:
+///
+/// - Elements that only contain the text `True` or `False` become booleans.
+/// - Elements that only contain any other text become strings.
+/// - Elements that contain other elements become nested maps.
+/// - Elements with the same key in the same parent become a list of all their
+///   values.
+///
+/// Other attributes, comments, processing instructions and the XML declaration
+/// are ignored. CDATA sections are treated as text. Returns [`None`] if the XML
+/// is malformed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::settings;
+/// let xml = r#"<Settings>
+///     <UseGameTime>True</UseGameTime>
+///     <CustomSettings>
+///         <Setting id="any_percent" type="bool">False</Setting>
+///     </CustomSettings>
+/// </Settings>"#;
+/// let map = settings::parse_legacy_xml(xml).unwrap();
+/// assert_eq!(map.get_bool("UseGameTime"), Some(true));
+/// let custom = map.get("CustomSettings").unwrap().get_map().unwrap();
+/// assert_eq!(custom.get_bool("any_percent"), Some(false));
+/// ```
+pub fn parse_legacy_xml(xml: &str) -> Option<Map> {
+    let mut parser = Parser { xml, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.parse_element(0)?;
+    parser.skip_misc()?;
+    if parser.pos != xml.len() {
+        return None;
+    }
+    Some(children_to_map(root.children))
+}
+
+/// Merges the XML based settings of the classic LiveSplit auto splitting
+/// components into the global settings map. The XML is converted with
+/// [`parse_legacy_xml`]. Keys that already exist in the global settings map
+/// are not overwritten, so this can be used as a one-time migration that
+/// doesn't revert any changes the user has made since. Returns [`false`] if
+/// the XML is malformed or the global settings map couldn't be stored.
+pub fn migrate_legacy_xml(xml: &str) -> bool {
+    let Some(legacy) = parse_legacy_xml(xml) else {
+        return false;
+    };
+    Map::update_with(|map| {
+        for (key, value) in legacy.iter() {
+            map.insert_if_absent(&key, &value);
+        }
+    })
+}
+
+struct Element<'a> {
+    key: Cow<'a, str>,
+    value: Value,
+}
+
+/// An element as it was parsed, before its value is converted.
+struct ParsedElement<'a> {
+    name: &'a str,
+    /// The value of the `id` attribute.
+    id: Option<Cow<'a, str>>,
+    children: Vec<Element<'a>>,
+    text: String,
+}
+
+fn children_to_map(children: Vec<Element<'_>>) -> Map {
+    let map = Map::new();
+    for (index, child) in children.iter().enumerate() {
+        let has_same_key = |c: &Element<'_>| c.key == child.key;
+        if children[..index].iter().any(has_same_key) {
+            // The element was already added as part of a list.
+            continue;
+        }
+        if children[index + 1..].iter().any(has_same_key) {
+            let list = List::new();
+            for element in children[index..].iter().filter(|c| has_same_key(c)) {
+                list.push(&element.value);
+            }
+            map.insert(&child.key, list);
+        } else {
+            map.insert(&child.key, &child.value);
+        }
+    }
+    map
+}
+
+struct Parser<'a> {
+    xml: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.xml[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips everything up to and including the terminator.
+    fn skip_past(&mut self, terminator: &str) -> Option<&'a str> {
+        let rest = self.rest();
+        let end = rest.find(terminator)?;
+        self.pos += end + terminator.len();
+        Some(&rest[..end])
+    }
+
+    /// Skips whitespace, comments, processing instructions and doctypes.
+    fn skip_misc(&mut self) -> Option<()> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Some(());
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '='))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// Parses an element along with its `id` attribute, its child elements
+    /// and its text.
+    fn parse_element(&mut self, depth: usize) -> Option<ParsedElement<'a>> {
+        if depth >= MAX_DEPTH || !self.rest().starts_with('<') {
+            return None;
+        }
+        self.pos += 1;
+        let name = self.parse_name()?;
+
+        let mut id = None;
+        let is_empty = loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                break true;
+            } else if rest.starts_with('>') {
+                self.pos += 1;
+                break false;
+            }
+
+            let attribute = self.parse_name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') {
+                return None;
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|&c| c == '"' || c == '\'')?;
+            self.pos += 1;
+            let raw = self.skip_past(if quote == '"' { "\"" } else { "'" })?;
+            if attribute == "id" {
+                id = Some(unescape(raw)?);
+            }
+        };
+
+        let mut children = Vec::new();
+        let mut text = String::new();
+        if is_empty {
+            return Some(ParsedElement {
+                name,
+                id,
+                children,
+                text,
+            });
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                if self.parse_name()? != name {
+                    return None;
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return None;
+                }
+                self.pos += 1;
+                return Some(ParsedElement {
+                    name,
+                    id,
+                    children,
+                    text,
+                });
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                text.push_str(self.skip_past("]]>")?);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                let child = self.parse_element(depth + 1)?;
+                let value = if child.children.is_empty() {
+                    match child.text.trim() {
+                        "True" => Value::from(true),
+                        "False" => Value::from(false),
+                        _ => Value::from(child.text.as_str()),
+                    }
+                } else {
+                    Value::from(children_to_map(child.children))
+                };
+                children.push(Element {
+                    key: child.id.unwrap_or(Cow::Borrowed(child.name)),
+                    value,
+                });
+            } else {
+                let len = rest.find('<')?;
+                unescape_into(&rest[..len], &mut text)?;
+                self.pos += len;
+            }
+        }
+    }
+}
+
+fn unescape(raw: &str) -> Option<Cow<'_, str>> {
+    if !raw.contains('&') {
+        return Some(Cow::Borrowed(raw));
+    }
+    let mut text = String::new();
+    unescape_into(raw, &mut text)?;
+    Some(Cow::Owned(text))
+}
+
+fn unescape_into(mut raw: &str, text: &mut String) -> Option<()> {
+    while let Some(start) = raw.find('&') {
+        text.push_str(&raw[..start]);
+        raw = &raw[start + 1..];
+        let end = raw.find(';')?;
+        let entity = &raw[..end];
+        raw = &raw[end + 1..];
+        let c = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code_point = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else {
+                    entity.strip_prefix('#')?.parse().ok()?
+                };
+                char::from_u32(code_point)?
+            }
+        };
+        text.push(c);
+    }
+    text.push_str(raw);
+    Some(())
+}
@@ -2,10 +2,27 @@ use core::{borrow::Borrow, fmt};
 
 use crate::{runtime::sys, Error};
 
-use super::{AsValue, Value};
+use super::{AsValue, FromValue, Value};
 
 /// A list of [`Value`]s that can itself be a [`Value`] and thus be stored in a
 /// [`Map`](super::Map).
+///
+/// A list that is retrieved from a [`Value`] or a [`Map`](super::Map) is a
+/// copy. Modifying it, such as pushing values to it, does not modify the list
+/// that is stored in the value or the map. The list needs to be inserted into
+/// the map again for the changes to be reflected there.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::settings::{List, Map};
+/// let map = Map::load();
+/// let list = map.get_list("splits").unwrap_or_default();
+/// list.push("new split");
+/// // The map still contains the old list until the new one is inserted.
+/// map.insert("splits", &list);
+/// map.store();
+/// ```
 #[repr(transparent)]
 pub struct List(pub(super) sys::SettingsList);
 
@@ -15,6 +32,16 @@ impl fmt::Debug for List {
     }
 }
 
+/// Lists are equal if they have the same length and all of their values are
+/// equal. Check the [`PartialEq`] implementation of [`Value`] for more
+/// information.
+#[cfg(feature = "alloc")]
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
 impl Drop for List {
     #[inline]
     fn drop(&mut self) {
@@ -24,6 +51,19 @@ impl Drop for List {
     }
 }
 
+/// Cloning a list creates an independent copy, so modifying either of them
+/// doesn't affect the other.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::settings::List;
+/// let list = List::from_iter([1i64, 2]);
+/// let copy = list.clone();
+/// copy.push(3i64);
+/// assert_eq!(list.len(), 2);
+/// assert_eq!(copy.len(), 3);
+/// ```
 impl Clone for List {
     #[inline]
     fn clone(&self) -> Self {
@@ -70,6 +110,25 @@ impl List {
     }
 
     /// Pushes a copy of the value to the end of the list.
+    ///
+    /// # Example
+    ///
+    /// Pushing to a list that was retrieved from a map doesn't modify the list
+    /// that is stored in the map:
+    ///
+    /// ```no_run
+    /// # use asr::settings::{List, Map};
+    /// let map = Map::new();
+    /// map.insert("splits", &List::from_iter(["a", "b"]));
+    ///
+    /// let list = map.get_list("splits").unwrap();
+    /// list.push("c");
+    /// assert_eq!(list.len(), 3);
+    /// assert_eq!(map.get_list("splits").unwrap().len(), 2);
+    ///
+    /// map.insert("splits", &list);
+    /// assert_eq!(map.get_list("splits").unwrap().len(), 3);
+    /// ```
     #[inline]
     pub fn push(&self, value: impl AsValue) {
         // SAFETY: The settings list handle is valid and the value handle is
@@ -77,6 +136,30 @@ impl List {
         unsafe { sys::settings_list_push(self.0, value.as_value().borrow().0) }
     }
 
+    /// Pushes a copy of each of the values to the end of the list.
+    #[inline]
+    pub fn extend<T: AsValue>(&self, values: impl IntoIterator<Item = T>) {
+        for value in values {
+            self.push(value);
+        }
+    }
+
+    /// Returns the value at the given index as the type specified. Returns
+    /// [`None`] if the index is out of bounds or the value has a different
+    /// type.
+    #[inline]
+    pub fn get_typed<T: FromValue>(&self, index: u64) -> Option<T> {
+        T::from_value(&self.get(index)?)
+    }
+
+    /// Collects all the values of the list as the type specified. Returns
+    /// [`None`] if any of the values has a different type.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_vec<T: FromValue>(&self) -> Option<alloc::vec::Vec<T>> {
+        self.iter().map(|value| T::from_value(&value)).collect()
+    }
+
     /// Inserts a copy of the value at the given index, pushing all values at
     /// and after the index one position further. Returns an error if the index
     /// is out of bounds. You may specify an index that is equal to the length
@@ -107,3 +190,20 @@ impl List {
         (0..self.len()).flat_map(|i| self.get(i))
     }
 }
+
+impl<T: AsValue> FromIterator<T> for List {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: AsValue> From<alloc::vec::Vec<T>> for List {
+    #[inline]
+    fn from(values: alloc::vec::Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
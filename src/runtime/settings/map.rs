@@ -1,10 +1,22 @@
 use core::{borrow::Borrow, fmt};
+#[cfg(feature = "alloc")]
+use core::{
+    hash::{Hash, Hasher},
+    sync::atomic::{self, AtomicBool, AtomicU64},
+};
 
 use arrayvec::ArrayString;
 
 use crate::{runtime::sys, Error};
 
-use super::{AsValue, Value};
+use super::{AsValue, List, Value, ValueType};
+
+/// The maximum length of a string that gets parsed as a number or a boolean
+/// when coercing a value.
+const COERCION_CAP: usize = 64;
+
+/// The number of times [`Map::update_with`] tries to store the modified map.
+const MAX_UPDATE_ATTEMPTS: usize = 16;
 
 /// A map consisting of settings that are configured. Every setting has a string
 /// based key and a [`Value`]. There is a global settings map that represents
@@ -28,6 +40,19 @@ impl fmt::Debug for Map {
     }
 }
 
+/// Maps are equal if they contain the same keys and the values of all the keys
+/// are equal. The order of the keys doesn't matter. Check the [`PartialEq`]
+/// implementation of [`Value`] for more information.
+#[cfg(feature = "alloc")]
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(&key).is_some_and(|other| other == value))
+    }
+}
+
 impl Drop for Map {
     #[inline]
     fn drop(&mut self) {
@@ -89,6 +114,61 @@ impl Map {
         unsafe { sys::settings_map_store_if_unchanged(old.0, self.0) }
     }
 
+    /// Modifies the global settings map without losing any changes that are
+    /// made to it in the meantime, such as the user changing a setting. The
+    /// global settings map is loaded, the closure modifies a copy of it, and
+    /// the copy gets stored with [`store_if_unchanged`](Self::store_if_unchanged).
+    /// If the global settings map was changed in the meantime, this is retried
+    /// with the new global settings map, so the closure may be called multiple
+    /// times. After 16 failed attempts this gives up and returns [`false`]
+    /// without storing the modified map. Returns [`true`] if the modified map
+    /// was stored successfully.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::settings::Map;
+    /// Map::update_with(|map| {
+    ///     map.insert_if_absent("version", 2i64);
+    /// });
+    /// ```
+    pub fn update_with(mut f: impl FnMut(&Map)) -> bool {
+        for _ in 0..MAX_UPDATE_ATTEMPTS {
+            let old = Map::load();
+            let new = old.clone();
+            f(&new);
+            if new.store_if_unchanged(&old) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Checks whether the map has the same contents as a map that was loaded
+    /// earlier. This can be used to skip work on ticks where the user didn't
+    /// change any settings. The maps are compared deeply, so this is not free
+    /// either, but it's cheaper than updating everything that depends on the
+    /// settings.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::settings::Map;
+    /// let mut previous = Map::load();
+    /// loop {
+    ///     let current = Map::load();
+    ///     if !current.is_unchanged_since(&previous) {
+    ///         // Update everything that depends on the settings.
+    ///         previous = current;
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn is_unchanged_since(&self, other: &Map) -> bool {
+        self == other
+    }
+
     /// Inserts a copy of the setting value into the settings map based on the
     /// key. If the key already exists, the existing value will be overwritten.
     #[inline]
@@ -111,6 +191,120 @@ impl Map {
         unsafe { sys::settings_map_get(self.0, key.as_ptr(), key.len()).map(Value) }
     }
 
+    /// Inserts a copy of the setting value into the settings map based on the
+    /// key, but only if the key does not exist yet. This is useful for seeding
+    /// default values without overwriting the values that the user has set.
+    /// Returns [`true`] if the value was inserted.
+    #[inline]
+    pub fn insert_if_absent(&self, key: &str, value: impl AsValue) -> bool {
+        if self.get(key).is_some() {
+            return false;
+        }
+        self.insert(key, value);
+        true
+    }
+
+    /// Gets the setting value of the given key as a [`bool`]. Returns [`None`]
+    /// if the key does not exist or the value can't be coerced into a boolean.
+    /// The following values are coerced:
+    ///
+    /// - The integers `0` and `1`, as well as the floating point numbers `0.0`
+    ///   and `1.0`, are treated as [`false`] and [`true`].
+    /// - The strings `"false"` and `"true"`, as well as `"0"` and `"1"`, are
+    ///   treated as [`false`] and [`true`].
+    ///
+    /// Use [`get_bool_strict`](Self::get_bool_strict) if no coercion should
+    /// happen.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        coerce_bool(self.get(key).map(|value| Decoded::new(&value)))
+    }
+
+    /// Gets the setting value of the given key as an [`i64`]. Returns [`None`]
+    /// if the key does not exist or the value can't be coerced into an integer.
+    /// The following values are coerced:
+    ///
+    /// - Floating point numbers without a fractional part that are in the range
+    ///   of an [`i64`].
+    /// - Booleans are treated as `0` and `1`.
+    /// - Strings that contain an integer, optionally surrounded by whitespace.
+    ///
+    /// Use [`get_i64_strict`](Self::get_i64_strict) if no coercion should
+    /// happen.
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        coerce_i64(self.get(key).map(|value| Decoded::new(&value)))
+    }
+
+    /// Gets the setting value of the given key as an [`f64`]. Returns [`None`]
+    /// if the key does not exist or the value can't be coerced into a floating
+    /// point number. The following values are coerced:
+    ///
+    /// - Integers are converted to the nearest floating point number.
+    /// - Strings that contain a number, optionally surrounded by whitespace.
+    ///
+    /// Use [`get_f64_strict`](Self::get_f64_strict) if no coercion should
+    /// happen.
+    pub fn get_f64(&self, key: &str) -> Option<f64> {
+        coerce_f64(self.get(key).map(|value| Decoded::new(&value)))
+    }
+
+    /// Gets the setting value of the given key as a
+    /// [`String`](alloc::string::String). Returns [`None`] if the key does not
+    /// exist or the value can't be coerced into a string. Booleans and numbers
+    /// are coerced into their textual representation.
+    ///
+    /// Use [`get_string_strict`](Self::get_string_strict) if no coercion
+    /// should happen.
+    #[cfg(feature = "alloc")]
+    pub fn get_string(&self, key: &str) -> Option<alloc::string::String> {
+        use alloc::string::ToString;
+
+        let value = self.get(key)?;
+        match value.get_type() {
+            ValueType::String => value.get_string(),
+            ValueType::Bool => Some(value.get_bool()?.to_string()),
+            ValueType::I64 => Some(value.get_i64()?.to_string()),
+            ValueType::F64 => Some(value.get_f64()?.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Gets the setting value of the given key as a [`List`]. Returns [`None`]
+    /// if the key does not exist or the value is not a list.
+    #[inline]
+    pub fn get_list(&self, key: &str) -> Option<List> {
+        self.get(key)?.get_list()
+    }
+
+    /// Gets the setting value of the given key as a [`bool`]. Returns [`None`]
+    /// if the key does not exist or the value is not a boolean.
+    #[inline]
+    pub fn get_bool_strict(&self, key: &str) -> Option<bool> {
+        self.get(key)?.get_bool()
+    }
+
+    /// Gets the setting value of the given key as an [`i64`]. Returns [`None`]
+    /// if the key does not exist or the value is not an integer.
+    #[inline]
+    pub fn get_i64_strict(&self, key: &str) -> Option<i64> {
+        self.get(key)?.get_i64()
+    }
+
+    /// Gets the setting value of the given key as an [`f64`]. Returns [`None`]
+    /// if the key does not exist or the value is not a floating point number.
+    #[inline]
+    pub fn get_f64_strict(&self, key: &str) -> Option<f64> {
+        self.get(key)?.get_f64()
+    }
+
+    /// Gets the setting value of the given key as a
+    /// [`String`](alloc::string::String). Returns [`None`] if the key does not
+    /// exist or the value is not a string.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn get_string_strict(&self, key: &str) -> Option<alloc::string::String> {
+        self.get(key)?.get_string()
+    }
+
     /// Returns the number of key value pairs in the map.
     #[inline]
     pub fn len(&self) -> u64 {
@@ -263,3 +457,312 @@ impl Map {
         (0..self.len()).flat_map(|i| self.get_value_by_index(i))
     }
 }
+
+/// The generation of the global settings map that was last observed by
+/// [`map_generation`], along with the hash of its contents.
+#[cfg(feature = "alloc")]
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc")]
+static GENERATION_HASH: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc")]
+static GENERATION_OBSERVED: AtomicBool = AtomicBool::new(false);
+
+/// Returns the generation of the global settings map. The generation starts at
+/// `0` and increments whenever the contents of the global settings map are
+/// found to be different from the last time this function was called. This is
+/// a cheap way to check whether anything depending on the settings needs to be
+/// rebuilt.
+///
+/// The runtime creates a new handle every time the map is loaded, so the
+/// generation is derived from a hash of the contents instead. Replacing the
+/// map with an identical one therefore doesn't increment the generation, while
+/// storing a modified map from within the auto splitter does. Any number of
+/// changes between two calls only increment the generation once. Use
+/// [`Gui::update_returns_changed`](super::Gui::update_returns_changed) to only
+/// react to changes of the settings in the GUI.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::settings;
+/// let mut generation = settings::map_generation();
+/// loop {
+///     let current = settings::map_generation();
+///     if current != generation {
+///         // Rebuild everything that depends on the settings.
+///         generation = current;
+///     }
+/// }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn map_generation() -> u64 {
+    let mut hasher = Fnv1a::default();
+    hash_map(&Map::load(), &mut hasher);
+    let hash = hasher.finish();
+
+    let observed = GENERATION_OBSERVED.swap(true, atomic::Ordering::Relaxed);
+    let previous = GENERATION_HASH.swap(hash, atomic::Ordering::Relaxed);
+    if observed && previous != hash {
+        GENERATION.fetch_add(1, atomic::Ordering::Relaxed) + 1
+    } else {
+        GENERATION.load(atomic::Ordering::Relaxed)
+    }
+}
+
+/// Hashes the entries of a map independently of their order, as the order
+/// doesn't matter when comparing maps.
+#[cfg(feature = "alloc")]
+fn hash_map(map: &Map, state: &mut impl Hasher) {
+    let combined = map.iter().fold(0u64, |combined, (key, value)| {
+        let mut hasher = Fnv1a::default();
+        key.hash(&mut hasher);
+        hash_value(&value, &mut hasher);
+        combined.wrapping_add(hasher.finish())
+    });
+    state.write_u64(map.len());
+    state.write_u64(combined);
+}
+
+#[cfg(feature = "alloc")]
+fn hash_value(value: &Value, state: &mut impl Hasher) {
+    let ty = value.get_type();
+    state.write_u8(ty as u8);
+    match ty {
+        ValueType::Map => {
+            if let Some(map) = value.get_map() {
+                hash_map(&map, state);
+            }
+        }
+        ValueType::List => {
+            if let Some(list) = value.get_list() {
+                state.write_u64(list.len());
+                for value in list.iter() {
+                    hash_value(&value, state);
+                }
+            }
+        }
+        ValueType::Bool => value.get_bool().hash(state),
+        ValueType::I64 => value.get_i64().hash(state),
+        ValueType::F64 => value.get_f64().map(f64::to_bits).hash(state),
+        ValueType::String => value.get_string().hash(state),
+        ValueType::Unknown => {}
+    }
+}
+
+/// The 64-bit FNV-1a hash function. It's simple and has no state beyond the
+/// hash itself, which is all that's needed for detecting changes.
+#[cfg(feature = "alloc")]
+struct Fnv1a(u64);
+
+#[cfg(feature = "alloc")]
+impl Default for Fnv1a {
+    #[inline]
+    fn default() -> Self {
+        Self(0xCBF2_9CE4_8422_2325)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Hasher for Fnv1a {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3);
+        }
+    }
+}
+
+/// A setting value that is read out of its handle, so that the coercions can
+/// be applied to it.
+enum Decoded {
+    Bool(bool),
+    I64(i64),
+    F64(f64),
+    String(ArrayString<COERCION_CAP>),
+    /// A value of any other type, or a string that is too long to be coerced.
+    Other,
+}
+
+impl Decoded {
+    fn new(value: &Value) -> Self {
+        let decoded = match value.get_type() {
+            ValueType::Bool => value.get_bool().map(Self::Bool),
+            ValueType::I64 => value.get_i64().map(Self::I64),
+            ValueType::F64 => value.get_f64().map(Self::F64),
+            ValueType::String => value
+                .get_array_string::<COERCION_CAP>()
+                .and_then(Result::ok)
+                .map(Self::String),
+            _ => None,
+        };
+        decoded.unwrap_or(Self::Other)
+    }
+}
+
+/// Applies the coercions of [`Map::get_bool`]. [`None`] stands for a missing
+/// key.
+fn coerce_bool(value: Option<Decoded>) -> Option<bool> {
+    match value? {
+        Decoded::Bool(value) => Some(value),
+        Decoded::I64(0) => Some(false),
+        Decoded::I64(1) => Some(true),
+        Decoded::F64(0.0) => Some(false),
+        Decoded::F64(1.0) => Some(true),
+        Decoded::String(value) => match value.trim() {
+            "false" | "0" => Some(false),
+            "true" | "1" => Some(true),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Applies the coercions of [`Map::get_i64`]. [`None`] stands for a missing
+/// key.
+fn coerce_i64(value: Option<Decoded>) -> Option<i64> {
+    match value? {
+        Decoded::I64(value) => Some(value),
+        Decoded::F64(value) => {
+            // The cast saturates, so values out of range and values with a
+            // fractional part don't survive the round trip. 2^63 needs to be
+            // excluded explicitly, as i64::MAX rounds up to it.
+            let integer = value as i64;
+            if integer as f64 == value && integer != i64::MAX {
+                Some(integer)
+            } else {
+                None
+            }
+        }
+        Decoded::Bool(value) => Some(value as i64),
+        Decoded::String(value) => value.trim().parse().ok(),
+        Decoded::Other => None,
+    }
+}
+
+/// Applies the coercions of [`Map::get_f64`]. [`None`] stands for a missing
+/// key.
+fn coerce_f64(value: Option<Decoded>) -> Option<f64> {
+    match value? {
+        Decoded::F64(value) => Some(value),
+        Decoded::I64(value) => Some(value as f64),
+        Decoded::String(value) => value.trim().parse().ok(),
+        Decoded::Bool(_) | Decoded::Other => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(value: &str) -> Option<Decoded> {
+        Some(Decoded::String(ArrayString::from(value).unwrap()))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn fnv1a() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = Fnv1a::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        assert_eq!(hash(b""), 0xCBF2_9CE4_8422_2325);
+        assert_eq!(hash(b"a"), 0xAF63_DC4C_8601_EC8C);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_F739_67E8);
+    }
+
+    #[test]
+    fn absent_key() {
+        assert_eq!(coerce_bool(None), None);
+        assert_eq!(coerce_i64(None), None);
+        assert_eq!(coerce_f64(None), None);
+    }
+
+    #[test]
+    fn other_types() {
+        assert_eq!(coerce_bool(Some(Decoded::Other)), None);
+        assert_eq!(coerce_i64(Some(Decoded::Other)), None);
+        assert_eq!(coerce_f64(Some(Decoded::Other)), None);
+    }
+
+    #[test]
+    fn bool_coercions() {
+        assert_eq!(coerce_bool(Some(Decoded::Bool(true))), Some(true));
+        assert_eq!(coerce_bool(Some(Decoded::Bool(false))), Some(false));
+        assert_eq!(coerce_bool(Some(Decoded::I64(0))), Some(false));
+        assert_eq!(coerce_bool(Some(Decoded::I64(1))), Some(true));
+        assert_eq!(coerce_bool(Some(Decoded::I64(2))), None);
+        assert_eq!(coerce_bool(Some(Decoded::I64(-1))), None);
+        assert_eq!(coerce_bool(Some(Decoded::F64(0.0))), Some(false));
+        assert_eq!(coerce_bool(Some(Decoded::F64(-0.0))), Some(false));
+        assert_eq!(coerce_bool(Some(Decoded::F64(1.0))), Some(true));
+        assert_eq!(coerce_bool(Some(Decoded::F64(0.5))), None);
+        assert_eq!(coerce_bool(Some(Decoded::F64(f64::NAN))), None);
+    }
+
+    #[test]
+    fn bool_from_strings() {
+        assert_eq!(coerce_bool(string("true")), Some(true));
+        assert_eq!(coerce_bool(string("1")), Some(true));
+        assert_eq!(coerce_bool(string("false")), Some(false));
+        assert_eq!(coerce_bool(string("0")), Some(false));
+        assert_eq!(coerce_bool(string(" \ttrue\n")), Some(true));
+        assert_eq!(coerce_bool(string("  0 ")), Some(false));
+        assert_eq!(coerce_bool(string("True")), None);
+        assert_eq!(coerce_bool(string("yes")), None);
+        assert_eq!(coerce_bool(string("")), None);
+        assert_eq!(coerce_bool(string("   ")), None);
+    }
+
+    #[test]
+    fn i64_coercions() {
+        assert_eq!(coerce_i64(Some(Decoded::I64(-5))), Some(-5));
+        assert_eq!(coerce_i64(Some(Decoded::Bool(false))), Some(0));
+        assert_eq!(coerce_i64(Some(Decoded::Bool(true))), Some(1));
+        assert_eq!(coerce_i64(string(" -42 ")), Some(-42));
+        assert_eq!(coerce_i64(string("+7")), Some(7));
+        assert_eq!(coerce_i64(string("4.0")), None);
+        assert_eq!(coerce_i64(string("9223372036854775808")), None);
+        assert_eq!(coerce_i64(string("")), None);
+    }
+
+    #[test]
+    fn i64_from_f64_round_trip() {
+        assert_eq!(coerce_i64(Some(Decoded::F64(3.0))), Some(3));
+        assert_eq!(coerce_i64(Some(Decoded::F64(-0.0))), Some(0));
+        assert_eq!(coerce_i64(Some(Decoded::F64(3.5))), None);
+        assert_eq!(coerce_i64(Some(Decoded::F64(f64::NAN))), None);
+        assert_eq!(coerce_i64(Some(Decoded::F64(f64::INFINITY))), None);
+        assert_eq!(coerce_i64(Some(Decoded::F64(f64::NEG_INFINITY))), None);
+
+        // -2^63 is exactly representable, but 2^63 is one past i64::MAX.
+        let two_pow_63 = 9_223_372_036_854_775_808.0;
+        assert_eq!(coerce_i64(Some(Decoded::F64(-two_pow_63))), Some(i64::MIN));
+        assert_eq!(coerce_i64(Some(Decoded::F64(two_pow_63))), None);
+        // The largest double below 2^63 survives the round trip.
+        let below = 9_223_372_036_854_774_784.0;
+        assert_eq!(
+            coerce_i64(Some(Decoded::F64(below))),
+            Some(9_223_372_036_854_774_784)
+        );
+    }
+
+    #[test]
+    fn f64_coercions() {
+        assert_eq!(coerce_f64(Some(Decoded::F64(1.5))), Some(1.5));
+        assert_eq!(coerce_f64(Some(Decoded::I64(-3))), Some(-3.0));
+        assert_eq!(
+            coerce_f64(Some(Decoded::I64(i64::MAX))),
+            Some(9_223_372_036_854_775_808.0)
+        );
+        assert_eq!(coerce_f64(Some(Decoded::Bool(true))), None);
+        assert_eq!(coerce_f64(string(" 2.5\t")), Some(2.5));
+        assert_eq!(coerce_f64(string("1e3")), Some(1000.0));
+        assert_eq!(coerce_f64(string("abc")), None);
+    }
+}
@@ -0,0 +1,198 @@
+use core::ops::Range;
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::{Map, Value};
+
+/// The number of times [`Migrations::run`] tries to store the migrated map.
+const MAX_ATTEMPTS: usize = 16;
+
+type TransformFn = Box<dyn Fn(&Value) -> Option<Value>>;
+
+enum Step {
+    Rename(&'static str, &'static str),
+    Transform(&'static str, TransformFn),
+    Remove(&'static str),
+}
+
+/// Migrates the global settings map from older versions of the auto splitter.
+/// The steps are registered in order and each step increments the version of
+/// the settings. The version is stored in the settings map under the key
+/// [`VERSION_KEY`](Self::VERSION_KEY). When the migrations are run, only the
+/// steps that are newer than the stored version are applied. Steps therefore
+/// must never be reordered or removed once they have been published, only new
+/// steps may be added at the end.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::settings::{Migrations, Value};
+/// Migrations::new()
+///     .rename("useGameTime", "use_game_time")
+///     .transform("fps", |v| Some(Value::from(v.get_i64()? as f64)))
+///     .remove("obsolete")
+///     .run();
+/// ```
+#[derive(Default)]
+pub struct Migrations {
+    steps: Vec<Step>,
+}
+
+impl Migrations {
+    /// The key that the version of the settings is stored under.
+    pub const VERSION_KEY: &'static str = "__settings_version";
+
+    /// Creates a new empty list of migration steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a step that renames a key. If a value is stored under the old key,
+    /// it replaces any value that is stored under the new key.
+    pub fn rename(mut self, old_key: &'static str, new_key: &'static str) -> Self {
+        self.steps.push(Step::Rename(old_key, new_key));
+        self
+    }
+
+    /// Adds a step that transforms the value of a key. The closure is only
+    /// called if a value is stored under the key. If the closure returns
+    /// [`None`], the key is removed.
+    pub fn transform(
+        mut self,
+        key: &'static str,
+        transform: impl Fn(&Value) -> Option<Value> + 'static,
+    ) -> Self {
+        self.steps.push(Step::Transform(key, Box::new(transform)));
+        self
+    }
+
+    /// Adds a step that removes a key.
+    pub fn remove(mut self, key: &'static str) -> Self {
+        self.steps.push(Step::Remove(key));
+        self
+    }
+
+    /// Returns the version of the settings after all the steps are applied.
+    pub const fn version(&self) -> i64 {
+        self.steps.len() as i64
+    }
+
+    /// Applies all the steps that are newer than the version stored in the
+    /// global settings map and stores the migrated map along with the new
+    /// version. The map is stored with
+    /// [`store_if_unchanged`](Map::store_if_unchanged), so either all the
+    /// steps are applied or none of them. If the global settings map was
+    /// changed in the meantime, the migration is retried with the new map, up
+    /// to 16 times. Returns [`true`] if the settings are now up to date.
+    pub fn run(&self) -> bool {
+        for _ in 0..MAX_ATTEMPTS {
+            let old = Map::load();
+            let Some(migrated) = self.apply(&old) else {
+                return true;
+            };
+            if migrated.store_if_unchanged(&old) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Applies the steps that are newer than the version stored in the map
+    /// to a copy of it. Returns [`None`] if the map is already up to date.
+    pub fn apply(&self, map: &Map) -> Option<Map> {
+        let stored_version = map.get(Self::VERSION_KEY).and_then(|v| v.get_i64());
+        let pending = &self.steps[pending_steps(stored_version, self.steps.len())?];
+
+        let mut map = map.clone();
+        for step in pending {
+            match step {
+                Step::Rename(old_key, new_key) => {
+                    if let Some(value) = map.get(old_key) {
+                        map = without(&map, old_key);
+                        map.insert(new_key, &value);
+                    }
+                }
+                Step::Transform(key, transform) => {
+                    if let Some(value) = map.get(key) {
+                        match transform(&value) {
+                            Some(value) => map.insert(key, &value),
+                            None => map = without(&map, key),
+                        }
+                    }
+                }
+                Step::Remove(key) => {
+                    if map.get(key).is_some() {
+                        map = without(&map, key);
+                    }
+                }
+            }
+        }
+        map.insert(Self::VERSION_KEY, self.version());
+        Some(map)
+    }
+}
+
+/// Determines the range of steps that still need to be applied, based on the
+/// version stored in the map. A missing or negative version means that no
+/// step has been applied yet. Returns [`None`] if there is nothing to apply,
+/// which includes versions that are newer than the steps that are known, as
+/// an older auto splitter can't migrate them back.
+fn pending_steps(stored_version: Option<i64>, step_count: usize) -> Option<Range<usize>> {
+    let applied = usize::try_from(stored_version.unwrap_or(0).max(0)).ok()?;
+    if applied >= step_count {
+        return None;
+    }
+    Some(applied..step_count)
+}
+
+/// Creates a copy of the map without the given key. The runtime doesn't support
+/// removing keys, so the map needs to be rebuilt.
+fn without(map: &Map, key: &str) -> Map {
+    let new = Map::new();
+    for (k, v) in map.iter() {
+        if k != key {
+            new.insert(&k, &v);
+        }
+    }
+    new
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fresh_map() {
+        assert_eq!(pending_steps(None, 3), Some(0..3));
+        assert_eq!(pending_steps(Some(0), 3), Some(0..3));
+    }
+
+    #[test]
+    fn negative_version() {
+        assert_eq!(pending_steps(Some(-1), 3), Some(0..3));
+        assert_eq!(pending_steps(Some(i64::MIN), 3), Some(0..3));
+    }
+
+    #[test]
+    fn partially_migrated_map() {
+        assert_eq!(pending_steps(Some(1), 3), Some(1..3));
+        assert_eq!(pending_steps(Some(2), 3), Some(2..3));
+    }
+
+    #[test]
+    fn current_map() {
+        assert_eq!(pending_steps(Some(3), 3), None);
+    }
+
+    #[test]
+    fn future_version() {
+        assert_eq!(pending_steps(Some(4), 3), None);
+        assert_eq!(pending_steps(Some(i64::MAX), 3), None);
+    }
+
+    #[test]
+    fn no_steps() {
+        assert_eq!(pending_steps(None, 0), None);
+        assert_eq!(pending_steps(Some(0), 0), None);
+    }
+}
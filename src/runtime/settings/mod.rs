@@ -55,11 +55,23 @@
 //! Check the [`Map`](struct@Map) struct for more information.
 
 pub mod gui;
+#[cfg(feature = "alloc")]
+mod json;
+#[cfg(feature = "alloc")]
+mod legacy_xml;
 mod list;
 mod map;
+#[cfg(feature = "alloc")]
+mod migrations;
 mod value;
 
 pub use gui::Gui;
+#[cfg(feature = "alloc")]
+pub use json::JsonError;
+#[cfg(feature = "alloc")]
+pub use legacy_xml::*;
 pub use list::*;
 pub use map::*;
+#[cfg(feature = "alloc")]
+pub use migrations::Migrations;
 pub use value::*;
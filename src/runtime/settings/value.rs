@@ -280,6 +280,29 @@ impl Value {
     }
 }
 
+/// Values are compared deeply, so maps and lists are equal if all of their
+/// entries are equal. Floating point numbers are compared as usual, so a value
+/// that is or contains `NaN` is not equal to itself. Values of an unknown type
+/// are never equal.
+#[cfg(feature = "alloc")]
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        let ty = self.get_type();
+        if ty != other.get_type() {
+            return false;
+        }
+        match ty {
+            ValueType::Map => self.get_map() == other.get_map(),
+            ValueType::List => self.get_list() == other.get_list(),
+            ValueType::Bool => self.get_bool() == other.get_bool(),
+            ValueType::I64 => self.get_i64() == other.get_i64(),
+            ValueType::F64 => self.get_f64() == other.get_f64(),
+            ValueType::String => self.get_string() == other.get_string(),
+            ValueType::Unknown => false,
+        }
+    }
+}
+
 impl Drop for Value {
     #[inline]
     fn drop(&mut self) {
@@ -381,3 +404,62 @@ impl From<&str> for Value {
         Self(unsafe { sys::setting_value_new_string(value.as_ptr(), value.len()) })
     }
 }
+
+/// A trait for types that can be extracted from a [`Value`]. This is
+/// implemented for all the types that a [`Value`] can hold. No coercion
+/// happens, so the extraction fails if the value has a different type.
+pub trait FromValue: Sized {
+    /// Extracts the type from the value. Returns [`None`] if the value has a
+    /// different type.
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl FromValue for Value {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+impl FromValue for Map {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_map()
+    }
+}
+
+impl FromValue for List {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_list()
+    }
+}
+
+impl FromValue for bool {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_bool()
+    }
+}
+
+impl FromValue for i64 {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_i64()
+    }
+}
+
+impl FromValue for f64 {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_f64()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromValue for alloc::string::String {
+    #[inline]
+    fn from_value(value: &Value) -> Option<Self> {
+        value.get_string()
+    }
+}
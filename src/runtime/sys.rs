@@ -8,7 +8,7 @@ pub struct NonZeroAddress(pub NonZeroU64);
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(transparent)]
-pub struct Process(NonZeroU64);
+pub struct Process(pub(super) NonZeroU64);
 
 /// A process id is a unique identifier for a process. It is not guaranteed to
 /// be the same across multiple runs of the same process. It is only guaranteed
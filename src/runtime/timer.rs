@@ -1,4 +1,13 @@
 //! This module provides functions for interacting with the timer.
+//!
+//! Use a [`TimerStateWatcher`] to react to the user starting, resetting or
+//! finishing a run manually, such as clearing per-run state on a reset.
+
+use core::fmt::{self, Write};
+
+use arrayvec::ArrayString;
+
+use crate::watcher::{Pair, Watcher};
 
 use super::sys;
 
@@ -97,6 +106,86 @@ pub fn set_variable_float(key: &str, value: impl ryu::Float) {
     set_variable(key, buf.format(value));
 }
 
+/// Sets a custom key value pair where the value is a floating point number
+/// that is formatted with the given number of decimal places. This may be
+/// arbitrary information that the auto splitter wants to provide for
+/// visualization.
+pub fn set_variable_float_fixed(key: &str, value: f64, precision: usize) {
+    let mut buf = ArrayString::<64>::new();
+    let _ = write!(buf, "{value:.precision$}");
+    set_variable(key, &buf);
+}
+
+/// A custom variable that remembers the value it was last set to. Setting the
+/// same value again doesn't call into the runtime, so the variable can be
+/// updated on every tick without any overhead when the value doesn't change.
+/// Values that are formatted are stored in a buffer with a capacity of `N`
+/// bytes, so longer values may be truncated.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::timer::Variable;
+/// # fn example(deaths: u32) {
+/// let mut variable = Variable::<16>::new("Deaths");
+/// // In the update loop:
+/// variable.set_display(&deaths);
+/// # }
+/// ```
+pub struct Variable<'key, const N: usize> {
+    key: &'key str,
+    value: Option<ArrayString<N>>,
+}
+
+impl<'key, const N: usize> Variable<'key, N> {
+    /// Creates a new variable with the given key. The variable is not set
+    /// until a value is provided.
+    pub const fn new(key: &'key str) -> Self {
+        Self { key, value: None }
+    }
+
+    /// Returns the key of the variable.
+    pub const fn key(&self) -> &'key str {
+        self.key
+    }
+
+    /// Returns the value that the variable was last set to, if it fit into the
+    /// buffer.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Sets the value of the variable, unless it already has this value.
+    pub fn set(&mut self, value: &str) {
+        if self.value() == Some(value) {
+            return;
+        }
+        set_variable(self.key, value);
+        self.value = ArrayString::from(value).ok();
+    }
+
+    /// Formats the value and sets the variable to it, unless it already has
+    /// this value.
+    pub fn set_display(&mut self, value: &dyn fmt::Display) {
+        let mut buf = ArrayString::<N>::new();
+        let _ = write!(buf, "{value}");
+        self.set(&buf);
+    }
+
+    /// Sets the variable to an integer, unless it already has this value.
+    pub fn set_int(&mut self, value: i64) {
+        self.set_display(&value);
+    }
+
+    /// Sets the variable to a floating point number that is formatted with the
+    /// given number of decimal places, unless it already has this value.
+    pub fn set_float(&mut self, value: f64, precision: usize) {
+        let mut buf = ArrayString::<N>::new();
+        let _ = write!(buf, "{value:.precision$}");
+        self.set(&buf);
+    }
+}
+
 /// Gets the state that the timer currently is in.
 #[inline]
 pub fn state() -> TimerState {
@@ -118,3 +207,178 @@ pub fn set_game_time(time: time::Duration) {
     // SAFETY: It is always safe to call this function.
     unsafe { sys::timer_set_game_time(time.whole_seconds(), time.subsec_nanoseconds()) }
 }
+
+/// Pauses the game time while the game is loading. The game time is only
+/// paused and resumed when the loading state changes, so the loading state can
+/// be provided on every tick. When the timer isn't running, the game time is
+/// considered to not be paused, so a run that starts during a load is paused
+/// correctly and a reset during a load doesn't leave the next run paused.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::timer::LoadRemover;
+/// # fn example(is_loading: bool) {
+/// let mut load_remover = LoadRemover::new();
+/// // In the update loop:
+/// load_remover.set_loading(is_loading);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct LoadRemover {
+    is_loading: bool,
+    is_paused: bool,
+    #[cfg(target_os = "wasi")]
+    load_start: Option<crate::time_util::Instant>,
+    #[cfg(target_os = "wasi")]
+    load_time: core::time::Duration,
+}
+
+impl LoadRemover {
+    /// Creates a new load remover that assumes that the game is not loading.
+    pub const fn new() -> Self {
+        Self {
+            is_loading: false,
+            is_paused: false,
+            #[cfg(target_os = "wasi")]
+            load_start: None,
+            #[cfg(target_os = "wasi")]
+            load_time: core::time::Duration::ZERO,
+        }
+    }
+
+    /// Returns whether the game was loading the last time
+    /// [`set_loading`](Self::set_loading) was called.
+    pub const fn is_loading(&self) -> bool {
+        self.is_loading
+    }
+
+    /// Sets whether the game is loading and pauses or resumes the game time
+    /// if necessary.
+    pub fn set_loading(&mut self, is_loading: bool) {
+        self.is_loading = is_loading;
+        match state() {
+            TimerState::Running | TimerState::Paused if is_loading != self.is_paused => {
+                if is_loading {
+                    pause_game_time();
+                } else {
+                    resume_game_time();
+                }
+                self.is_paused = is_loading;
+                #[cfg(target_os = "wasi")]
+                self.track_load_time(is_loading);
+            }
+            TimerState::NotRunning => {
+                // The runtime resumes the game time when the timer is reset.
+                self.is_paused = false;
+                #[cfg(target_os = "wasi")]
+                {
+                    self.load_start = None;
+                    self.load_time = core::time::Duration::ZERO;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(target_os = "wasi")]
+    fn track_load_time(&mut self, is_loading: bool) {
+        let now = crate::time_util::Instant::now();
+        if is_loading {
+            self.load_start = Some(now);
+        } else if let Some(start) = self.load_start.take() {
+            self.load_time += now.duration_since(start);
+        }
+    }
+
+    /// Returns the total time that the game time was paused for loads during
+    /// the current run. This can for example be shown through a custom
+    /// [`Variable`].
+    #[cfg(target_os = "wasi")]
+    pub fn load_time(&self) -> core::time::Duration {
+        let current = self
+            .load_start
+            .map_or(core::time::Duration::ZERO, |start| start.elapsed());
+        self.load_time + current
+    }
+}
+
+/// Watches the state of the timer to detect when a run was started, reset,
+/// finished, paused or resumed, regardless of whether the auto splitter or the
+/// user caused it. Call [`update`](Self::update) once per tick before checking
+/// for any of the transitions. The first update never reports a transition.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::timer::TimerStateWatcher;
+/// # fn example(timer_state: &mut TimerStateWatcher, deaths: &mut u32) {
+/// timer_state.update();
+/// if timer_state.just_reset() {
+///     *deaths = 0;
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct TimerStateWatcher {
+    watcher: Watcher<TimerState>,
+}
+
+impl TimerStateWatcher {
+    /// Creates a new watcher for the state of the timer.
+    pub const fn new() -> Self {
+        Self {
+            watcher: Watcher::new(),
+        }
+    }
+
+    /// Queries the current state of the timer and updates the pair of states.
+    pub fn update(&mut self) -> &Pair<TimerState> {
+        self.watcher.update_infallible(state())
+    }
+
+    /// Returns the pair of states from the latest update.
+    pub const fn pair(&self) -> Option<&Pair<TimerState>> {
+        self.watcher.pair.as_ref()
+    }
+
+    fn check(&self, f: impl FnOnce(TimerState, TimerState) -> bool) -> bool {
+        self.pair().is_some_and(|p| f(p.old, p.current))
+    }
+
+    /// Checks if a run was started since the previous update.
+    pub fn just_started(&self) -> bool {
+        self.check(|old, current| {
+            old == TimerState::NotRunning
+                && matches!(
+                    current,
+                    TimerState::Running | TimerState::Paused | TimerState::Ended
+                )
+        })
+    }
+
+    /// Checks if the timer was reset since the previous update.
+    pub fn just_reset(&self) -> bool {
+        self.check(|old, current| {
+            matches!(
+                old,
+                TimerState::Running | TimerState::Paused | TimerState::Ended
+            ) && current == TimerState::NotRunning
+        })
+    }
+
+    /// Checks if the run was finished since the previous update.
+    pub fn just_ended(&self) -> bool {
+        self.check(|old, current| old != TimerState::Ended && current == TimerState::Ended)
+    }
+
+    /// Checks if the timer was paused since the previous update.
+    pub fn just_paused(&self) -> bool {
+        self.check(|old, current| old != TimerState::Paused && current == TimerState::Paused)
+    }
+
+    /// Checks if the timer was resumed since the previous update.
+    pub fn just_resumed(&self) -> bool {
+        self.check(|old, current| old == TimerState::Paused && current == TimerState::Running)
+    }
+}
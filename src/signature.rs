@@ -1,7 +1,12 @@
 //! Support for finding patterns in a process's memory.
 
-use core::mem::{self, MaybeUninit};
+#[cfg(feature = "alloc")]
+use core::fmt;
+use core::mem;
 
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+use arrayvec::ArrayVec;
 use bytemuck::AnyBitPattern;
 
 use crate::{Address, Process};
@@ -55,6 +60,41 @@ impl Parser<'_> {
     }
 }
 
+/// Builds the lookup table of offsets to jump forward by when a byte is
+/// encountered at the end of the current window. Bytes that are not fully
+/// known can match anything, so the jump can't go past them.
+const fn skip_offsets<const N: usize>(needle: &[u8; N], mask: &[u8; N]) -> [Offset; 256] {
+    let mut skip_offsets = [0; 256];
+
+    let mut unknown = 0;
+    let end = N - 1;
+    let mut i = 0;
+    while i < end {
+        let byte = needle[i];
+        let mask = mask[i];
+        if mask == 0xFF {
+            skip_offsets[byte as usize] = (end - i) as Offset;
+        } else {
+            unknown = (end - i) as Offset;
+        }
+        i += 1;
+    }
+
+    if unknown == 0 {
+        unknown = N as Offset;
+    }
+
+    i = 0;
+    while i < skip_offsets.len() {
+        if unknown < skip_offsets[i] || skip_offsets[i] == 0 {
+            skip_offsets[i] = unknown;
+        }
+        i += 1;
+    }
+
+    skip_offsets
+}
+
 #[inline]
 const fn contains(mut bytes: &[u8], search_byte: u8) -> bool {
     while let [b, rem @ ..] = bytes {
@@ -111,38 +151,10 @@ impl<const N: usize> Signature<N> {
             }
             assert!(i == N);
 
-            let mut skip_offsets = [0; 256];
-
-            let mut unknown = 0;
-            let end = N - 1;
-            let mut i = 0;
-            while i < end {
-                let byte = needle[i];
-                let mask = mask[i];
-                if mask == 0xFF {
-                    skip_offsets[byte as usize] = (end - i) as Offset;
-                } else {
-                    unknown = (end - i) as Offset;
-                }
-                i += 1;
-            }
-
-            if unknown == 0 {
-                unknown = N as Offset;
-            }
-
-            i = 0;
-            while i < skip_offsets.len() {
-                if unknown < skip_offsets[i] || skip_offsets[i] == 0 {
-                    skip_offsets[i] = unknown;
-                }
-                i += 1;
-            }
-
             Self::Complex {
                 needle,
                 mask,
-                skip_offsets,
+                skip_offsets: skip_offsets(&needle, &mask),
             }
         } else {
             let mut needle = [0; N];
@@ -164,6 +176,49 @@ impl<const N: usize> Signature<N> {
         }
     }
 
+    /// Creates a new signature from the bytes and the mask given. A byte in
+    /// the process matches if `byte & mask == bytes & mask`, so individual
+    /// bits can be masked out, such as the bits that encode a register of an
+    /// instruction. A mask of `0x00` matches any byte and a mask of `0xFF`
+    /// only matches the exact byte.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the signature is longer than 255 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use asr::signature::Signature;
+    /// // mov r64, [rip+disp32] with any destination register.
+    /// static SIG: Signature<7> = Signature::with_mask(
+    ///     [0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00],
+    ///     [0xFF, 0xFF, 0xC7, 0x00, 0x00, 0x00, 0x00],
+    /// );
+    /// ```
+    pub const fn with_mask(bytes: [u8; N], mask: [u8; N]) -> Self {
+        assert!(N > 0 && N < 256);
+
+        let mut needle = [0; N];
+        let mut is_simple = true;
+        let mut i = 0;
+        while i < N {
+            needle[i] = bytes[i] & mask[i];
+            is_simple &= mask[i] == 0xFF;
+            i += 1;
+        }
+
+        if is_simple {
+            Self::Simple(needle)
+        } else {
+            Self::Complex {
+                needle,
+                mask,
+                skip_offsets: skip_offsets(&needle, &mask),
+            }
+        }
+    }
+
     fn scan(&self, haystack: &[u8]) -> Option<usize> {
         match self {
             Signature::Simple(needle) => memchr::memmem::find(haystack, needle),
@@ -194,30 +249,730 @@ impl<const N: usize> Signature<N> {
         process: &Process,
         (addr, len): (impl Into<Address>, u64),
     ) -> Option<Address> {
-        let mut addr: Address = Into::into(addr);
-        // TODO: Handle the case where a signature may be cut in half by a page
-        // boundary.
-        let overall_end = addr.value() + len;
-        let mut buf = [MaybeUninit::uninit(); 4 << 10];
-        while addr.value() < overall_end {
-            // We round up to the 4 KiB address boundary as that's a single
-            // page, which is safe to read either fully or not at all. We do
-            // this to do a single read rather than many small ones as the
-            // syscall overhead is a quite high.
-            let end = (addr.value() & !((4 << 10) - 1)) + (4 << 10).min(overall_end);
-            let len = end - addr.value();
-            let current_read_buf = &mut buf[..len as usize];
-            if let Ok(current_read_buf) = process.read_into_uninit_buf(addr, current_read_buf) {
-                if let Some(pos) = self.scan(current_read_buf) {
-                    return Some(addr.add(pos as u64));
-                }
-            };
-            addr = Address::new(end);
+        self.scan_iter(process, (addr, len)).next()
+    }
+
+    /// Scans a process for all the occurrences of the signature in the address
+    /// range of the process given. The addresses of the starts of the matches
+    /// are returned in increasing order. Matches may overlap, but each match is
+    /// only returned once, even if it crosses a page boundary. The memory is
+    /// only read as the iterator advances.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{signature::Signature, Address, Process};
+    /// # fn example(process: &Process, text_section: (Address, u64)) {
+    /// static SIG: Signature<7> = Signature::new("48 8B 05 ?? ?? ?? ??");
+    /// for address in SIG.scan_iter(process, text_section) {
+    ///     // TODO: Check which of the matches is the right one.
+    /// }
+    /// # }
+    /// ```
+    pub fn scan_iter<'a>(
+        &'a self,
+        process: &'a Process,
+        (addr, len): (impl Into<Address>, u64),
+    ) -> SignatureMatches<'a, N> {
+        SignatureMatches {
+            inner: Matches::new(process, self, [0; SIGNATURE_BUF_LEN], addr.into(), len),
+        }
+    }
+
+    /// Scans a process for all the occurrences of the signature in the address
+    /// range of the process given, with the options given. Check
+    /// [`scan_iter`](Self::scan_iter) for more information. Memory that can't
+    /// be read is skipped, so the range may include unmapped memory. The
+    /// iterator reports how many bytes were actually scanned.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the alignment is not a power of two.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{signature::{ScanOptions, Signature}, Address, Process};
+    /// # fn example(process: &Process, heap: (Address, u64)) {
+    /// static SIG: Signature<8> = Signature::new("47 41 4D 45 ?? ?? 00 00");
+    /// let options = ScanOptions {
+    ///     alignment: 16,
+    ///     max_matches: Some(4),
+    ///     ..ScanOptions::new()
+    /// };
+    /// let mut matches = SIG.scan_with_options(process, heap, &options);
+    /// for address in &mut matches {
+    ///     // TODO: Do something with the address.
+    /// }
+    /// asr::print_limited::<64>(&format_args!("Scanned {} bytes", matches.bytes_scanned()));
+    /// # }
+    /// ```
+    pub fn scan_with_options<'a>(
+        &'a self,
+        process: &'a Process,
+        range: (impl Into<Address>, u64),
+        options: &ScanOptions,
+    ) -> SignatureMatches<'a, N> {
+        let mut matches = self.scan_iter(process, range);
+        matches.inner = matches.inner.with_options(options);
+        matches
+    }
+
+    /// Scans a process for the signature, only testing addresses that are a
+    /// multiple of the alignment given. This is a lot faster when the pattern
+    /// is known to be aligned, such as the start of a structure. If the
+    /// signature is found, the address of the start of the signature is
+    /// returned.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the alignment is not a power of two.
+    pub fn scan_process_range_aligned(
+        &self,
+        process: &Process,
+        range: (impl Into<Address>, u64),
+        alignment: u64,
+    ) -> Option<Address> {
+        let options = ScanOptions {
+            alignment,
+            max_matches: Some(1),
+            ..ScanOptions::new()
+        };
+        self.scan_with_options(process, range, &options).next()
+    }
+
+    /// Scans a process for the `n`th occurrence of the signature in the address
+    /// range of the process given, counting from zero. Check
+    /// [`scan_iter`](Self::scan_iter) for more information.
+    pub fn scan_nth(
+        &self,
+        process: &Process,
+        range: (impl Into<Address>, u64),
+        n: usize,
+    ) -> Option<Address> {
+        self.scan_iter(process, range).nth(n)
+    }
+
+    /// Scans a process for the signature and resolves the 32-bit displacement
+    /// of the instruction that the signature matches, such as the one of
+    /// `mov rax, [rip+disp32]` on x86-64. The displacement is read at
+    /// `displacement_offset` bytes into the match and is relative to the end of
+    /// the instruction, which is `instruction_len` bytes into the match. The
+    /// displacement is sign-extended, so it may point backwards.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{signature::Signature, Address, Process};
+    /// # fn example(process: &Process, module: (Address, u64)) {
+    /// static SIG: Signature<10> = Signature::new("48 8B 05 ?? ?? ?? ?? 48 85 C0");
+    /// let game_manager = SIG.scan_and_resolve_rel32(process, module, 3, 7);
+    /// # }
+    /// ```
+    pub fn scan_and_resolve_rel32(
+        &self,
+        process: &Process,
+        range: (impl Into<Address>, u64),
+        displacement_offset: u64,
+        instruction_len: u64,
+    ) -> Option<Address> {
+        let address = self.scan_process_range(process, range)?;
+        let displacement = process.read::<i32>(address + displacement_offset).ok()?;
+        Some(rel32_target(address, instruction_len, displacement))
+    }
+
+    /// Counts how often the signature occurs in the address range of the
+    /// process given. This is mostly useful for checking whether a signature is
+    /// unique. Check [`scan_iter`](Self::scan_iter) for more information.
+    pub fn scan_count(&self, process: &Process, range: (impl Into<Address>, u64)) -> usize {
+        self.scan_iter(process, range).count()
+    }
+}
+
+impl<const N: usize> Pattern for Signature<N> {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.scan(haystack)
+    }
+
+    fn matches_at(&self, haystack: &[u8]) -> bool {
+        match self {
+            Signature::Simple(needle) => haystack.starts_with(needle),
+            Signature::Complex { needle, mask, .. } => strip_pod::<[u8; N]>(&mut &*haystack)
+                .is_some_and(|scan| matches(scan, needle, mask)),
+        }
+    }
+}
+
+/// Resolves a 32-bit displacement that is relative to the end of an
+/// instruction. The displacement is sign-extended.
+const fn rel32_target(instruction: Address, instruction_len: u64, displacement: i32) -> Address {
+    Address::new(instruction.value().wrapping_add(instruction_len)).add_signed(displacement as i64)
+}
+
+/// The size of the chunks that memory is read in. This is the size of a single
+/// page, which is safe to read either fully or not at all.
+const CHUNK_LEN: usize = 4 << 10;
+
+/// The size of the buffer needed to scan for a [`Signature`], which is a chunk
+/// plus the longest possible overlap with the previous chunk.
+const SIGNATURE_BUF_LEN: usize = CHUNK_LEN + 256;
+
+/// Options that control how a [`Signature`] is scanned for. Check
+/// [`Signature::scan_with_options`] for more information.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ScanOptions {
+    /// Only addresses that are a multiple of the alignment are tested for a
+    /// match. This needs to be a power of two. The default is 1, which tests
+    /// every address.
+    pub alignment: u64,
+    /// The maximum number of matches to return. The scan stops once this many
+    /// matches are found. The default is no limit.
+    pub max_matches: Option<usize>,
+    /// The address at which the scan stops, even if the range continues past
+    /// it. Matches need to end before this address. The default is no limit.
+    pub end_address: Option<Address>,
+    /// The maximum number of bytes that are read per tick when scanning
+    /// asynchronously. The scan yields back to the runtime whenever this many
+    /// bytes were read without finding a match. The default is 4 MiB.
+    pub bytes_per_tick: u64,
+}
+
+impl ScanOptions {
+    /// Creates the default options, which scan every address of the range.
+    pub const fn new() -> Self {
+        Self {
+            alignment: 1,
+            max_matches: None,
+            end_address: None,
+            bytes_per_tick: 4 << 20,
+        }
+    }
+}
+
+impl Default for ScanOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iterator over the matches of a [`Signature`] in an address range of a
+/// process. Check [`Signature::scan_iter`] for more information.
+pub struct SignatureMatches<'a, const N: usize> {
+    inner: Matches<'a, &'a Signature<N>, [u8; SIGNATURE_BUF_LEN]>,
+}
+
+impl<const N: usize> SignatureMatches<'_, N> {
+    /// Returns the number of bytes that were read from the process so far.
+    /// Memory that couldn't be read is not counted.
+    #[inline]
+    pub const fn bytes_scanned(&self) -> u64 {
+        self.inner.bytes_scanned
+    }
+}
+
+impl<'a, const N: usize> SignatureMatches<'a, N> {
+    pub(crate) const fn process(&self) -> &'a Process {
+        self.inner.process
+    }
+
+    /// Returns the next match, reading at most the amount of bytes that may be
+    /// read per tick. Returns [`None`] if the scan needs to continue on the
+    /// next tick.
+    pub(crate) fn next_within_tick(&mut self) -> Option<Option<Address>> {
+        self.inner.next_bounded(self.inner.bytes_per_tick)
+    }
+}
+
+impl<const N: usize> Iterator for SignatureMatches<'_, N> {
+    type Item = Address;
+
+    #[inline]
+    fn next(&mut self) -> Option<Address> {
+        self.inner.next()
+    }
+}
+
+/// A pattern that can be searched for in memory.
+trait Pattern {
+    /// The length of the pattern in bytes.
+    fn len(&self) -> usize;
+    /// Finds the first position in the haystack where the pattern matches.
+    fn find(&self, haystack: &[u8]) -> Option<usize>;
+    /// Checks whether the pattern matches at the start of the haystack.
+    fn matches_at(&self, haystack: &[u8]) -> bool;
+}
+
+impl<P: Pattern + ?Sized> Pattern for &P {
+    #[inline]
+    fn len(&self) -> usize {
+        P::len(self)
+    }
+
+    #[inline]
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        P::find(self, haystack)
+    }
+
+    #[inline]
+    fn matches_at(&self, haystack: &[u8]) -> bool {
+        P::matches_at(self, haystack)
+    }
+}
+
+impl<const CAP: usize> Pattern for ArrayVec<u8, CAP> {
+    #[inline]
+    fn len(&self) -> usize {
+        ArrayVec::len(self)
+    }
+
+    #[inline]
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        memchr::memmem::find(haystack, self)
+    }
+
+    #[inline]
+    fn matches_at(&self, haystack: &[u8]) -> bool {
+        haystack.starts_with(self)
+    }
+}
+
+/// An iterator over all the matches of a pattern in an address range of a
+/// process. The memory is read in chunks that don't cross page boundaries. The
+/// last bytes of each chunk are kept when reading the next chunk, so matches
+/// that cross a page boundary are found as well. The buffer needs to be able to
+/// hold a chunk plus the length of the pattern. Chunks that can't be read are
+/// skipped.
+struct Matches<'a, P, B> {
+    process: &'a Process,
+    pattern: P,
+    buf: B,
+    /// The address of the next chunk to read.
+    addr: u64,
+    /// The address at which scanning stops.
+    end: u64,
+    /// The address of the first byte in the buffer.
+    buf_addr: u64,
+    /// The number of bytes in the buffer.
+    filled: usize,
+    /// The position in the buffer to continue searching at.
+    pos: usize,
+    /// The alignment of the addresses to test, which is a power of two.
+    alignment: u64,
+    /// The number of matches that may still be returned.
+    remaining: usize,
+    /// The number of bytes that were read from the process.
+    bytes_scanned: u64,
+    /// The number of bytes that may be read per tick when scanning
+    /// asynchronously.
+    bytes_per_tick: u64,
+}
+
+impl<'a, P: Pattern, B: AsMut<[u8]>> Matches<'a, P, B> {
+    const fn new(process: &'a Process, pattern: P, buf: B, addr: Address, len: u64) -> Self {
+        let addr = addr.value();
+        Self {
+            process,
+            pattern,
+            buf,
+            addr,
+            end: addr.saturating_add(len),
+            buf_addr: addr,
+            filled: 0,
+            pos: 0,
+            alignment: 1,
+            remaining: usize::MAX,
+            bytes_scanned: 0,
+            bytes_per_tick: ScanOptions::new().bytes_per_tick,
+        }
+    }
+
+    fn with_options(mut self, options: &ScanOptions) -> Self {
+        assert!(
+            options.alignment.is_power_of_two(),
+            "The alignment needs to be a power of two."
+        );
+        self.alignment = options.alignment;
+        if let Some(max_matches) = options.max_matches {
+            self.remaining = max_matches;
+        }
+        if let Some(end_address) = options.end_address {
+            self.end = self.end.min(end_address.value());
+        }
+        self.bytes_per_tick = options.bytes_per_tick;
+        self
+    }
+
+    /// Finds the next match in the buffer, starting at the current position.
+    fn find_in_buf(&mut self) -> Option<usize> {
+        let haystack = &self.buf.as_mut()[..self.filled];
+        if self.alignment == 1 {
+            return self
+                .pattern
+                .find(&haystack[self.pos..])
+                .map(|index| self.pos + index);
+        }
+        let misalignment = (self.buf_addr + self.pos as u64).wrapping_neg() & (self.alignment - 1);
+        let mut pos = self.pos as u64 + misalignment;
+        let last = haystack.len().checked_sub(self.pattern.len())? as u64;
+        while pos <= last {
+            if self.pattern.matches_at(&haystack[pos as usize..]) {
+                return Some(pos as usize);
+            }
+            pos += self.alignment;
         }
         None
     }
 }
 
+impl<P: Pattern, B: AsMut<[u8]>> Iterator for Matches<'_, P, B> {
+    type Item = Address;
+
+    #[inline]
+    fn next(&mut self) -> Option<Address> {
+        self.next_bounded(u64::MAX).flatten()
+    }
+}
+
+impl<P: Pattern, B: AsMut<[u8]>> Matches<'_, P, B> {
+    /// Returns the next match, reading at most `max_bytes` from the process,
+    /// but always at least a single chunk. Returns [`None`] if the scan isn't
+    /// finished yet, but no match was found within the bytes read.
+    fn next_bounded(&mut self, max_bytes: u64) -> Option<Option<Address>> {
+        if self.remaining == 0 {
+            return Some(None);
+        }
+        let pattern_len = self.pattern.len();
+        let mut bytes_read = 0u64;
+        loop {
+            if let Some(pos) = self.find_in_buf() {
+                self.pos = pos + 1;
+                self.remaining -= 1;
+                return Some(Some(Address::new(self.buf_addr + pos as u64)));
+            }
+
+            if self.addr >= self.end {
+                return Some(None);
+            }
+            if bytes_read != 0 && bytes_read >= max_bytes {
+                return None;
+            }
+            let buf = self.buf.as_mut();
+
+            // Only the bytes that may still be the start of a match are kept.
+            let keep_from = self
+                .filled
+                .saturating_sub(pattern_len.saturating_sub(1))
+                .max(self.pos.min(self.filled));
+            buf.copy_within(keep_from..self.filled, 0);
+            self.buf_addr += keep_from as u64;
+            self.filled -= keep_from;
+            self.pos = 0;
+
+            let chunk_end =
+                ((self.addr & !(CHUNK_LEN as u64 - 1)) + CHUNK_LEN as u64).min(self.end);
+            let len = (chunk_end - self.addr) as usize;
+            bytes_read += len as u64;
+            let chunk = &mut buf[self.filled..self.filled + len];
+            if self.process.read_into_buf(self.addr, chunk).is_ok() {
+                self.filled += len;
+                self.bytes_scanned += len as u64;
+            } else {
+                // The chunk is skipped, as matches can't span memory that
+                // can't be read.
+                self.filled = 0;
+                self.buf_addr = chunk_end;
+            }
+            self.addr = chunk_end;
+        }
+    }
+}
+
+/// An iterator over the matches of a string in an address range of a process.
+/// Check [`Process::scan_for_str`] for more information.
+pub struct StrMatches<'a, const CAP: usize> {
+    inner: Matches<'a, ArrayVec<u8, CAP>, [u8; SIGNATURE_BUF_LEN]>,
+}
+
+impl<const CAP: usize> StrMatches<'_, CAP> {
+    /// Returns the number of bytes that were read from the process so far.
+    /// Memory that couldn't be read is not counted.
+    #[inline]
+    pub const fn bytes_scanned(&self) -> u64 {
+        self.inner.bytes_scanned
+    }
+}
+
+impl<const CAP: usize> Iterator for StrMatches<'_, CAP> {
+    type Item = Address;
+
+    #[inline]
+    fn next(&mut self) -> Option<Address> {
+        self.inner.next()
+    }
+}
+
+impl Process {
+    /// Scans the address range of the process given for all the occurrences of
+    /// a UTF-8 string. The string doesn't need to be nul-terminated in the
+    /// process. The addresses of the starts of the matches are returned in
+    /// increasing order. The string is encoded into a buffer that can hold up
+    /// to `CAP` bytes.
+    ///
+    /// # Panics
+    ///
+    /// This panics if the string is empty or longer than `CAP` bytes, or if
+    /// `CAP` is larger than 256.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{Address, Process};
+    /// # fn example(process: &Process, module: (Address, u64)) {
+    /// let ready = process.scan_for_str::<16>(module, "Ready?").next();
+    /// # }
+    /// ```
+    pub fn scan_for_str<const CAP: usize>(
+        &self,
+        range: (impl Into<Address>, u64),
+        text: &str,
+    ) -> StrMatches<'_, CAP> {
+        self.scan_for_bytes(range, text.bytes())
+    }
+
+    /// Scans the address range of the process given for all the occurrences of
+    /// a nul-terminated UTF-8 string. The nul terminator needs to be part of
+    /// the match, so strings that merely start with the string given are not
+    /// found. The nul terminator counts towards the `CAP` bytes. Check
+    /// [`scan_for_str`](Self::scan_for_str) for more information.
+    pub fn scan_for_cstr<const CAP: usize>(
+        &self,
+        range: (impl Into<Address>, u64),
+        text: &str,
+    ) -> StrMatches<'_, CAP> {
+        self.scan_for_bytes(range, text.bytes().chain([0]))
+    }
+
+    /// Scans the address range of the process given for all the occurrences of
+    /// a string encoded as little-endian UTF-16, which is how most strings are
+    /// stored on Windows and in .NET. Every 16-bit character takes up two of
+    /// the `CAP` bytes. Check [`scan_for_str`](Self::scan_for_str) for more
+    /// information.
+    pub fn scan_for_utf16_str<const CAP: usize>(
+        &self,
+        range: (impl Into<Address>, u64),
+        text: &str,
+    ) -> StrMatches<'_, CAP> {
+        self.scan_for_bytes(range, text.encode_utf16().flat_map(u16::to_le_bytes))
+    }
+
+    fn scan_for_bytes<const CAP: usize>(
+        &self,
+        (addr, len): (impl Into<Address>, u64),
+        bytes: impl Iterator<Item = u8>,
+    ) -> StrMatches<'_, CAP> {
+        assert!(CAP <= 256, "The capacity can't be larger than 256 bytes.");
+        let mut pattern = ArrayVec::new();
+        for byte in bytes {
+            pattern.try_push(byte).expect("The string is too long.");
+        }
+        assert!(!pattern.is_empty(), "The string can't be empty.");
+        StrMatches {
+            inner: Matches::new(self, pattern, [0; SIGNATURE_BUF_LEN], addr.into(), len),
+        }
+    }
+}
+
+/// A signature that is parsed at runtime from a pattern string, such as the
+/// ones that are copied from IDA or x64dbg. Prefer [`Signature`] if the pattern
+/// is known at compile time. The pattern consists of hexadecimal bytes that are
+/// separated by whitespace. A byte can be replaced by `?` or `??` to match any
+/// byte, and a single nibble can be replaced by `?` to only match the other
+/// nibble, such as in `4?`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{signature::DynSignature, Address, Process};
+/// # fn example(process: &Process, module: (Address, u64), pattern: &str) {
+/// let signature = DynSignature::new(pattern).expect("Invalid pattern");
+/// for address in signature.scan_all(process, module) {
+///     // TODO: Do something with the address.
+/// }
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynSignature {
+    needle: Vec<u8>,
+    mask: Vec<u8>,
+}
+
+/// An error that occurred while parsing a [`DynSignature`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureError {
+    /// The byte offset into the pattern at which the offending token starts.
+    pub offset: usize,
+    /// The token that couldn't be parsed. This is empty if the pattern doesn't
+    /// contain any bytes.
+    pub token: String,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.is_empty() {
+            f.write_str("The pattern doesn't contain any bytes")
+        } else {
+            write!(
+                f,
+                "Invalid token `{}` at byte offset {}",
+                self.token, self.offset
+            )
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl DynSignature {
+    /// Parses a signature from a pattern string. Returns an error describing
+    /// the offending token if the pattern is invalid or empty.
+    pub fn new(pattern: &str) -> Result<Self, SignatureError> {
+        let mut needle = Vec::new();
+        let mut mask = Vec::new();
+
+        let mut rest = pattern;
+        loop {
+            let trimmed = rest.trim_start();
+            if trimmed.is_empty() {
+                break;
+            }
+            let offset = pattern.len() - trimmed.len();
+            let len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            let (token, after) = trimmed.split_at(len);
+            rest = after;
+
+            let error = || SignatureError {
+                offset,
+                token: token.into(),
+            };
+            if token == "?" {
+                needle.push(0);
+                mask.push(0);
+                continue;
+            }
+            // Multiple bytes may be written without spaces in between.
+            if token.len() % 2 != 0 {
+                return Err(error());
+            }
+            for pair in token.as_bytes().chunks_exact(2) {
+                let (high, high_mask) = parse_nibble(pair[0]).ok_or_else(error)?;
+                let (low, low_mask) = parse_nibble(pair[1]).ok_or_else(error)?;
+                needle.push(high << 4 | low);
+                mask.push(high_mask << 4 | low_mask);
+            }
+        }
+
+        if needle.is_empty() {
+            return Err(SignatureError {
+                offset: 0,
+                token: String::new(),
+            });
+        }
+        Ok(Self { needle, mask })
+    }
+
+    /// Returns the number of bytes that the signature matches.
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(&self) -> usize {
+        self.needle.len()
+    }
+
+    /// Scans a process for the signature. This will scan the address range of
+    /// the process given. If the signature is found, the address of the start
+    /// of the signature is returned.
+    pub fn scan_process_range(
+        &self,
+        process: &Process,
+        (addr, len): (impl Into<Address>, u64),
+    ) -> Option<Address> {
+        self.scan_all(process, (addr, len)).next()
+    }
+
+    /// Scans a process for all the occurrences of the signature in the address
+    /// range of the process given. The addresses of the starts of the matches
+    /// are returned in increasing order. Matches may overlap. The memory is
+    /// only read as the iterator advances.
+    pub fn scan_all<'a>(
+        &'a self,
+        process: &'a Process,
+        (addr, len): (impl Into<Address>, u64),
+    ) -> impl Iterator<Item = Address> + 'a {
+        let buf = vec![0; CHUNK_LEN + self.needle.len()];
+        Matches::new(process, self, buf, addr.into(), len)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Pattern for DynSignature {
+    fn len(&self) -> usize {
+        self.needle.len()
+    }
+
+    fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let len = self.needle.len();
+        if haystack.len() < len {
+            return None;
+        }
+        let matches_at = |start: usize| self.matches_at(&haystack[start..]);
+        let last = haystack.len() - len;
+        if self.mask[0] == 0xFF {
+            // Jump straight to the candidates that start with the first byte.
+            let first = self.needle[0];
+            let mut start = 0;
+            while let Some(index) = memchr::memchr(first, &haystack[start..=last]) {
+                let candidate = start + index;
+                if matches_at(candidate) {
+                    return Some(candidate);
+                }
+                start = candidate + 1;
+                if start > last {
+                    break;
+                }
+            }
+            None
+        } else {
+            (0..=last).find(|&start| matches_at(start))
+        }
+    }
+
+    fn matches_at(&self, haystack: &[u8]) -> bool {
+        haystack.len() >= self.needle.len()
+            && haystack
+                .iter()
+                .zip(&self.needle)
+                .zip(&self.mask)
+                .all(|((&b, &n), &m)| b & m == n)
+    }
+}
+
+/// Parses a single nibble of a pattern, returning the value and the mask.
+#[cfg(feature = "alloc")]
+const fn parse_nibble(c: u8) -> Option<(u8, u8)> {
+    Some(match c {
+        b'0'..=b'9' => (c - b'0', 0xF),
+        b'a'..=b'f' => (c - b'a' + 0xA, 0xF),
+        b'A'..=b'F' => (c - b'A' + 0xA, 0xF),
+        b'?' => (0, 0),
+        _ => return None,
+    })
+}
+
 fn matches<const N: usize>(scan: &[u8; N], needle: &[u8; N], mask: &[u8; N]) -> bool {
     // SAFETY: Before reading individual chunks from the arrays, we check that
     // we can still read values of that size. We also read them unaligned as the
@@ -297,3 +1052,112 @@ fn strip_pod<'a, T: AnyBitPattern>(cursor: &mut &'a [u8]) -> Option<&'a T> {
     *cursor = after;
     Some(bytemuck::from_bytes(before))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find_naive<const N: usize>(
+        haystack: &[u8],
+        bytes: &[u8; N],
+        mask: &[u8; N],
+    ) -> Option<usize> {
+        haystack.windows(N).position(|window| {
+            window
+                .iter()
+                .zip(bytes.iter().zip(mask))
+                .all(|(&h, (&b, &m))| h & m == b & m)
+        })
+    }
+
+    /// Checks that scanning finds the same first match as a naive search in
+    /// a lot of pseudo-random haystacks. The haystacks only use a few
+    /// distinct bytes, so partial matches are common.
+    fn check_against_naive<const N: usize>(bytes: [u8; N], mask: [u8; N]) {
+        let signature = Signature::with_mask(bytes, mask);
+        let alphabet = [bytes[0], bytes[N - 1], bytes[0] ^ 0x08, 0x00, 0xFF, 0x45];
+        let mut state = 0x2545_F491_u32;
+        for _ in 0..300 {
+            let mut haystack = [0; 64];
+            for byte in &mut haystack {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                *byte = alphabet[state as usize % alphabet.len()];
+            }
+            for start in 0..haystack.len() {
+                let haystack = &haystack[start..];
+                assert_eq!(
+                    signature.scan(haystack),
+                    find_naive(haystack, &bytes, &mask),
+                    "{haystack:02X?}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn skip_table_fully_masked_bytes() {
+        let bytes = [0x48, 0x8B, 0x00, 0x45];
+        let mask = [0xFF, 0xFF, 0x00, 0xFF];
+        let signature = Signature::with_mask(bytes, mask);
+        assert_eq!(signature.scan(&[0x48, 0x8B, 0x12, 0x45]), Some(0));
+        assert_eq!(signature.scan(&[0x45, 0x48, 0x8B, 0x45, 0x45]), Some(1));
+        assert_eq!(signature.scan(&[0x48, 0x8B, 0x12, 0x44]), None);
+        check_against_naive(bytes, mask);
+
+        // Wildcards at the start and at the end, which is the byte that is
+        // looked up in the skip table.
+        check_against_naive([0x00, 0x8B, 0x45, 0x00], [0x00, 0xFF, 0xFF, 0x00]);
+        check_against_naive([0x00, 0x00, 0x45], [0x00, 0x00, 0xFF]);
+        check_against_naive([0x45, 0x00, 0x00], [0xFF, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn skip_table_partially_masked_first_byte() {
+        // Any of `rex.w` to `rex.wrxb` followed by `mov r64, r/m64`.
+        let bytes = [0x48, 0x8B, 0x45];
+        let mask = [0xF8, 0xFF, 0xFF];
+        let signature = Signature::with_mask(bytes, mask);
+        assert_eq!(signature.scan(&[0x4C, 0x8B, 0x45]), Some(0));
+        assert_eq!(signature.scan(&[0x8B, 0x4F, 0x8B, 0x45]), Some(1));
+        assert_eq!(signature.scan(&[0x40, 0x8B, 0x45]), None);
+        check_against_naive(bytes, mask);
+
+        // The same, but with a partially masked byte in the middle as well.
+        check_against_naive([0x48, 0x8B, 0x05, 0x45], [0xF8, 0xFF, 0xC7, 0xFF]);
+    }
+
+    #[test]
+    fn rel32_negative_displacement() {
+        // mov rax, [rip-0x200]
+        static SIG: Signature<7> = Signature::new("48 8B 05 ?? ?? ?? ??");
+        let mut haystack = [0xCC; 0x20];
+        haystack[0x10..0x17].copy_from_slice(&[0x48, 0x8B, 0x05, 0x00, 0xFE, 0xFF, 0xFF]);
+
+        let base = Address::new(0x1000_0000);
+        let offset = SIG.scan(&haystack).unwrap();
+        assert_eq!(offset, 0x10);
+        let displacement = i32::from_le_bytes(haystack[offset + 3..offset + 7].try_into().unwrap());
+        assert_eq!(displacement, -0x200);
+        assert_eq!(
+            rel32_target(base + offset as u64, 7, displacement),
+            Address::new(0x1000_0017 - 0x200),
+        );
+    }
+
+    #[test]
+    fn rel32_displacement_limits() {
+        let instruction = Address::new(0x1_0000_0000);
+        assert_eq!(
+            rel32_target(instruction, 7, i32::MIN),
+            Address::new(0x1_0000_0007 - 0x8000_0000),
+        );
+        assert_eq!(
+            rel32_target(instruction, 7, i32::MAX),
+            Address::new(0x1_0000_0007 + 0x7FFF_FFFF),
+        );
+        // A displacement pointing at the start of the instruction itself.
+        assert_eq!(rel32_target(instruction, 7, -7), instruction);
+    }
+}
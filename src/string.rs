@@ -1,9 +1,30 @@
 //! Support for string types that can be read from a process's memory.
 
+#[cfg(feature = "alloc")]
+use core::iter::{self, FusedIterator};
 use core::{ops, slice, str};
 
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
 use bytemuck::{Pod, Zeroable};
 
+/// A UTF-8 string with a fixed capacity that doesn't need to allocate. It
+/// implements [`Write`](core::fmt::Write), so it can be used with the
+/// [`write!`] macro to build strings, such as the values of custom variables.
+/// If the formatted string doesn't fit, [`write!`] returns an error. The
+/// pieces that were formatted before that are kept.
+///
+/// # Example
+///
+/// ```
+/// # use asr::string::ArrayString;
+/// use core::fmt::Write;
+///
+/// let mut value = ArrayString::<16>::new();
+/// write!(value, "Deaths: {}", 42).unwrap();
+/// assert_eq!(value.as_str(), "Deaths: 42");
+/// ```
 pub use arrayvec::ArrayString;
 
 use crate::FromEndian;
@@ -41,6 +62,42 @@ impl<const N: usize> ArrayCString<N> {
             && self.0.get(..bytes.len()).is_some_and(|s| s == bytes)
     }
 
+    /// Checks whether the string matches the given text, ignoring the case of
+    /// ASCII letters. Bytes that are not ASCII letters need to match exactly.
+    pub fn matches_ignore_case(&self, text: impl AsRef<[u8]>) -> bool {
+        self.as_bytes().eq_ignore_ascii_case(text.as_ref())
+    }
+
+    /// Checks whether the string starts with the given text.
+    pub fn starts_with(&self, text: impl AsRef<[u8]>) -> bool {
+        self.as_bytes().starts_with(text.as_ref())
+    }
+
+    /// Checks whether the string ends with the given text. Only the bytes up
+    /// until the nul-terminator are considered.
+    pub fn ends_with(&self, text: impl AsRef<[u8]>) -> bool {
+        self.as_bytes().ends_with(text.as_ref())
+    }
+
+    /// Checks whether the string contains the given text anywhere before the
+    /// nul-terminator. An empty text is always contained.
+    pub fn contains_str(&self, text: impl AsRef<[u8]>) -> bool {
+        let text = text.as_ref();
+        text.is_empty() || self.as_bytes().windows(text.len()).any(|w| w == text)
+    }
+
+    /// Returns the bytes of the string up until (but excluding) the
+    /// nul-terminator, without any leading and trailing ASCII whitespace.
+    pub fn trimmed(&self) -> &[u8] {
+        self.as_bytes().trim_ascii()
+    }
+
+    /// Converts the string to UTF-8 with a capacity of `M` bytes. Check
+    /// [`from_utf8_lossy`] for more information.
+    pub fn to_array_string<const M: usize>(&self) -> ArrayString<M> {
+        from_utf8_lossy(self.as_bytes())
+    }
+
     /// Reduces the size of the string contained inside the ArrayString
     /// to the value provided by `len`. If a value higher than the size of the ArrayString
     /// is provided, no action is performed.
@@ -78,6 +135,18 @@ impl<const N: usize> PartialEq for ArrayCString<N> {
 
 impl<const N: usize> Eq for ArrayCString<N> {}
 
+impl<const N: usize> PartialEq<str> for ArrayCString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.matches(other)
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayCString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches(other)
+    }
+}
+
 /// SAFETY: The type is transparent over an array of `N` bytes, which is `Pod`.
 unsafe impl<const N: usize> Pod for ArrayCString<N> {}
 /// SAFETY: The type is transparent over an array of `N` bytes, which is `Zeroable`.
@@ -127,6 +196,86 @@ impl<const N: usize> ArrayWString<N> {
     pub fn matches_str(&self, text: &str) -> bool {
         self.as_slice().iter().copied().eq(text.encode_utf16())
     }
+
+    /// Checks whether the string matches the given text, ignoring the case of
+    /// ASCII letters. Characters that are not ASCII letters need to match
+    /// exactly. The text is re-encoded to UTF-16 on the fly.
+    pub fn matches_str_ignore_case(&self, text: &str) -> bool {
+        self.as_slice()
+            .iter()
+            .map(|&c| fold_ascii_case(c))
+            .eq(text.encode_utf16().map(fold_ascii_case))
+    }
+
+    /// Checks whether the string starts with the given text. The text is
+    /// re-encoded to UTF-16 on the fly.
+    pub fn starts_with_str(&self, text: &str) -> bool {
+        let mut chars = self.as_slice().iter();
+        text.encode_utf16().all(|c| chars.next() == Some(&c))
+    }
+
+    /// Checks whether the string ends with the given text. Only the
+    /// characters up until the nul-terminator are considered. The text is
+    /// re-encoded to UTF-16 on the fly.
+    pub fn ends_with_str(&self, text: &str) -> bool {
+        let chars = self.as_slice();
+        let len = text.encode_utf16().count();
+        len <= chars.len()
+            && chars[chars.len() - len..]
+                .iter()
+                .copied()
+                .eq(text.encode_utf16())
+    }
+
+    /// Checks whether the string contains the given text anywhere before the
+    /// nul-terminator. An empty text is always contained. The text is
+    /// re-encoded to UTF-16 on the fly.
+    pub fn contains_str(&self, text: &str) -> bool {
+        let len = text.encode_utf16().count();
+        len == 0
+            || self
+                .as_slice()
+                .windows(len)
+                .any(|w| w.iter().copied().eq(text.encode_utf16()))
+    }
+
+    /// Returns the 16-bit characters of the string up until (but excluding)
+    /// the nul-terminator, without any leading and trailing whitespace.
+    pub fn trimmed(&self) -> &[u16] {
+        let is_whitespace = |&c: &u16| char::from_u32(c.into()).is_some_and(char::is_whitespace);
+        let chars = self.as_slice();
+        let start = chars
+            .iter()
+            .position(|c| !is_whitespace(c))
+            .unwrap_or(chars.len());
+        let end = chars
+            .iter()
+            .rposition(|c| !is_whitespace(c))
+            .map_or(start, |i| i + 1);
+        &chars[start..end]
+    }
+
+    /// Decodes the characters of the string up until (but excluding) the
+    /// nul-terminator. Unpaired surrogates are replaced by
+    /// [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER).
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        char::decode_utf16(self.as_slice().iter().copied())
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Converts the string to UTF-8 with a capacity of `M` bytes. Unpaired
+    /// surrogates are replaced by
+    /// [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER). If the string
+    /// doesn't fit, it is truncated to the characters that fit.
+    pub fn to_array_string<const M: usize>(&self) -> ArrayString<M> {
+        let mut string = ArrayString::new();
+        for c in self.chars() {
+            if string.try_push(c).is_err() {
+                break;
+            }
+        }
+        string
+    }
 }
 
 impl<const N: usize> Default for ArrayWString<N> {
@@ -151,6 +300,18 @@ impl<const N: usize> PartialEq for ArrayWString<N> {
 
 impl<const N: usize> Eq for ArrayWString<N> {}
 
+impl<const N: usize> PartialEq<str> for ArrayWString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.matches_str(other)
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArrayWString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.matches_str(other)
+    }
+}
+
 /// SAFETY: The type is transparent over an array of `N` u16s, which is `Pod`.
 unsafe impl<const N: usize> Pod for ArrayWString<N> {}
 /// SAFETY: The type is transparent over an array of `N` u16s, which is `Zeroable`.
@@ -164,3 +325,257 @@ impl<const N: usize> FromEndian for ArrayWString<N> {
         Self(self.0.map(|x| x.from_le()))
     }
 }
+
+/// Converts bytes to a UTF-8 string with a capacity of `N` bytes. Invalid UTF-8
+/// sequences are replaced by
+/// [`REPLACEMENT_CHARACTER`](char::REPLACEMENT_CHARACTER). If the string
+/// doesn't fit, it is truncated to the characters that fit. This is useful for
+/// converting bytes read from a process.
+///
+/// # Example
+///
+/// ```
+/// # use asr::string::{from_utf8_lossy, ArrayString};
+/// let string: ArrayString<8> = from_utf8_lossy(b"Level\xFF1");
+/// assert_eq!(string.as_str(), "Level\u{FFFD}");
+/// ```
+pub fn from_utf8_lossy<const N: usize>(bytes: &[u8]) -> ArrayString<N> {
+    let mut string = ArrayString::new();
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if string.try_push(c).is_err() {
+                return string;
+            }
+        }
+        if !chunk.invalid().is_empty() && string.try_push(char::REPLACEMENT_CHARACTER).is_err() {
+            return string;
+        }
+    }
+    string
+}
+
+/// Splits a Windows command line into its arguments, following the rules of
+/// the Microsoft C runtime. This is useful for reading the arguments that a
+/// game was started with, such as the profile or the mods that were loaded.
+///
+/// The first argument is the path of the executable. Quotes toggle whether
+/// whitespace is part of it, and backslashes are taken literally. All other
+/// arguments are separated by spaces and tabs outside of quotes, and:
+///
+/// - Backslashes are taken literally, unless they are followed by a quote. In
+///   that case every pair of backslashes becomes a single backslash.
+/// - A quote that is preceded by an odd number of backslashes is taken
+///   literally. Any other quote toggles whether whitespace is part of the
+///   argument.
+/// - Within quotes, two consecutive quotes become a single literal quote.
+///
+/// # Example
+///
+/// ```
+/// # use asr::string::split_command_line;
+/// let args = split_command_line(r#""C:\Games\My Game.exe" -profile "Any%" a\\"b c"#)
+///     .collect::<Vec<_>>();
+/// assert_eq!(args, [r"C:\Games\My Game.exe", "-profile", "Any%", r"a\b c"]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn split_command_line(command_line: &str) -> CommandLineArgs<'_> {
+    CommandLineArgs {
+        chars: command_line.chars().peekable(),
+        is_first: true,
+    }
+}
+
+/// An iterator over the arguments of a Windows command line. Check
+/// [`split_command_line`] for more information.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct CommandLineArgs<'a> {
+    chars: iter::Peekable<str::Chars<'a>>,
+    is_first: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl CommandLineArgs<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|&c| c == ' ' || c == '\t').is_some() {}
+    }
+
+    /// Parses the path of the executable, which doesn't support escaping.
+    fn next_executable(&mut self) -> String {
+        let mut arg = String::new();
+        let mut in_quotes = false;
+        for c in &mut self.chars {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ' ' | '\t' if !in_quotes => break,
+                _ => arg.push(c),
+            }
+        }
+        arg
+    }
+
+    fn next_argument(&mut self) -> String {
+        let mut arg = String::new();
+        let mut in_quotes = false;
+        while let Some(c) = self.chars.next() {
+            match c {
+                ' ' | '\t' if !in_quotes => break,
+                '\\' => {
+                    let mut backslashes = 1;
+                    while self.chars.next_if_eq(&'\\').is_some() {
+                        backslashes += 1;
+                    }
+                    if self.chars.peek() == Some(&'"') {
+                        arg.extend(iter::repeat_n('\\', backslashes / 2));
+                        if backslashes % 2 == 1 {
+                            self.chars.next();
+                            arg.push('"');
+                        }
+                    } else {
+                        arg.extend(iter::repeat_n('\\', backslashes));
+                    }
+                }
+                '"' if in_quotes => {
+                    if self.chars.next_if_eq(&'"').is_some() {
+                        arg.push('"');
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+                '"' => in_quotes = true,
+                _ => arg.push(c),
+            }
+        }
+        arg
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Iterator for CommandLineArgs<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let arg = if self.is_first {
+            self.is_first = false;
+            self.chars.peek()?;
+            self.next_executable()
+        } else {
+            self.chars.peek()?;
+            self.next_argument()
+        };
+        self.skip_whitespace();
+        Some(arg)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FusedIterator for CommandLineArgs<'_> {}
+
+/// Converts an ASCII uppercase letter to lowercase, leaving all other 16-bit
+/// characters as they are.
+const fn fold_ascii_case(c: u16) -> u16 {
+    if c >= b'A' as u16 && c <= b'Z' as u16 {
+        c + (b'a' - b'A') as u16
+    } else {
+        c
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn split(command_line: &str) -> Vec<String> {
+        split_command_line(command_line).collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn executable_only() {
+        assert_eq!(split("game.exe"), ["game.exe"]);
+        assert_eq!(split("game.exe  \t "), ["game.exe"]);
+    }
+
+    #[test]
+    fn leading_whitespace_is_an_empty_executable() {
+        assert_eq!(split(" a b"), ["", "a", "b"]);
+    }
+
+    #[test]
+    fn executable_has_no_escaping() {
+        assert_eq!(
+            split(r#""C:\Program Files\game.exe" a"#),
+            [r"C:\Program Files\game.exe", "a"]
+        );
+        assert_eq!(
+            split(r#"C:\"My Games"\game.exe a"#),
+            [r"C:\My Games\game.exe", "a"]
+        );
+        assert_eq!(split(r#"C:\dir\" a"#), [r"C:\dir\ a"]);
+    }
+
+    #[test]
+    fn whitespace_separates_arguments() {
+        assert_eq!(split("game.exe a\tb  \t c"), ["game.exe", "a", "b", "c"]);
+    }
+
+    #[test]
+    fn quotes_group_whitespace() {
+        assert_eq!(
+            split(r#"game.exe "a b" c"d e"f"#),
+            ["game.exe", "a b", "cd ef"]
+        );
+    }
+
+    #[test]
+    fn empty_quotes_are_an_empty_argument() {
+        assert_eq!(split(r#"game.exe "" a """#), ["game.exe", "", "a", ""]);
+    }
+
+    #[test]
+    fn unterminated_quotes() {
+        assert_eq!(split(r#"game.exe "a b"#), ["game.exe", "a b"]);
+        assert_eq!(split(r#"game.exe a ""#), ["game.exe", "a", ""]);
+    }
+
+    #[test]
+    fn backslashes_without_quotes_are_literal() {
+        assert_eq!(
+            split(r"game.exe a\b a\\\b a\"),
+            ["game.exe", r"a\b", r"a\\\b", r"a\"]
+        );
+    }
+
+    #[test]
+    fn backslashes_before_quotes() {
+        // 2n backslashes become n backslashes and the quote toggles.
+        assert_eq!(split(r#"game.exe a\\"b c" d"#), ["game.exe", r"a\b c", "d"]);
+        // 2n + 1 backslashes become n backslashes and a literal quote.
+        assert_eq!(split(r#"game.exe a\"b c"#), ["game.exe", r#"a"b"#, "c"]);
+        assert_eq!(split(r#"game.exe a\\\"b c"#), ["game.exe", r#"a\"b"#, "c"]);
+        assert_eq!(split(r#"game.exe "a\\" b"#), ["game.exe", r"a\", "b"]);
+    }
+
+    #[test]
+    fn doubled_quotes_within_quotes() {
+        assert_eq!(split(r#"game.exe "a""b" c"#), ["game.exe", r#"a"b"#, "c"]);
+        assert_eq!(split(r#"game.exe "a"""#), ["game.exe", r#"a""#]);
+        assert_eq!(split(r#"game.exe """" b"#), ["game.exe", r#"""#, "b"]);
+        // Outside of quotes, two quotes open and close an empty quotation.
+        assert_eq!(split(r#"game.exe a""b"#), ["game.exe", "ab"]);
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(
+            split("spiel.exe \"Größe 2\" ö"),
+            ["spiel.exe", "Größe 2", "ö"]
+        );
+    }
+}
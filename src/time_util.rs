@@ -1,5 +1,10 @@
 //! This module provides utilities for creating durations.
 
+use core::{
+    sync::atomic::{self, AtomicU64},
+    time::Duration,
+};
+
 /// From a frame count and a fixed frame rate, returns an accurate duration.
 pub fn frame_count<const FRAME_RATE: u64>(frame_count: u64) -> time::Duration {
     let secs = frame_count / FRAME_RATE;
@@ -7,9 +12,268 @@ pub fn frame_count<const FRAME_RATE: u64>(frame_count: u64) -> time::Duration {
     time::Duration::new(secs as _, nanos as _)
 }
 
+/// Parses a duration that is formatted as text, such as an in-game time that a
+/// game only stores as a string. The following formats are supported, where
+/// the fractional part is optional, may have up to 9 digits and may be
+/// separated by either a `.` or a `,`:
+///
+/// - `SS.fff`
+/// - `MM:SS.fff`
+/// - `H:MM:SS.fff`
+///
+/// The duration may be prefixed with a `+` or `-` sign. The leading component
+/// may have any number of digits, but the minutes and seconds that follow it
+/// need to have two digits and be less than 60. Otherwise [`None`] is
+/// returned, as this usually indicates that the text isn't a valid time.
+///
+/// # Example
+///
+/// ```
+/// # use asr::{time::Duration, time_util::parse_duration};
+/// assert_eq!(parse_duration("1:02:03.5"), Some(Duration::milliseconds(3_723_500)));
+/// assert_eq!(parse_duration("-0:01,25"), Some(Duration::milliseconds(-1250)));
+/// assert_eq!(parse_duration("1:75"), None);
+/// ```
+pub fn parse_duration(text: &str) -> Option<time::Duration> {
+    let text = text.trim();
+    let (is_negative, text) = match text.as_bytes().first()? {
+        b'-' => (true, &text[1..]),
+        b'+' => (false, &text[1..]),
+        _ => (false, text),
+    };
+
+    let (whole, fraction) = match text.find(['.', ',']) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+
+    let nanos = match fraction {
+        Some(fraction) => {
+            if fraction.is_empty() || fraction.len() > 9 {
+                return None;
+            }
+            let digits = parse_digits(fraction)?;
+            digits * 10u64.pow(9 - fraction.len() as u32)
+        }
+        None => 0,
+    };
+
+    let mut secs = 0u64;
+    for (index, component) in whole.split(':').enumerate() {
+        if index >= 3 {
+            return None;
+        }
+        let value = parse_digits(component)?;
+        if index != 0 && (component.len() != 2 || value >= 60) {
+            return None;
+        }
+        secs = secs.checked_mul(60)?.checked_add(value)?;
+    }
+
+    let duration = time::Duration::new(i64::try_from(secs).ok()?, nanos as i32);
+    Some(if is_negative { -duration } else { duration })
+}
+
+fn parse_digits(text: &str) -> Option<u64> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Formats a duration as text in the form of `H:MM:SS.fff`, or `M:SS.fff` if
+/// it is shorter than an hour. The number of decimals is given by `decimals`,
+/// which is limited to 9. The fractional part is truncated, not rounded. The
+/// text is written into the writer provided, so this can be used without an
+/// allocator, for example with an [`ArrayString`](arrayvec::ArrayString). The
+/// text can be parsed again with [`parse_duration`].
+///
+/// # Example
+///
+/// ```
+/// # use asr::{arrayvec::ArrayString, time_util};
+/// let mut text = ArrayString::<32>::new();
+/// time_util::format_duration(&mut text, asr::time::Duration::seconds(3723), 2).unwrap();
+/// assert_eq!(text.as_str(), "1:02:03.00");
+/// ```
+pub fn format_duration(
+    f: &mut impl core::fmt::Write,
+    duration: time::Duration,
+    decimals: u8,
+) -> core::fmt::Result {
+    if duration.is_negative() {
+        f.write_char('-')?;
+    }
+    let total_secs = duration.whole_seconds().unsigned_abs();
+    let (hours, minutes, secs) = (total_secs / 3600, total_secs / 60 % 60, total_secs % 60);
+    if hours != 0 {
+        write!(f, "{hours}:{minutes:02}:{secs:02}")?;
+    } else {
+        write!(f, "{minutes}:{secs:02}")?;
+    }
+    let decimals = decimals.min(9) as u32;
+    if decimals != 0 {
+        let fraction = duration.subsec_nanoseconds().unsigned_abs() / 10u32.pow(9 - decimals);
+        write!(f, ".{fraction:0width$}", width = decimals as usize)?;
+    }
+    Ok(())
+}
+
+/// Accumulates the time of frames at a frame rate that is not necessarily a
+/// whole number, such as the 59.94 frames per second of NTSC, which is
+/// 60000/1001. The time is calculated from the total number of frames with
+/// integer math, so there is no drift no matter how many frames are added.
+/// The frame rate can be changed at any point, which preserves the time that
+/// was accumulated so far.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::time_util::FrameTimer;
+/// let mut timer = FrameTimer::new(60000, 1001);
+/// timer.advance(60);
+/// // The game switches to 30 frames per second.
+/// timer.set_rate(30, 1);
+/// timer.advance(30);
+/// let game_time = timer.duration();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrameTimer {
+    numerator: u64,
+    denominator: u64,
+    frames: u64,
+    base: time::Duration,
+}
+
+impl FrameTimer {
+    /// Creates a new timer with a frame rate of `numerator / denominator`
+    /// frames per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numerator or denominator is 0.
+    pub const fn new(numerator: u64, denominator: u64) -> Self {
+        assert!(numerator != 0 && denominator != 0);
+        Self {
+            numerator,
+            denominator,
+            frames: 0,
+            base: time::Duration::ZERO,
+        }
+    }
+
+    /// Adds the given number of frames at the current frame rate.
+    #[inline]
+    pub const fn advance(&mut self, frames: u64) {
+        self.frames = self.frames.saturating_add(frames);
+    }
+
+    /// Changes the frame rate to `numerator / denominator` frames per second.
+    /// The time accumulated so far is kept, only frames added afterwards use
+    /// the new frame rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numerator or denominator is 0.
+    pub const fn set_rate(&mut self, numerator: u64, denominator: u64) {
+        assert!(numerator != 0 && denominator != 0);
+        self.base = self.duration();
+        self.frames = 0;
+        self.numerator = numerator;
+        self.denominator = denominator;
+    }
+
+    /// Resets the accumulated time to zero while keeping the frame rate.
+    #[inline]
+    pub const fn reset(&mut self) {
+        self.frames = 0;
+        self.base = time::Duration::ZERO;
+    }
+
+    /// Returns the accumulated time, rounded down to whole nanoseconds.
+    pub const fn duration(&self) -> time::Duration {
+        let nanos =
+            self.frames as u128 * self.denominator as u128 * 1_000_000_000 / self.numerator as u128;
+        let frames = time::Duration::new(
+            (nanos / 1_000_000_000) as i64,
+            (nanos % 1_000_000_000) as i32,
+        );
+        self.base.saturating_add(frames)
+    }
+}
+
+/// The nanoseconds that were estimated to have passed by
+/// [`record_tick`].
+static ESTIMATED_UPTIME: AtomicU64 = AtomicU64::new(0);
+
+/// The source of the time returned by [`uptime`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ClockSource {
+    /// The monotonic clock of the runtime. This is only available when
+    /// targeting WASI.
+    Monotonic,
+    /// An estimate based on the amount of ticks that passed and the tick rate
+    /// at each of them. This is used when the monotonic clock is not
+    /// available. Check [`record_tick`] for more information.
+    TickEstimate,
+}
+
+/// Returns the source of the time returned by [`uptime`].
+pub const fn clock_source() -> ClockSource {
+    if cfg!(target_os = "wasi") {
+        ClockSource::Monotonic
+    } else {
+        ClockSource::TickEstimate
+    }
+}
+
+/// Returns the time that passed since an unspecified point in time. The time
+/// never decreases, so it is suitable for measuring how much time passed
+/// between two points, even if the tick rate changes in between. Check
+/// [`clock_source`] for how accurate the time is.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::time_util;
+/// # use core::time::Duration;
+/// # fn example(menu_opened_at: Duration) {
+/// if time_util::uptime().saturating_sub(menu_opened_at) > Duration::from_secs(2) {
+///     // TODO: The menu has been open for more than 2 seconds.
+/// }
+/// # }
+/// ```
+pub fn uptime() -> Duration {
+    #[cfg(target_os = "wasi")]
+    {
+        Duration::from_nanos(Instant::now().0)
+    }
+    #[cfg(not(target_os = "wasi"))]
+    {
+        Duration::from_nanos(ESTIMATED_UPTIME.load(atomic::Ordering::Relaxed))
+    }
+}
+
+/// Advances the estimate of [`uptime`] by a single tick at the current
+/// [tick rate](crate::tick_rate). This is called automatically on every tick by
+/// [`async_main`](crate::async_main). If you define your own `update`
+/// function and need the estimate, you need to call this at the start of it.
+/// The estimate is not used if the monotonic clock is available.
+pub fn record_tick() {
+    let tick = Duration::try_from_secs_f64(1.0 / crate::tick_rate()).map_or(u64::MAX, |tick| {
+        tick.as_nanos().try_into().unwrap_or(u64::MAX)
+    });
+    let uptime = ESTIMATED_UPTIME.load(atomic::Ordering::Relaxed);
+    ESTIMATED_UPTIME.store(uptime.saturating_add(tick), atomic::Ordering::Relaxed);
+}
+
 #[cfg(target_os = "wasi")]
 mod instant {
-    use core::{mem::MaybeUninit, ops::Add, time::Duration};
+    use core::{
+        mem::MaybeUninit,
+        ops::{Add, Sub},
+        time::Duration,
+    };
 
     use wasi::Timestamp;
 
@@ -68,6 +332,198 @@ mod instant {
             Self(self.0 + rhs.as_nanos() as u64)
         }
     }
+
+    impl Sub for Instant {
+        type Output = Duration;
+
+        /// Returns the amount of time elapsed from another instant to this
+        /// one, or zero duration if that instant is later than this one.
+        fn sub(self, rhs: Self) -> Self::Output {
+            self.duration_since(rhs)
+        }
+    }
+
+    impl Sub<Duration> for Instant {
+        type Output = Self;
+
+        /// Returns the instant the given duration before this one. This
+        /// saturates at the earliest representable instant.
+        fn sub(self, rhs: Duration) -> Self::Output {
+            Self(
+                self.0
+                    .saturating_sub(rhs.as_nanos().try_into().unwrap_or(u64::MAX)),
+            )
+        }
+    }
 }
 #[cfg(target_os = "wasi")]
 pub use self::instant::Instant;
+
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+
+    use super::*;
+
+    fn parse(text: &str) -> Option<i128> {
+        parse_duration(text).map(|d| d.whole_milliseconds())
+    }
+
+    fn format<const N: usize>(duration: time::Duration, decimals: u8) -> ArrayString<N> {
+        let mut text = ArrayString::new();
+        format_duration(&mut text, duration, decimals).unwrap();
+        text
+    }
+
+    #[test]
+    fn parse_duration_formats() {
+        assert_eq!(parse("5"), Some(5_000));
+        assert_eq!(parse("75.5"), Some(75_500));
+        assert_eq!(parse("1:05"), Some(65_000));
+        assert_eq!(parse("12:34.567"), Some(754_567));
+        assert_eq!(parse("1:02:03"), Some(3_723_000));
+        assert_eq!(parse("100:00:00"), Some(360_000_000));
+        assert_eq!(parse("  1:05\n"), Some(65_000));
+        assert_eq!(
+            parse_duration("0.123456789").unwrap().whole_nanoseconds(),
+            123_456_789,
+        );
+    }
+
+    #[test]
+    fn parse_duration_sign() {
+        assert_eq!(parse("+1:05"), Some(65_000));
+        assert_eq!(parse("-1:05.5"), Some(-65_500));
+        assert_eq!(parse("-0.25"), Some(-250));
+        assert_eq!(parse("-"), None);
+        assert_eq!(parse("+"), None);
+        assert_eq!(parse("--1"), None);
+        assert_eq!(parse("- 1"), None);
+    }
+
+    #[test]
+    fn parse_duration_comma_separator() {
+        assert_eq!(parse("1:05,25"), Some(65_250));
+        assert_eq!(parse("-3,5"), Some(-3_500));
+        assert_eq!(parse("1,2,3"), None);
+        assert_eq!(parse("1.2,3"), None);
+    }
+
+    #[test]
+    fn parse_duration_rejects_invalid_fields() {
+        assert_eq!(parse("1:60"), None);
+        assert_eq!(parse("1:99.5"), None);
+        assert_eq!(parse("1:60:00"), None);
+        assert_eq!(parse("1:00:60"), None);
+        assert_eq!(parse("1:5"), None);
+        assert_eq!(parse("1:005"), None);
+        assert_eq!(parse("1:02:03:04"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse(":05"), None);
+        assert_eq!(parse("1:"), None);
+        assert_eq!(parse("1."), None);
+        assert_eq!(parse(".5"), None);
+        assert_eq!(parse("1.1234567890"), None);
+        assert_eq!(parse("1.5s"), None);
+        assert_eq!(parse("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn format_duration_formats() {
+        assert_eq!(format::<16>(time::Duration::seconds(5), 0).as_str(), "0:05");
+        assert_eq!(
+            format::<16>(time::Duration::milliseconds(754_567), 3).as_str(),
+            "12:34.567"
+        );
+        assert_eq!(
+            format::<16>(time::Duration::milliseconds(3_723_999), 2).as_str(),
+            "1:02:03.99"
+        );
+        assert_eq!(
+            format::<16>(time::Duration::milliseconds(-65_500), 1).as_str(),
+            "-1:05.5"
+        );
+        assert_eq!(
+            format::<32>(time::Duration::nanoseconds(1), 12).as_str(),
+            "0:00.000000001"
+        );
+    }
+
+    #[test]
+    fn parse_format_round_trip() {
+        for nanos in [
+            0,
+            1,
+            999_999_999,
+            59_999_999_999,
+            60_000_000_000,
+            3_599_999_999_999,
+            3_600_000_000_000,
+            123_456_789_012_345,
+            -1,
+            -3_723_500_000_000,
+        ] {
+            let duration = time::Duration::nanoseconds(nanos);
+            let text = format::<32>(duration, 9);
+            assert_eq!(parse_duration(&text), Some(duration), "{text}");
+        }
+    }
+
+    #[test]
+    fn frame_timer_no_drift_over_ten_hours() {
+        // 60000 frames at 60000/1001 frames per second take exactly 1001
+        // seconds, so 36 times that is a little over 10 hours.
+        let mut timer = FrameTimer::new(60000, 1001);
+        for _ in 0..36 * 60000 {
+            timer.advance(1);
+        }
+        assert_eq!(timer.duration(), time::Duration::seconds(36 * 1001));
+
+        // In between the time is the exact time, rounded down.
+        timer.reset();
+        let frames = 10 * 60 * 60 * 60000 / 1001;
+        timer.advance(frames);
+        let nanos = frames as i128 * 1001 * 1_000_000_000 / 60000;
+        assert_eq!(timer.duration().whole_nanoseconds(), nanos);
+    }
+
+    #[test]
+    fn frame_timer_set_rate_mid_run() {
+        let mut timer = FrameTimer::new(60000, 1001);
+        timer.advance(60);
+        assert_eq!(timer.duration(), time::Duration::milliseconds(1001));
+
+        timer.set_rate(30, 1);
+        assert_eq!(timer.duration(), time::Duration::milliseconds(1001));
+        timer.advance(30);
+        assert_eq!(timer.duration(), time::Duration::milliseconds(2001));
+
+        timer.set_rate(60000, 1001);
+        timer.advance(60000);
+        assert_eq!(
+            timer.duration(),
+            time::Duration::milliseconds(2001) + time::Duration::seconds(1001),
+        );
+    }
+
+    #[test]
+    fn frame_timer_set_rate_keeps_rounded_time() {
+        let mut timer = FrameTimer::new(60000, 1001);
+        timer.advance(1);
+        assert_eq!(timer.duration().whole_nanoseconds(), 16_683_333);
+        timer.set_rate(1, 1);
+        timer.advance(1);
+        assert_eq!(timer.duration().whole_nanoseconds(), 1_016_683_333);
+    }
+
+    #[test]
+    fn frame_timer_reset_keeps_rate() {
+        let mut timer = FrameTimer::new(60000, 1001);
+        timer.advance(100);
+        timer.set_rate(30, 1);
+        timer.reset();
+        assert_eq!(timer.duration(), time::Duration::ZERO);
+        timer.advance(30);
+        assert_eq!(timer.duration(), time::Duration::SECOND);
+    }
+}
@@ -2,7 +2,10 @@
 
 use core::{mem, ops};
 
-use bytemuck::{bytes_of, NoUninit};
+use arrayvec::ArrayVec;
+use bytemuck::{bytes_of, CheckedBitPattern, NoUninit};
+
+use crate::{deep_pointer::DeepPointer, memory_reader::MemoryReader};
 
 /// A watcher keeps a pair of values and allows you to track changes between
 /// them.
@@ -29,7 +32,9 @@ impl<T> Watcher<T> {
 
 impl<T: Clone> Watcher<T> {
     /// Updates the watcher with a new value. Returns the pair if the value
-    /// provided is not [`None`].
+    /// provided is not [`None`]. If the value is [`None`], the pair is cleared,
+    /// so the next value is considered unchanged. Use
+    /// [`update_with`](Self::update_with) to keep the pair instead.
     pub fn update(&mut self, value: Option<T>) -> Option<&Pair<T>> {
         match (&mut self.pair, value) {
             (None, Some(value)) => {
@@ -60,6 +65,311 @@ impl<T: Clone> Watcher<T> {
         }
         self.pair.as_ref().unwrap()
     }
+
+    /// Updates the watcher with the value returned by the closure. Unlike
+    /// [`update`](Self::update), the pair is kept as is if the closure returns
+    /// [`None`], such as when reading the value failed. Returns the pair if
+    /// the closure returned a value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::{watcher::Watcher, Address, Process};
+    /// # fn example(process: &Process, watcher: &mut Watcher<u32>, address: Address) {
+    /// if let Some(pair) = watcher.update_with(|| process.read(address).ok()) {
+    ///     // TODO: Do something with the pair.
+    /// }
+    /// # }
+    /// ```
+    pub fn update_with(&mut self, f: impl FnOnce() -> Option<T>) -> Option<&Pair<T>> {
+        let value = f()?;
+        Some(self.update_infallible(value))
+    }
+}
+
+impl<T: Clone + PartialEq> Watcher<T> {
+    /// Updates the watcher with a new value that always exists, like
+    /// [`update_infallible`](Self::update_infallible). The value is only
+    /// cloned if it differs from the values in the pair, which avoids copying
+    /// large values, such as strings, that rarely change. The pair is then
+    /// returned.
+    pub fn update_infallible_ref(&mut self, value: &T) -> &Pair<T> {
+        match &mut self.pair {
+            None => {
+                self.pair = Some(Pair {
+                    old: value.clone(),
+                    current: value.clone(),
+                });
+            }
+            Some(pair) => {
+                if pair.current == *value {
+                    if pair.old != pair.current {
+                        pair.old.clone_from(&pair.current);
+                    }
+                } else {
+                    mem::swap(&mut pair.old, &mut pair.current);
+                    pair.current.clone_from(value);
+                }
+            }
+        }
+        self.pair.as_ref().unwrap()
+    }
+}
+
+/// A watcher for a value that is read through a [`DeepPointer`]. Dereferencing
+/// the pointer path, reading the value and updating the pair happens in a
+/// single call to [`update`](Self::update).
+///
+/// Unlike [`Watcher::update`] with [`None`], a failed read doesn't clear the
+/// pair. The previous pair stays accessible through [`pair`](Self::pair), so
+/// transient read failures, such as during load screens, don't cause spurious
+/// changes. The checks for changes, such as [`changed`](Self::changed), only
+/// report changes observed by the latest update, so a change isn't reported
+/// twice if the update after it fails.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::{deep_pointer::DeepPointer, watcher::DeepPointerWatcher, Process};
+/// # fn example(process: &Process, level: &mut DeepPointerWatcher<u32, 2>) {
+/// if level.update(process).is_some() && level.changed_from_to(&1, &2) {
+///     asr::timer::split();
+/// }
+/// # }
+/// ```
+#[derive(Copy, Clone)]
+pub struct DeepPointerWatcher<T, const CAP: usize> {
+    pointer: DeepPointer<CAP>,
+    watcher: Watcher<T>,
+    updated: bool,
+}
+
+impl<T, const CAP: usize> DeepPointerWatcher<T, CAP> {
+    /// Creates a new watcher for the value that the pointer path points to.
+    #[inline]
+    pub const fn new(pointer: DeepPointer<CAP>) -> Self {
+        Self {
+            pointer,
+            watcher: Watcher::new(),
+            updated: false,
+        }
+    }
+
+    /// Returns the pointer path of the value.
+    #[inline]
+    pub const fn pointer(&self) -> &DeepPointer<CAP> {
+        &self.pointer
+    }
+
+    /// Returns the pair of the most recent successful update. This is still
+    /// available if the latest update failed.
+    #[inline]
+    pub const fn pair(&self) -> Option<&Pair<T>> {
+        self.watcher.pair.as_ref()
+    }
+
+    fn updated_pair(&self) -> Option<&Pair<T>> {
+        self.pair().filter(|_| self.updated)
+    }
+}
+
+impl<T: CheckedBitPattern + Clone, const CAP: usize> DeepPointerWatcher<T, CAP> {
+    /// Reads the value through the pointer path and updates the pair. Returns
+    /// [`None`] if the value couldn't be read, in which case the pair is left
+    /// unchanged.
+    pub fn update(&mut self, process: &impl MemoryReader) -> Option<&Pair<T>> {
+        self.updated = false;
+        let value = self.pointer.deref(process).ok()?;
+        self.updated = true;
+        Some(self.watcher.update_infallible(value))
+    }
+}
+
+impl<T: Eq, const CAP: usize> DeepPointerWatcher<T, CAP> {
+    /// Checks if the value changed. Check [`Pair::changed`] for more
+    /// information.
+    #[inline]
+    pub fn changed(&self) -> bool {
+        self.updated_pair().is_some_and(Pair::changed)
+    }
+
+    /// Checks if the value changed to a specific value that it was not before.
+    /// Check [`Pair::changed_to`] for more information.
+    #[inline]
+    pub fn changed_to(&self, value: &T) -> bool {
+        self.updated_pair().is_some_and(|p| p.changed_to(value))
+    }
+
+    /// Checks if the value changed from a specific value to another specific
+    /// value. Check [`Pair::changed_from_to`] for more information.
+    #[inline]
+    pub fn changed_from_to(&self, old: &T, current: &T) -> bool {
+        self.updated_pair()
+            .is_some_and(|p| p.changed_from_to(old, current))
+    }
+}
+
+/// A watcher that keeps the last `N` values it was updated with. This allows
+/// detecting patterns across multiple ticks, such as a flag that is only set
+/// for a single frame. The values are stored in a ring buffer, so updating the
+/// history doesn't move any of the values.
+///
+/// # Example
+///
+/// ```no_run
+/// # use asr::watcher::HistoryWatcher;
+/// # fn example(flag: &mut HistoryWatcher<bool, 10>, value: bool) {
+/// flag.update(value);
+/// if flag.any_window(3, |w| w == [false, true, false]) {
+///     asr::timer::split();
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct HistoryWatcher<T, const N: usize> {
+    values: ArrayVec<T, N>,
+    /// The index of the oldest value once the history is full. This is where
+    /// the next value is stored.
+    next: usize,
+}
+
+impl<T, const N: usize> Default for HistoryWatcher<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> HistoryWatcher<T, N> {
+    /// Creates a new empty history.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            values: ArrayVec::new_const(),
+            next: 0,
+        }
+    }
+
+    /// Adds a value to the history. If the history is full, the oldest value
+    /// is replaced. Values are added even if they are equal to the latest
+    /// value, so the position of a value in the history corresponds to the
+    /// number of updates since then.
+    pub fn update(&mut self, value: T) {
+        if N == 0 {
+            return;
+        }
+        if self.values.is_full() {
+            self.values[self.next] = value;
+            self.next = (self.next + 1) % N;
+        } else {
+            self.values.push(value);
+        }
+    }
+
+    /// Removes all values from the history.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.next = 0;
+    }
+
+    /// Returns the number of values in the history.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if the history is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the values in the history as two slices, which together are
+    /// ordered from the oldest to the latest value.
+    #[inline]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (newer, older) = self.values.split_at(self.next);
+        (older, newer)
+    }
+
+    /// Iterates over the values in the history, from the oldest to the latest
+    /// value.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (older, newer) = self.as_slices();
+        older.iter().chain(newer)
+    }
+
+    /// Returns the latest value.
+    #[inline]
+    pub fn latest(&self) -> Option<&T> {
+        match self.next {
+            0 => self.values.last(),
+            next => self.values.get(next - 1),
+        }
+    }
+
+    /// Returns the oldest value that is still in the history.
+    #[inline]
+    pub fn oldest(&self) -> Option<&T> {
+        self.values.get(self.next)
+    }
+
+    /// Returns the number of updates since a value satisfied the condition.
+    /// If the latest value satisfies it, this is 0. Returns [`None`] if no
+    /// value in the history satisfies the condition.
+    pub fn ticks_since(&self, f: impl FnMut(&T) -> bool) -> Option<usize> {
+        self.iter().rev().position(f)
+    }
+}
+
+impl<T: Clone, const N: usize> HistoryWatcher<T, N> {
+    /// Checks if any consecutive values of the given length satisfy the
+    /// condition. The values are passed from the oldest to the latest. Only
+    /// the windows that wrap around the end of the ring buffer need their
+    /// values to be cloned.
+    pub fn any_window(&self, len: usize, mut f: impl FnMut(&[T]) -> bool) -> bool {
+        if len == 0 || len > self.values.len() {
+            return false;
+        }
+        let (older, newer) = self.as_slices();
+        if older.windows(len).any(&mut f) {
+            return true;
+        }
+
+        // The windows that contain values of both slices are formed by up to
+        // `len - 1` values from the end of the older slice and from the start
+        // of the newer slice.
+        if !older.is_empty() && !newer.is_empty() {
+            let wrapping: ArrayVec<T, N> = older[older.len().saturating_sub(len - 1)..]
+                .iter()
+                .chain(&newer[..newer.len().min(len - 1)])
+                .cloned()
+                .collect();
+            if wrapping.windows(len).any(&mut f) {
+                return true;
+            }
+        }
+
+        newer.windows(len).any(f)
+    }
+}
+
+impl<T: PartialEq, const N: usize> HistoryWatcher<T, N> {
+    /// Adds a value to the history, unless it is equal to the latest value.
+    /// The history then only contains the distinct values that were observed,
+    /// so the number of updates in between is not tracked.
+    pub fn update_dedup(&mut self, value: T) {
+        if self.latest() != Some(&value) {
+            self.update(value);
+        }
+    }
+
+    /// Checks if the history contains the value.
+    #[inline]
+    pub fn contains(&self, value: &T) -> bool {
+        self.values.contains(value)
+    }
 }
 
 /// A pair consisting of an old and a current value that can be used for
@@ -88,6 +398,24 @@ impl<T> Pair<T> {
         !f(&self.old) && f(&self.current)
     }
 
+    /// Checks if the old and the current value satisfy a condition. This is
+    /// useful for comparisons that the other methods don't cover.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::watcher::Pair;
+    /// # fn example(room: Pair<u32>) {
+    /// if room.changed_by(|old, current| old / 100 != current / 100) {
+    ///     asr::timer::split();
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn changed_by(&self, f: impl FnOnce(&T, &T) -> bool) -> bool {
+        f(&self.old, &self.current)
+    }
+
     /// Maps the pair to a new pair with a different type.
     pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Pair<U> {
         Pair {
@@ -119,6 +447,17 @@ impl<T: Eq> Pair<T> {
     }
 
     /// Checks if the value changed from a specific value to another specific value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use asr::watcher::Pair;
+    /// # fn example(igt: Pair<u32>) {
+    /// if igt.changed_from_to(&0, &1) {
+    ///     asr::timer::start();
+    /// }
+    /// # }
+    /// ```
     pub fn changed_from_to(&self, old: &T, current: &T) -> bool {
         &self.old == old && &self.current == current
     }
@@ -165,3 +504,162 @@ impl<T: PartialOrd> Pair<T> {
         self.old > self.current
     }
 }
+
+macro_rules! impl_increased_by {
+    ($($ty:ty),*) => {$(
+        impl Pair<$ty> {
+            /// Checks if the value increased by exactly the amount given. This
+            /// is useful for counters, such as frame counters. The difference
+            /// is calculated with wrapping arithmetic, so a counter that
+            /// overflows is still considered to have increased.
+            #[inline]
+            pub const fn increased_by(&self, delta: $ty) -> bool {
+                self.current.wrapping_sub(self.old) == delta
+            }
+        }
+    )*};
+}
+
+impl_increased_by!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history<const N: usize>(values: impl IntoIterator<Item = u32>) -> HistoryWatcher<u32, N> {
+        let mut history = HistoryWatcher::new();
+        for value in values {
+            history.update(value);
+        }
+        history
+    }
+
+    fn collect<const N: usize>(history: &HistoryWatcher<u32, N>) -> ArrayVec<u32, N> {
+        history.iter().copied().collect()
+    }
+
+    #[test]
+    fn history_before_wraparound() {
+        let history = history::<4>([1, 2, 3]);
+        assert_eq!(collect(&history).as_slice(), [1, 2, 3]);
+        assert_eq!(history.as_slices(), (&[1, 2, 3][..], &[][..]));
+        assert_eq!(history.oldest(), Some(&1));
+        assert_eq!(history.latest(), Some(&3));
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn history_wraparound() {
+        let history = history::<4>(1..=6);
+        assert_eq!(collect(&history).as_slice(), [3, 4, 5, 6]);
+        assert_eq!(history.as_slices(), (&[3, 4][..], &[5, 6][..]));
+        assert_eq!(history.oldest(), Some(&3));
+        assert_eq!(history.latest(), Some(&6));
+        assert_eq!(history.len(), 4);
+        assert!(history.contains(&3));
+        assert!(!history.contains(&2));
+    }
+
+    #[test]
+    fn history_full_cycle() {
+        let history = history::<4>(1..=8);
+        assert_eq!(collect(&history).as_slice(), [5, 6, 7, 8]);
+        assert_eq!(history.as_slices(), (&[5, 6, 7, 8][..], &[][..]));
+        assert_eq!(history.oldest(), Some(&5));
+        assert_eq!(history.latest(), Some(&8));
+    }
+
+    #[test]
+    fn history_ticks_since_across_wraparound() {
+        let history = history::<4>(1..=6);
+        assert_eq!(history.ticks_since(|&v| v == 6), Some(0));
+        assert_eq!(history.ticks_since(|&v| v == 5), Some(1));
+        assert_eq!(history.ticks_since(|&v| v == 4), Some(2));
+        assert_eq!(history.ticks_since(|&v| v == 3), Some(3));
+        assert_eq!(history.ticks_since(|&v| v == 2), None);
+    }
+
+    #[test]
+    fn history_windows_across_wraparound() {
+        let history = history::<4>(1..=6);
+        let mut windows = ArrayVec::<[u32; 3], 4>::new();
+        assert!(!history.any_window(3, |w| {
+            windows.push(w.try_into().unwrap());
+            false
+        }));
+        assert_eq!(windows.as_slice(), [[3, 4, 5], [4, 5, 6]]);
+
+        assert!(history.any_window(2, |w| w == [4, 5]));
+        assert!(history.any_window(4, |w| w == [3, 4, 5, 6]));
+        assert!(!history.any_window(5, |_| true));
+        assert!(!history.any_window(0, |_| true));
+    }
+
+    #[test]
+    fn history_flag_flicker() {
+        let mut history = HistoryWatcher::<bool, 3>::new();
+        for value in [false, false, false, true, false] {
+            history.update(value);
+        }
+        assert!(history.any_window(3, |w| w == [false, true, false]));
+        history.update(false);
+        assert!(!history.any_window(3, |w| w == [false, true, false]));
+    }
+
+    #[test]
+    fn history_dedup() {
+        let mut history = HistoryWatcher::<u32, 3>::new();
+        for value in [1, 1, 2, 2, 2, 3, 4, 4] {
+            history.update_dedup(value);
+        }
+        assert_eq!(collect(&history).as_slice(), [2, 3, 4]);
+    }
+
+    #[test]
+    fn history_clear() {
+        let mut history = history::<3>(1..=5);
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(history.latest(), None);
+        history.update(7);
+        assert_eq!(collect(&history).as_slice(), [7]);
+        assert_eq!(history.oldest(), Some(&7));
+    }
+
+    #[test]
+    fn history_empty_capacity() {
+        let history = history::<0>(1..=3);
+        assert!(history.is_empty());
+        assert_eq!(history.latest(), None);
+        assert_eq!(history.oldest(), None);
+    }
+
+    #[test]
+    fn increased_by_wraps() {
+        assert!(Pair {
+            old: 5u8,
+            current: 6
+        }
+        .increased_by(1));
+        assert!(Pair {
+            old: u8::MAX,
+            current: 0
+        }
+        .increased_by(1));
+        assert!(Pair {
+            old: i32::MAX,
+            current: i32::MIN
+        }
+        .increased_by(1));
+        assert!(!Pair {
+            old: 6u32,
+            current: 5
+        }
+        .increased_by(1));
+        assert!(Pair {
+            old: 10i64,
+            current: 7
+        }
+        .increased_by(-3));
+    }
+}